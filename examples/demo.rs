@@ -1,5 +1,5 @@
 use autoswap_rust_sdk::types::connector::{
-    Amount, AutoSwapprConfig, PoolKey, SwapData, SwapParameters, Uint256,
+    AccountType, Amount, AutoSwapprConfig, FeeToken, PoolKey, SwapData, SwapParameters, Uint256,
 };
 
 #[tokio::main]
@@ -13,11 +13,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = AutoSwapprConfig {
         contract_address: "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b"
             .to_string(),
-        rpc_url: "https://starknet-mainnet.public.blastapi.io/rpc/v0_7".to_string(),
-        account_address: "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
+        rpc_url: "https://starknet-mainnet.public.blastapi.io/rpc/v0_9".to_string(),
+        account_address: "0x0234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
             .to_string(),
         private_key: "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
             .to_string(),
+        account_type: AccountType::Auto,
+        fee_token: FeeToken::Strk,
     };
 
     println!("📋 Configuration created:");
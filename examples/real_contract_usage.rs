@@ -20,7 +20,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Get configuration from environment variables
     let rpc_url = env::var("RPC_URL")
-        .unwrap_or_else(|_| "https://starknet-mainnet.public.blastapi.io/rpc/v0_7".to_string());
+        .unwrap_or_else(|_| "https://starknet-mainnet.public.blastapi.io/rpc/v0_9".to_string());
     let private_key =
         env::var("PRIVATE_KEY").expect("PRIVATE_KEY environment variable is required");
     let account_address =
@@ -46,20 +46,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Create contract instances
-    let autoswappr_contract = AutoSwapprContract::new(
-        addresses::mainnet::autoswappr(),
-        std::sync::Arc::new(provider.clone()),
-    );
+    let autoswappr_contract = AutoSwapprContract::new(addresses::mainnet::autoswappr());
 
-    let eth_contract = Erc20Contract::new(
-        addresses::mainnet::eth(),
-        std::sync::Arc::new(provider.clone()),
-    );
+    let eth_contract = Erc20Contract::new(addresses::mainnet::eth());
 
-    let strk_contract = Erc20Contract::new(
-        addresses::mainnet::strk(),
-        std::sync::Arc::new(provider.clone()),
-    );
+    let strk_contract = Erc20Contract::new(addresses::mainnet::strk());
 
     println!("\n📋 Contract Addresses:");
     println!("AutoSwappr: {}", autoswappr_contract.address());
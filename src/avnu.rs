@@ -0,0 +1,235 @@
+// AVNU off-chain quote API integration
+// Fetches optimal routes to feed into `AutoSwapprClient::execute_avnu_swap`
+
+use reqwest::Client;
+use serde::Deserialize;
+use starknet::core::types::Felt;
+
+use crate::{contracts::Route, types::connector::AutoSwapprError};
+
+/// Base URL of the AVNU mainnet API
+pub const AVNU_MAINNET_API: &str = "https://starknet.api.avnu.fi";
+
+/// Route percentages are sent on-chain scaled so that 100% = 10^12
+pub const PERCENT_SCALE: f64 = 1_000_000_000_000.0;
+
+/// Quote returned by the AVNU API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Quote {
+    routes: Vec<QuoteRoute>,
+}
+
+/// Single route of an AVNU quote
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuoteRoute {
+    name: String,
+    sell_token_address: String,
+    buy_token_address: String,
+    address: String,
+    percent: f64,
+}
+
+/// Fetch the best AVNU quote for swapping `amount` of `token_in` into `token_out`
+/// and map its routes into [`Route`]s.
+///
+/// Quotes routed through Ekubo are rejected, since its adapter needs pool parameters
+/// the quote doesn't include.
+pub async fn fetch_quote(
+    client: &Client,
+    token_in: Felt,
+    token_out: Felt,
+    amount: u128,
+    base_url: &str,
+) -> Result<Vec<Route>, AutoSwapprError> {
+    if amount == 0 {
+        return Err(AutoSwapprError::ZeroAmount);
+    }
+
+    let url = format!("{}/swap/v2/quotes", base_url.trim_end_matches('/'));
+    let response = client
+        .get(&url)
+        .query(&[
+            ("sellTokenAddress", format!("{:#x}", token_in)),
+            ("buyTokenAddress", format!("{:#x}", token_out)),
+            ("sellAmount", format!("{:#x}", amount)),
+        ])
+        .send()
+        .await
+        .map_err(|e| AutoSwapprError::NetworkError {
+            message: format!("AVNU quote request failed: {}", e),
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(AutoSwapprError::NetworkError {
+            message: format!("AVNU quote error: {} - {}", status, text),
+        });
+    }
+
+    let quotes: Vec<Quote> = response
+        .json()
+        .await
+        .map_err(|e| AutoSwapprError::NetworkError {
+            message: format!("Invalid AVNU quote response: {}", e),
+        })?;
+
+    let quote = quotes
+        .into_iter()
+        .next()
+        .ok_or_else(|| AutoSwapprError::SwapFailed {
+            reason: "No AVNU quote available".to_string(),
+        })?;
+
+    quote.routes.into_iter().map(into_route).collect()
+}
+
+// Exchanges whose AVNU adapter needs pool parameters that quotes don't carry
+const EXCHANGES_WITH_SWAP_PARAMS: &[&str] = &["Ekubo"];
+
+fn into_route(route: QuoteRoute) -> Result<Route, AutoSwapprError> {
+    if EXCHANGES_WITH_SWAP_PARAMS.contains(&route.name.as_str()) {
+        return Err(AutoSwapprError::SwapFailed {
+            reason: format!(
+                "AVNU route through {} needs pool parameters the quote doesn't include",
+                route.name
+            ),
+        });
+    }
+
+    let parse = |value: &str, field: &str| {
+        Felt::from_hex(value).map_err(|e| AutoSwapprError::InvalidInput {
+            details: format!("Invalid {} in AVNU quote: {}", field, e),
+        })
+    };
+
+    Ok(Route {
+        token_from: parse(&route.sell_token_address, "sell token address")?,
+        token_to: parse(&route.buy_token_address, "buy token address")?,
+        exchange_address: parse(&route.address, "exchange address")?,
+        percent: (route.percent * PERCENT_SCALE).round() as u128,
+        additional_swap_params: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::Query, routing::get};
+    use serde_json::{Value, json};
+    use std::collections::HashMap;
+
+    async fn start_quote_server(routes: Value) -> String {
+        let app = Router::new().route(
+            "/swap/v2/quotes",
+            get(|Query(query): Query<HashMap<String, String>>| async move {
+                assert_eq!(query["sellAmount"], "0xde0b6b3a7640000");
+                let routes: Vec<Value> = routes
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|route| {
+                        let mut route = route.clone();
+                        route["sellTokenAddress"] = json!(query["sellTokenAddress"]);
+                        route["buyTokenAddress"] = json!(query["buyTokenAddress"]);
+                        route["routes"] = json!([]);
+                        route
+                    })
+                    .collect();
+                Json::<Value>(json!([{
+                    "quoteId": "abc",
+                    "sellTokenAddress": query["sellTokenAddress"],
+                    "buyTokenAddress": query["buyTokenAddress"],
+                    "sellAmount": query["sellAmount"],
+                    "buyAmount": "0x1e8480",
+                    "routes": routes
+                }]))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_fetch_quote_maps_routes() {
+        let base_url = start_quote_server(json!([
+            {
+                "name": "JediSwap",
+                "address": "0x41fd22b238fa21cfcf5dd45a8548974d8263b3a531a60388411c5e230f97023",
+                "percent": 0.9979
+            },
+            {
+                "name": "10kSwap",
+                "address": "0x7a6f98c03379b9513ca84cca1373ff452a7462a3b61598f0af5bb27ad7f76d1",
+                "percent": 0.0021
+            }
+        ]))
+        .await;
+        let eth = *crate::ETH;
+        let usdc = *crate::USDC;
+
+        let routes = fetch_quote(
+            &Client::new(),
+            eth,
+            usdc,
+            1_000_000_000_000_000_000,
+            &base_url,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].token_from, eth);
+        assert_eq!(routes[0].token_to, usdc);
+        assert_eq!(
+            routes[0].exchange_address,
+            Felt::from_hex("0x41fd22b238fa21cfcf5dd45a8548974d8263b3a531a60388411c5e230f97023")
+                .unwrap()
+        );
+        // 0.0021 * 10^12 is just below 2_100_000_000 in f64
+        assert_eq!(routes[0].percent, 997_900_000_000);
+        assert_eq!(routes[1].percent, 2_100_000_000);
+        assert!(routes[1].additional_swap_params.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_quote_rejects_routes_needing_swap_params() {
+        let base_url = start_quote_server(json!([
+            {
+                "name": "Ekubo",
+                "address": "0x5dd3d2f4429af886cd1a3b08289dbcea99a294197e9eb43b0e0325b4b",
+                "percent": 1.0
+            }
+        ]))
+        .await;
+
+        let result = fetch_quote(
+            &Client::new(),
+            *crate::ETH,
+            *crate::USDC,
+            1_000_000_000_000_000_000,
+            &base_url,
+        )
+        .await;
+        assert!(
+            matches!(result, Err(AutoSwapprError::SwapFailed { reason }) if reason.contains("Ekubo"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_quote_rejects_zero_amount() {
+        let result = fetch_quote(
+            &Client::new(),
+            *crate::ETH,
+            *crate::USDC,
+            0,
+            AVNU_MAINNET_API,
+        )
+        .await;
+        assert!(matches!(result, Err(AutoSwapprError::ZeroAmount)));
+    }
+}
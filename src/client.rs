@@ -1,98 +1,643 @@
 use crate::{
-    contracts::{AutoSwapprContract, Erc20Contract},
-    types::connector::{AutoSwappr, AutoSwapprError, ContractInfo, SwapData, Uint256},
+    constant::TokenAddress,
+    contracts::{
+        AutoSwapprContract, ContractError, EkuboCoreContract, Erc20Contract, OracleContract,
+        PoolPrice, addresses, ekubo_abi,
+    },
+    format::{format_tx_hash, from_display, to_display},
+    math::{self, sqrt_ratio_limit_for_max_input},
+    nonce::{NonceTracker, TrackedAccount},
+    provider::{
+        Network, ReconnectingTransport, RetryPolicy, SUPPORTED_SPEC_VERSION,
+        is_spec_version_supported, sleep, unix_timestamp, unix_timestamp_millis,
+    },
+    types::address::{Address, IntoAddress, TokenRef},
+    types::connector::{
+        AccountType, AutoSwapprConfig, AutoSwapprError, ClientSnapshot, ContractInfo, FeeToken,
+        FeeType, HealthStatus, PoolKey, PortfolioValue, SwapData, SwapExecuted, SwapRecord,
+        TokenInfo, TxStatus, Uint256,
+    },
 };
+use futures::{StreamExt, TryStreamExt, stream};
 use starknet::{
-    accounts::{Account, ExecutionEncoding, SingleOwnerAccount},
-    core::{chain_id, types::Felt},
+    accounts::{
+        Account, ConnectedAccount, ExecutionEncoder, ExecutionEncoding, ExecutionV3,
+        SingleOwnerAccount,
+    },
+    core::{
+        chain_id,
+        crypto::compute_hash_on_elements,
+        types::{
+            BlockId, BlockTag, Call, ContractClass, ExecutionResult, FeeEstimate, Felt,
+            MaybePreConfirmedBlockWithTxHashes, ResourceBounds, ResourceBoundsMapping,
+            StarknetError,
+            typed_data::{Domain, Revision},
+        },
+    },
     providers::{
-        Url,
+        Provider, ProviderError, Url,
         jsonrpc::{HttpTransport, JsonRpcClient},
     },
-    signers::{LocalWallet, SigningKey},
+    signers::{LocalWallet, Signer, SigningKey},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
-use std::sync::Arc;
 
-/// Main client for interacting with AutoSwappr with real Starknet integration
-pub struct AutoSwapprClient {
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+/// Main client for interacting with AutoSwappr with real Starknet integration.
+///
+/// Generic over the provider so a caching or mock [`Provider`] can be injected with
+/// [`from_provider`](Self::from_provider); defaults to the HTTP JSON-RPC client.
+///
+/// Token amounts are raw base units (e.g. wei) everywhere except
+/// [`swap_whole_tokens`](Self::swap_whole_tokens), which takes a human amount and scales
+/// it by the token's on-chain decimals.
+pub struct AutoSwapprClient<P = JsonRpcClient<ReconnectingTransport<HttpTransport>>>
+where
+    P: Provider + Send + Sync,
+{
+    provider: Arc<P>,
     autoswappr_contract: AutoSwapprContract,
-    account: SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
-    config: AutoSwappr,
+    account: SingleOwnerAccount<P, LocalWallet>,
+    // The account's signer, for messages signed outside of transactions
+    wallet: LocalWallet,
+    config: AutoSwapprConfig,
+    retry_policy: RetryPolicy,
+    concurrency_limit: usize,
+    history: Mutex<Option<Vec<SwapRecord>>>,
+    // Shared by clones, which sign for the same account
+    nonce: Arc<NonceTracker>,
+    max_resource_bounds: Option<ResourceBoundsMapping>,
+    dry_run: bool,
+    decimals: Mutex<HashMap<Felt, u8>>,
+    price_ttl: Option<Duration>,
+    // USD value of one whole token, with the unix time in milliseconds it was read at
+    prices: Mutex<HashMap<Felt, (u128, u64)>>,
+}
+
+/// Clones share the provider and the account's nonce tracker, which reserves a distinct nonce
+/// for each transaction, so they can be moved into spawned tasks and submit concurrently.
+/// Swap history and the decimals and price caches are copied.
+impl<P> Clone for AutoSwapprClient<P>
+where
+    P: Provider + Send + Sync + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            provider: self.provider.clone(),
+            autoswappr_contract: self.autoswappr_contract.clone(),
+            account: self.account.clone(),
+            wallet: self.wallet.clone(),
+            config: self.config.clone(),
+            retry_policy: self.retry_policy.clone(),
+            concurrency_limit: self.concurrency_limit,
+            history: Mutex::new(self.history.lock().unwrap().clone()),
+            nonce: self.nonce.clone(),
+            max_resource_bounds: self.max_resource_bounds.clone(),
+            dry_run: self.dry_run,
+            decimals: Mutex::new(self.decimals.lock().unwrap().clone()),
+            price_ttl: self.price_ttl,
+            prices: Mutex::new(self.prices.lock().unwrap().clone()),
+        }
+    }
+}
+
+/// Maximum number of receipt polls in [`AutoSwapprClient::wait_for_transaction`]
+pub const WAIT_MAX_POLLS: u32 = 120;
+
+/// Most blocks queried per `starknet_getEvents` filter by
+/// [`AutoSwapprClient::get_swap_events`], which splits longer ranges
+pub const MAX_EVENTS_BLOCK_RANGE: u64 = 10_000;
+
+/// Default number of concurrent RPC requests issued by batch operations
+pub const DEFAULT_CONCURRENCY_LIMIT: usize = 8;
+
+/// Headroom over a fee estimate, in percent, for resource bounds capped by
+/// [`AutoSwapprClient::with_max_resource_bounds`]; starknet-rs' own default is 1.5x
+pub const FEE_ESTIMATE_MARGIN_PERCENT: u128 = 150;
+
+/// Ekubo pool, input decimals and direction of a token pair, resolved once by
+/// [`AutoSwapprClient::prepare_swap`] so repeated swaps of the pair skip the lookups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapPlan {
+    pub token_in: Felt,
+    pub token_out: Felt,
+    pub pool_key: PoolKey,
+    /// Decimals of `token_in`
+    pub decimals: u8,
+    /// Whether `token_in` is the pool's token1
+    pub is_token1: bool,
+}
+
+impl SwapPlan {
+    /// Swap `amount` of `token_in` (in base units) into `token_out` through the planned pool
+    pub async fn execute<P>(
+        &self,
+        client: &AutoSwapprClient<P>,
+        amount: u128,
+    ) -> Result<String, AutoSwapprError>
+    where
+        P: Provider + Send + Sync + Clone,
+    {
+        let mut swap_data = SwapData::builder()
+            .amount(amount)
+            .pool(self.token_in, self.token_out)
+            .caller(client.account.address())
+            .build()?;
+        // The builder assumes the standard fee tier; the plan has the one actually found
+        swap_data.pool_key = self.pool_key.clone();
+
+        client.execute_ekubo_swap(swap_data, None).await
+    }
+
+    /// Swap `whole_amount` of `token_in`, e.g. `1.5` ETH, scaled by the planned decimals
+    pub async fn execute_whole_tokens<P>(
+        &self,
+        client: &AutoSwapprClient<P>,
+        whole_amount: f64,
+    ) -> Result<String, AutoSwapprError>
+    where
+        P: Provider + Send + Sync + Clone,
+    {
+        if !whole_amount.is_finite() || whole_amount < 0.0 {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!("Invalid amount '{}'", whole_amount),
+            });
+        }
+
+        let amount = from_display(&whole_amount.to_string(), self.decimals)?;
+        self.execute(client, amount).await
+    }
+}
+
+/// Builder for [`AutoSwapprClient`] that fills in mainnet defaults
+#[derive(Debug, Clone, Default)]
+pub struct AutoSwapprClientBuilder {
+    account_address: Option<String>,
+    private_key: Option<String>,
+    contract_address: Option<String>,
+    network: Option<Network>,
+    retry_policy: Option<RetryPolicy>,
+    concurrency_limit: Option<usize>,
+    max_resource_bounds: Option<ResourceBoundsMapping>,
+    account_type: AccountType,
+    fee_token: FeeToken,
+    allow_spec_version_mismatch: bool,
+}
+
+impl AutoSwapprClientBuilder {
+    /// Set the account address (required)
+    pub fn account_address(mut self, account_address: impl Into<String>) -> Self {
+        self.account_address = Some(account_address.into());
+        self
+    }
+
+    /// Set the account private key (required)
+    pub fn private_key(mut self, private_key: impl Into<String>) -> Self {
+        self.private_key = Some(private_key.into());
+        self
+    }
+
+    /// Set the AutoSwappr contract address (defaults to the mainnet deployment)
+    pub fn contract_address(mut self, contract_address: impl Into<String>) -> Self {
+        self.contract_address = Some(contract_address.into());
+        self
+    }
+
+    /// Set the network whose RPC URL is used (defaults to mainnet)
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Set the retry policy for RPC requests
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Set the maximum number of concurrent RPC requests issued by batch operations
+    pub fn concurrency_limit(mut self, concurrency_limit: usize) -> Self {
+        self.concurrency_limit = Some(concurrency_limit);
+        self
+    }
+
+    /// Cap the resource bounds of every transaction (see
+    /// [`AutoSwapprClient::with_max_resource_bounds`])
+    pub fn max_resource_bounds(mut self, max_resource_bounds: ResourceBoundsMapping) -> Self {
+        self.max_resource_bounds = Some(max_resource_bounds);
+        self
+    }
+
+    /// Set the account implementation, which decides the calldata encoding (defaults to
+    /// detecting it from the account's class)
+    pub fn account_type(mut self, account_type: AccountType) -> Self {
+        self.account_type = account_type;
+        self
+    }
+
+    /// Set the token transaction fees are paid in (defaults to STRK)
+    pub fn fee_token(mut self, fee_token: FeeToken) -> Self {
+        self.fee_token = fee_token;
+        self
+    }
+
+    /// Only log a warning, instead of failing, when the RPC serves a different JSON-RPC
+    /// spec version than [`SUPPORTED_SPEC_VERSION`]
+    pub fn allow_spec_version_mismatch(mut self, allow: bool) -> Self {
+        self.allow_spec_version_mismatch = allow;
+        self
+    }
+
+    /// Validate the settings and build the client
+    pub async fn build(self) -> Result<AutoSwapprClient, AutoSwapprError> {
+        let account_address =
+            self.account_address
+                .ok_or_else(|| AutoSwapprError::InvalidInput {
+                    details: "Account address is required".to_string(),
+                })?;
+
+        let private_key = self
+            .private_key
+            .ok_or_else(|| AutoSwapprError::InvalidInput {
+                details: "Private key is required".to_string(),
+            })?;
+
+        let concurrency_limit = self.concurrency_limit.unwrap_or(DEFAULT_CONCURRENCY_LIMIT);
+        if concurrency_limit == 0 {
+            return Err(AutoSwapprError::InvalidInput {
+                details: "Concurrency limit must be greater than zero".to_string(),
+            });
+        }
+
+        let config = AutoSwapprConfig {
+            contract_address: self
+                .contract_address
+                .unwrap_or_else(|| addresses::mainnet::AUTOSWAPPR.to_string()),
+            rpc_url: self
+                .network
+                .unwrap_or(Network::Mainnet)
+                .rpc_url()
+                .to_string(),
+            account_address,
+            private_key,
+            account_type: self.account_type,
+            fee_token: self.fee_token,
+        };
+
+        let mut client =
+            AutoSwapprClient::connect(config, !self.allow_spec_version_mismatch).await?;
+        client.retry_policy = self.retry_policy.unwrap_or_default();
+        client.concurrency_limit = concurrency_limit;
+        client.max_resource_bounds = self.max_resource_bounds;
+
+        Ok(client)
+    }
 }
 
 impl AutoSwapprClient {
+    /// Start building a client with mainnet defaults
+    pub fn builder() -> AutoSwapprClientBuilder {
+        AutoSwapprClientBuilder::default()
+    }
+
     /// Create a new AutoSwappr client with real Starknet integration
-    pub async fn new(config: AutoSwappr) -> Result<Self, AutoSwapprError> {
+    ///
+    /// Fails with `ProviderError` if the RPC serves an incompatible JSON-RPC spec version;
+    /// use the builder's [`allow_spec_version_mismatch`](AutoSwapprClientBuilder::allow_spec_version_mismatch)
+    /// to only warn instead.
+    pub async fn new(config: AutoSwapprConfig) -> Result<Self, AutoSwapprError> {
+        Self::connect(config, true).await
+    }
+
+    async fn connect(
+        config: AutoSwapprConfig,
+        strict_spec_version: bool,
+    ) -> Result<Self, AutoSwapprError> {
         // Parse RPC URL
         let rpc_url = Url::parse(&config.rpc_url).map_err(|e| AutoSwapprError::InvalidInput {
             details: format!("Invalid RPC URL: {}", e),
         })?;
 
-        // Create provider
-        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(rpc_url)));
+        // Create provider, reconnecting if the connection drops during long-lived use
+        let provider = JsonRpcClient::new(ReconnectingTransport::http(rpc_url));
+        check_spec_version(&provider, strict_spec_version).await?;
 
-        // Parse account address
-        let account_address =
-            Felt::from_hex(&config.account_address).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid account address: {}", e),
-            })?;
+        Self::from_provider(provider, config).await
+    }
+
+    /// Create a new client, failing if the account is not deployed on-chain yet or holds
+    /// none of its fee token
+    pub async fn new_checked(config: AutoSwapprConfig) -> Result<Self, AutoSwapprError> {
+        let client = Self::new(config).await?;
 
-        // Parse private key
-        let private_key =
-            Felt::from_hex(&config.private_key).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid private key: {}", e),
+        if !client.is_account_deployed().await? {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!("Account {:#x} is not deployed", client.account.address()),
+            });
+        }
+
+        let fee_token = client.config.fee_token;
+        if client.get_token_balance(fee_token.address()).await? == 0 {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!(
+                    "Account {:#x} holds no {} to pay fees",
+                    client.account.address(),
+                    fee_token.symbol()
+                ),
+            });
+        }
+
+        Ok(client)
+    }
+
+    /// Create a new client, failing if `contract_address` does not answer `contract_parameters`
+    /// the way an AutoSwappr deployment does.
+    ///
+    /// [`new`](Self::new) stays unverified to avoid the extra round trip.
+    pub async fn new_verified(config: AutoSwapprConfig) -> Result<Self, AutoSwapprError> {
+        let client = Self::new(config).await?;
+
+        client
+            .autoswappr_contract
+            .verify_contract_parameters(&*client.provider)
+            .await
+            .map_err(|e| AutoSwapprError::InvalidInput {
+                details: format!(
+                    "{:#x} is not an AutoSwappr contract: {}",
+                    client.autoswappr_contract.address(),
+                    e
+                ),
             })?;
 
-        // Create signer
-        let signer = LocalWallet::from(SigningKey::from_secret_scalar(private_key));
+        Ok(client)
+    }
+}
+
+impl<P> AutoSwapprClient<P>
+where
+    P: Provider + Send + Sync + Clone,
+{
+    /// Create a client on top of an existing provider.
+    ///
+    /// `config.rpc_url` is not used to connect and is only reported back by
+    /// [`rpc_url`](Self::rpc_url).
+    pub async fn from_provider(
+        provider: P,
+        config: AutoSwapprConfig,
+    ) -> Result<Self, AutoSwapprError> {
+        if config.fee_token == FeeToken::Eth {
+            return Err(AutoSwapprError::InvalidInput {
+                details: "ETH fees need v1 transactions, which Starknet no longer accepts; \
+                          pay fees in STRK"
+                    .to_string(),
+            });
+        }
+
+        let provider = Arc::new(provider);
+
+        // Sign for the network the RPC actually serves, assuming mainnet if it can't be asked
+        let chain_id = match detect_network(&*provider).await {
+            Ok(network) => network_chain_id(&network)?,
+            Err(_e) => {
+                trace_event!(warn, error = %_e, "chain id detection failed, assuming mainnet");
+                chain_id::MAINNET
+            }
+        };
+
+        let encoding =
+            resolve_execution_encoding(&*provider, config.account_type, &config.account_address)
+                .await?;
 
         // Create account
-        let account = SingleOwnerAccount::new(
-            (*provider).clone(),
-            signer,
-            account_address,
-            chain_id::MAINNET, // TODO: Make this configurable based on RPC URL
-            ExecutionEncoding::New,
-        );
+        let wallet = build_wallet(&config.private_key)?;
+        let account = build_account(
+            &provider,
+            &config.account_address,
+            wallet.clone(),
+            chain_id,
+            encoding,
+        )?;
 
         // Parse contract address
-        let contract_address = Felt::from_hex(&config.contract_address).map_err(|e| {
-            AutoSwapprError::InvalidInput {
-                details: format!("Invalid contract address: {}", e),
-            }
-        })?;
+        let contract_address = Address::from_hex(&config.contract_address)?.felt();
 
         // Create AutoSwappr contract
-        let autoswappr_contract = AutoSwapprContract::new(contract_address, provider.clone());
+        let autoswappr_contract = AutoSwapprContract::new(contract_address);
 
         Ok(Self {
             provider,
             autoswappr_contract,
             account,
+            wallet,
             config,
+            retry_policy: RetryPolicy::default(),
+            concurrency_limit: DEFAULT_CONCURRENCY_LIMIT,
+            history: Mutex::new(None),
+            nonce: Arc::default(),
+            max_resource_bounds: None,
+            dry_run: false,
+            decimals: Mutex::new(HashMap::new()),
+            price_ttl: None,
+            prices: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Create a client for a different account that shares this client's provider and contract.
+    ///
+    /// With [`AccountType::Auto`] the new account's calldata encoding is detected from its
+    /// class, as in [`from_provider`](Self::from_provider).
+    pub async fn with_account(
+        &self,
+        account_address: &str,
+        private_key: &str,
+    ) -> Result<Self, AutoSwapprError> {
+        let encoding =
+            resolve_execution_encoding(&*self.provider, self.config.account_type, account_address)
+                .await?;
+        let wallet = build_wallet(private_key)?;
+        let account = build_account(
+            &self.provider,
+            account_address,
+            wallet.clone(),
+            self.account.chain_id(),
+            encoding,
+        )?;
+
+        Ok(Self {
+            provider: self.provider.clone(),
+            autoswappr_contract: self.autoswappr_contract.clone(),
+            account,
+            wallet,
+            config: AutoSwapprConfig {
+                account_address: account_address.to_string(),
+                private_key: private_key.to_string(),
+                ..self.config.clone()
+            },
+            retry_policy: self.retry_policy.clone(),
+            concurrency_limit: self.concurrency_limit,
+            history: Mutex::new(None),
+            nonce: Arc::default(),
+            max_resource_bounds: self.max_resource_bounds.clone(),
+            dry_run: self.dry_run,
+            decimals: Mutex::new(self.decimals.lock().unwrap().clone()),
+            price_ttl: self.price_ttl,
+            prices: Mutex::new(self.prices.lock().unwrap().clone()),
         })
     }
 
+    /// Check that the RPC endpoint is reachable, reporting its latest block and chain id.
+    ///
+    /// Fails with the provider error if either request does not succeed.
+    pub async fn health_check(&self) -> Result<HealthStatus, AutoSwapprError> {
+        let started = unix_timestamp_millis();
+        let (block_number, chain_id) =
+            futures::try_join!(self.provider.block_number(), self.provider.chain_id())
+                .map_err(ContractError::from)?;
+        let latency_ms = unix_timestamp_millis().saturating_sub(started);
+        trace_event!(debug, block_number, latency_ms, "rpc health checked");
+
+        Ok(HealthStatus {
+            block_number,
+            chain_id: format!("{:#x}", chain_id),
+            latency_ms,
+        })
+    }
+
+    /// Check whether the configured account has been deployed
+    pub async fn is_account_deployed(&self) -> Result<bool, AutoSwapprError> {
+        match self
+            .provider
+            .get_class_hash_at(BlockId::Tag(BlockTag::Latest), self.account.address())
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(false),
+            Err(e) => Err(ContractError::from(e).into()),
+        }
+    }
+
     /// Get contract parameters
     pub async fn get_contract_parameters(&self) -> Result<ContractInfo, AutoSwapprError> {
-        self.autoswappr_contract
+        Ok(self
+            .autoswappr_contract
             .get_contract_parameters(&*self.provider)
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })
+            .await?)
+    }
+
+    /// Get the swaps executed through the contract between `from_block` and `to_block`
+    /// (inclusive), oldest first.
+    ///
+    /// The range is queried in chunks of [`MAX_EVENTS_BLOCK_RANGE`] blocks and each chunk is
+    /// paged through, so long ranges stay within RPC limits.
+    pub async fn get_swap_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<SwapExecuted>, AutoSwapprError> {
+        if from_block > to_block {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!("Block range {}..={} is empty", from_block, to_block),
+            });
+        }
+
+        let mut swaps = Vec::new();
+        let mut chunk_start = from_block;
+        loop {
+            let chunk_end = chunk_start
+                .saturating_add(MAX_EVENTS_BLOCK_RANGE - 1)
+                .min(to_block);
+            swaps.extend(
+                self.autoswappr_contract
+                    .get_swap_events(&*self.provider, chunk_start, chunk_end)
+                    .await?,
+            );
+
+            if chunk_end == to_block {
+                return Ok(swaps);
+            }
+            chunk_start = chunk_end + 1;
+        }
+    }
+
+    /// Get a serializable snapshot of the client configuration and contract parameters
+    pub async fn snapshot(&self) -> Result<ClientSnapshot, AutoSwapprError> {
+        let contract_parameters = self.get_contract_parameters().await?;
+
+        Ok(ClientSnapshot {
+            account_address: self.account_address(),
+            contract_address: self.contract_address(),
+            contract_parameters,
+        })
     }
 
-    /// Get token amount in USD
+    /// Get token amount in USD.
+    ///
+    /// Fails with [`AutoSwapprError::UnsupportedToken`] when the contract has no price feed
+    /// for `token`, instead of returning the contract's zero value. With a
+    /// [price TTL](Self::with_price_ttl) set, the USD value of one whole token read within
+    /// the TTL is reused and scaled to `token_amount`.
     pub async fn get_token_amount_in_usd(
         &self,
-        token: &str,
+        token: impl IntoAddress,
         token_amount: u128,
     ) -> Result<u128, AutoSwapprError> {
-        let token_felt = Felt::from_hex(token).map_err(|e| AutoSwapprError::InvalidInput {
-            details: format!("Invalid token address: {}", e),
-        })?;
+        let token = token.into_address()?;
+        let token_felt = token.felt();
+
+        let Some(ttl) = self.price_ttl else {
+            return self.read_usd_value(&token, token_amount).await;
+        };
+
+        let one_token = 10_u128
+            .checked_pow(self.token_decimals(token_felt).await? as u32)
+            .ok_or_else(|| AutoSwapprError::InvalidInput {
+                details: format!("{} has too many decimals to price", token),
+            })?;
+        let unit_price = match self.cached_price(token_felt, ttl) {
+            Some(unit_price) => unit_price,
+            None => {
+                let unit_price = self.read_usd_value(&token, one_token).await?;
+                let now = unix_timestamp_millis();
+                let mut prices = self.prices.lock().unwrap();
+                prices.retain(|_, (_, read_at)| {
+                    now.saturating_sub(*read_at) < ttl.as_millis() as u64
+                });
+                prices.insert(token_felt, (unit_price, now));
+                unit_price
+            }
+        };
+
+        let usd = Uint256::from_u128(unit_price)
+            .mul_div(
+                Uint256::from_u128(token_amount),
+                Uint256::from_u128(one_token),
+            )
+            .ok_or_else(|| AutoSwapprError::InvalidInput {
+                details: format!("USD value of {} {} overflows", token_amount, token),
+            })?;
+
+        u128::try_from(usd)
+    }
+
+    // Read the oracle's USD value of `token_amount`, rejecting tokens without a price feed
+    async fn read_usd_value(
+        &self,
+        token: &Address,
+        token_amount: u128,
+    ) -> Result<u128, AutoSwapprError> {
+        let token_felt = token.felt();
+        let (supported, _) = self
+            .autoswappr_contract
+            .get_token_from_status_and_value(&*self.provider, token_felt)
+            .await?;
+        if !supported {
+            return Err(AutoSwapprError::UnsupportedToken {
+                token: token.to_string(),
+            });
+        }
 
         let amount_uint256 = Uint256::from_u128(token_amount);
         let starknet_uint256 = crate::contracts::conversions::uint256_to_starknet(&amount_uint256);
@@ -100,542 +645,4546 @@ impl AutoSwapprClient {
         let result = self
             .autoswappr_contract
             .get_token_amount_in_usd(&*self.provider, token_felt, starknet_uint256)
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+            .await?;
 
-        Ok(crate::contracts::conversions::uint256_to_u128(
-            result.low.try_into().unwrap_or(Felt::ZERO),
-            result.high.try_into().unwrap_or(Felt::ZERO),
-        ))
+        u128::try_from(result)
+    }
+
+    fn cached_price(&self, token: Felt, ttl: Duration) -> Option<u128> {
+        let (unit_price, read_at) = *self.prices.lock().unwrap().get(&token)?;
+
+        (unix_timestamp_millis().saturating_sub(read_at) < ttl.as_millis() as u64)
+            .then_some(unit_price)
     }
 
-    /// Get token amount in USD with proper decimal formatting
+    /// Get token amount in USD as a decimal string, e.g. `"2.5"`, with the oracle value
+    /// scaled down by `decimals`
     pub async fn get_token_amount_in_usd_formatted(
         &self,
-        token: &str,
+        token: impl IntoAddress,
         token_amount: u128,
         decimals: u8,
-    ) -> Result<f64, AutoSwapprError> {
+    ) -> Result<String, AutoSwapprError> {
         let raw_usd_amount = self.get_token_amount_in_usd(token, token_amount).await?;
 
-        // Convert from raw amount to decimal amount
-        let divisor = 10_u128.pow(decimals as u32);
-        let usd_amount = raw_usd_amount as f64 / divisor as f64;
+        Ok(to_display(raw_usd_amount, decimals))
+    }
+
+    /// Check that `claimed` matches the token's on-chain decimals.
+    ///
+    /// Returns `Ok(false)` on a mismatch, which would otherwise silently skew
+    /// [`get_token_amount_in_usd_formatted`](Self::get_token_amount_in_usd_formatted)
+    /// by orders of magnitude.
+    pub async fn verify_decimals(
+        &self,
+        token: impl IntoAddress,
+        claimed: u8,
+    ) -> Result<bool, AutoSwapprError> {
+        let token_felt = token.into_address()?.felt();
+
+        let erc20_contract = self.erc20(token_felt);
 
-        Ok(usd_amount)
+        let on_chain = erc20_contract.decimals(&*self.provider).await?;
+
+        Ok(on_chain == claimed)
     }
 
     /// Check token allowance
     pub async fn get_allowance(
         &self,
-        token_address: &str,
-        owner: &str,
-        spender: &str,
+        token_address: impl IntoAddress,
+        owner: impl IntoAddress,
+        spender: impl IntoAddress,
     ) -> Result<u128, AutoSwapprError> {
-        let token_felt =
-            Felt::from_hex(token_address).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid token address: {}", e),
-            })?;
-
-        let owner_felt = Felt::from_hex(owner).map_err(|e| AutoSwapprError::InvalidInput {
-            details: format!("Invalid owner address: {}", e),
-        })?;
+        let token = token_address.into_address()?;
+        let owner = owner.into_address()?;
+        let spender = spender.into_address()?;
 
-        let spender_felt = Felt::from_hex(spender).map_err(|e| AutoSwapprError::InvalidInput {
-            details: format!("Invalid spender address: {}", e),
-        })?;
-
-        let erc20_contract = Erc20Contract::new(token_felt, self.provider.clone());
+        let erc20_contract = self.erc20(token.felt());
 
         let result = erc20_contract
-            .allowance(&*self.provider, owner_felt, spender_felt)
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+            .allowance(&*self.provider, owner.felt(), spender.felt())
+            .await?;
 
-        Ok(crate::contracts::conversions::uint256_to_u128(
-            result.low.try_into().unwrap_or(Felt::ZERO),
-            result.high.try_into().unwrap_or(Felt::ZERO),
-        ))
+        let allowance = u128::try_from(result)?;
+        trace_event!(
+            debug,
+            token = %token,
+            owner = %owner,
+            spender = %spender,
+            allowance,
+            "allowance checked"
+        );
+
+        Ok(allowance)
     }
 
-    /// Approve token spending
+    /// Approve `spender` to spend `amount` base units of a token
     pub async fn approve_token(
         &self,
-        token_address: &str,
-        spender: &str,
+        token_address: impl IntoAddress,
+        spender: impl IntoAddress,
         amount: u128,
     ) -> Result<String, AutoSwapprError> {
-        let token_felt =
-            Felt::from_hex(token_address).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid token address: {}", e),
-            })?;
-
-        let spender_felt = Felt::from_hex(spender).map_err(|e| AutoSwapprError::InvalidInput {
-            details: format!("Invalid spender address: {}", e),
-        })?;
+        let token = token_address.into_address()?;
+        let spender = spender.into_address()?;
 
-        let erc20_contract = Erc20Contract::new(token_felt, self.provider.clone());
+        let erc20_contract = self.erc20(token.felt());
 
         let amount_uint256 = Uint256::from_u128(amount);
         let starknet_uint256 = crate::contracts::conversions::uint256_to_starknet(&amount_uint256);
 
-        let tx_hash = erc20_contract
-            .approve(&self.account, spender_felt, starknet_uint256)
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+        let tx_hash = self
+            .submit(vec![
+                erc20_contract.approve_call(spender.felt(), starknet_uint256)?,
+            ])
+            .await?;
+        trace_event!(
+            info,
+            token = %token,
+            spender = %spender,
+            amount,
+            tx_hash = %format!("{:#x}", tx_hash),
+            "approval submitted"
+        );
 
-        Ok(tx_hash.to_string())
+        Ok(format_tx_hash(tx_hash))
     }
 
-    /// Get token balance
-    pub async fn get_token_balance(&self, token_address: &str) -> Result<u128, AutoSwapprError> {
-        let token_felt =
-            Felt::from_hex(token_address).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid token address: {}", e),
-            })?;
+    /// Build a gasless approval of `amount` base units for `spender`, valid until `deadline`
+    /// (unix seconds).
+    ///
+    /// If the token supports permit, the approval is signed off-chain by the account's signer
+    /// (see [`Erc20Contract::permit_hash`]) and returned as a `permit` call that a relayer can
+    /// submit or that can be batched with a swap. Otherwise a standard `approve` call is
+    /// returned. Nothing is sent either way.
+    pub async fn approve_via_permit(
+        &self,
+        token_address: impl IntoAddress,
+        spender: impl IntoAddress,
+        amount: u128,
+        deadline: u64,
+    ) -> Result<Call, AutoSwapprError> {
+        let token = token_address.into_address()?;
+        let spender = spender.into_address()?;
+        let owner = self.account.address();
+        let amount = Uint256::from_u128(amount);
 
-        let erc20_contract = Erc20Contract::new(token_felt, self.provider.clone());
+        let erc20_contract = self.erc20(token.felt());
+        let Some(nonce) = erc20_contract.permit_nonce(&*self.provider, owner).await? else {
+            trace_event!(debug, token = %token, "token lacks permit, falling back to approve");
+            return Ok(erc20_contract.approve_call(spender.felt(), amount)?);
+        };
 
-        let result = erc20_contract
-            .balance_of(&*self.provider, self.account.address())
+        let (name, version) = erc20_contract.snip12_metadata(&*self.provider).await?;
+        let domain = Domain {
+            name,
+            version,
+            chain_id: self.account.chain_id(),
+            revision: Revision::V1,
+        };
+        let hash =
+            erc20_contract.permit_hash(owner, spender.felt(), amount, nonce, deadline, &domain)?;
+        let signature = self
+            .wallet
+            .sign_hash(&hash)
             .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+            .map_err(|e| ContractError::AccountError(e.to_string()))?;
 
-        Ok(crate::contracts::conversions::uint256_to_u128(
-            result.low.try_into().unwrap_or(Felt::ZERO),
-            result.high.try_into().unwrap_or(Felt::ZERO),
-        ))
+        Ok(erc20_contract.permit_call(
+            owner,
+            spender.felt(),
+            amount,
+            deadline,
+            &[signature.r, signature.s],
+        )?)
     }
 
-    /// Get token information
-    pub async fn get_token_info(
+    /// Revoke `spender`'s allowance on a token by approving an amount of zero
+    pub async fn revoke_approval(
         &self,
-        token_address: &str,
-    ) -> Result<(String, String, u8), AutoSwapprError> {
-        let token_felt =
-            Felt::from_hex(token_address).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid token address: {}", e),
-            })?;
-
-        let erc20_contract = Erc20Contract::new(token_felt, self.provider.clone());
+        token_address: impl IntoAddress,
+        spender: impl IntoAddress,
+    ) -> Result<String, AutoSwapprError> {
+        self.approve_token(token_address, spender, 0).await
+    }
 
-        let name =
-            erc20_contract
-                .name(&*self.provider)
-                .await
-                .map_err(|e| AutoSwapprError::Other {
-                    message: e.to_string(),
-                })?;
+    /// Revoke `spender`'s allowance on every token in one multicall transaction
+    pub async fn revoke_all_approvals(
+        &self,
+        tokens: &[&str],
+        spender: impl IntoAddress,
+    ) -> Result<String, AutoSwapprError> {
+        if tokens.is_empty() {
+            return Err(AutoSwapprError::InvalidInput {
+                details: "No tokens to revoke".to_string(),
+            });
+        }
 
-        let symbol =
-            erc20_contract
-                .symbol(&*self.provider)
-                .await
-                .map_err(|e| AutoSwapprError::Other {
-                    message: e.to_string(),
-                })?;
+        let spender = spender.into_address()?;
+        let calls = tokens
+            .iter()
+            .map(|token| {
+                let erc20_contract = self.erc20(token.into_address()?.felt());
+                Ok(erc20_contract.approve_call(spender.felt(), Uint256::default())?)
+            })
+            .collect::<Result<Vec<Call>, AutoSwapprError>>()?;
 
-        let decimals = erc20_contract
-            .decimals(&*self.provider)
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+        let tx_hash = self.submit(calls).await?;
+        trace_event!(
+            info,
+            spender = %spender,
+            tokens = tokens.len(),
+            tx_hash = %format!("{:#x}", tx_hash),
+            "approvals revoked"
+        );
 
-        Ok((name, symbol, decimals))
+        Ok(format_tx_hash(tx_hash))
     }
 
-    /// Execute ekubo manual swap
-    pub async fn execute_ekubo_manual_swap(
+    /// Get token balance
+    pub async fn get_token_balance(
         &self,
-        swap_data: SwapData,
-    ) -> Result<String, AutoSwapprError> {
-        let tx_hash = self
-            .autoswappr_contract
-            .ekubo_manual_swap(&self.account, swap_data)
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+        token_address: impl IntoAddress,
+    ) -> Result<u128, AutoSwapprError> {
+        let token_felt = token_address.into_address()?.felt();
+
+        let erc20_contract = self.erc20(token_felt);
+
+        let result = erc20_contract
+            .balance_of(&*self.provider, self.account.address())
+            .await?;
 
-        Ok(tx_hash.to_string())
+        u128::try_from(result)
     }
 
-    /// Execute ekubo swap
-    pub async fn execute_ekubo_swap(&self, swap_data: SwapData) -> Result<String, AutoSwapprError> {
-        let tx_hash = self
-            .autoswappr_contract
-            .ekubo_swap(&self.account, swap_data)
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+    /// Get the account balance of `token` (hex address or known symbol) including the effect
+    /// of transactions not yet in a block.
+    ///
+    /// Reads the pre-confirmed block, which replaced the `pending` tag in JSON-RPC 0.9,
+    /// regardless of the client's [`block_id`](Self::block_id).
+    pub async fn get_token_balance_pending(&self, token: &str) -> Result<u128, AutoSwapprError> {
+        let token_felt = resolve_token(token)?;
+
+        let result = self
+            .erc20(token_felt)
+            .with_block_id(BlockId::Tag(BlockTag::PreConfirmed))
+            .balance_of(&*self.provider, self.account.address())
+            .await?;
 
-        Ok(tx_hash.to_string())
+        u128::try_from(result)
     }
 
-    /// Execute AVNU swap
-    pub async fn execute_avnu_swap(
+    /// Get the account balance of each token, keeping the input order.
+    ///
+    /// Up to `concurrency_limit` calls are in flight at once, and a failing token
+    /// only fails its own entry.
+    pub async fn get_balances(
         &self,
-        protocol_swapper: &str,
-        token_from_address: &str,
-        token_from_amount: u128,
-        token_to_address: &str,
-        token_to_min_amount: u128,
-        beneficiary: &str,
-        integrator_fee_amount_bps: u128,
-        integrator_fee_recipient: &str,
-        routes: Vec<crate::contracts::Route>,
-    ) -> Result<String, AutoSwapprError> {
-        let protocol_swapper_felt =
-            Felt::from_hex(protocol_swapper).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid protocol swapper address: {}", e),
-            })?;
+        tokens: &[&str],
+    ) -> Vec<(String, Result<u128, AutoSwapprError>)> {
+        stream::iter(tokens)
+            .map(|token| async move { (token.to_string(), self.get_token_balance(*token).await) })
+            .buffered(self.concurrency_limit)
+            .collect()
+            .await
+    }
 
-        let token_from_felt =
-            Felt::from_hex(token_from_address).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid token from address: {}", e),
-            })?;
+    /// Value the account's holdings of `tokens` (hex addresses or known symbols) in USD.
+    ///
+    /// Each balance is quoted through the contract's oracle and scaled by the token's
+    /// decimals. Tokens the contract does not support are left out of the total and
+    /// reported in [`PortfolioValue::warnings`].
+    pub async fn portfolio_usd(&self, tokens: &[&str]) -> Result<PortfolioValue, AutoSwapprError> {
+        let valuations: Vec<_> = stream::iter(tokens)
+            .map(|token| async move { (token.to_string(), self.token_value_usd(token).await) })
+            .buffered(self.concurrency_limit)
+            .collect()
+            .await;
 
-        let token_to_felt =
-            Felt::from_hex(token_to_address).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid token to address: {}", e),
-            })?;
+        let mut portfolio = PortfolioValue::default();
+        for (token, valuation) in valuations {
+            match valuation {
+                Ok(usd) => {
+                    portfolio.total_usd += usd;
+                    portfolio.breakdown.push((token, usd));
+                }
+                Err(AutoSwapprError::UnsupportedToken { .. }) => {
+                    trace_event!(warn, token = %token, "skipping unsupported token in portfolio");
+                    portfolio
+                        .warnings
+                        .push(format!("{}: not supported by the oracle", token));
+                }
+                Err(e) => return Err(e),
+            }
+        }
 
-        let beneficiary_felt =
-            Felt::from_hex(beneficiary).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid beneficiary address: {}", e),
-            })?;
+        Ok(portfolio)
+    }
 
-        let integrator_fee_recipient_felt =
-            Felt::from_hex(integrator_fee_recipient).map_err(|e| {
-                AutoSwapprError::InvalidInput {
-                    details: format!("Invalid integrator fee recipient address: {}", e),
-                }
-            })?;
+    async fn token_value_usd(&self, token: &str) -> Result<f64, AutoSwapprError> {
+        let token_felt = resolve_token(token)?;
+        let erc20_contract = self.erc20(token_felt);
 
-        let from_amount_uint256 = Uint256::from_u128(token_from_amount);
-        let to_min_amount_uint256 = Uint256::from_u128(token_to_min_amount);
+        let (balance, decimals) = futures::try_join!(
+            erc20_contract.balance_of(&*self.provider, self.account.address()),
+            erc20_contract.decimals(&*self.provider),
+        )?;
 
-        let tx_hash = self
-            .autoswappr_contract
-            .avnu_swap(
-                &self.account,
-                protocol_swapper_felt,
-                token_from_felt,
-                crate::contracts::conversions::uint256_to_starknet(&from_amount_uint256),
-                token_to_felt,
-                crate::contracts::conversions::uint256_to_starknet(&to_min_amount_uint256),
-                beneficiary_felt,
-                integrator_fee_amount_bps,
-                integrator_fee_recipient_felt,
-                routes,
-            )
-            .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
+        let usd = self
+            .get_token_amount_in_usd_formatted(token_felt, u128::try_from(balance)?, decimals)
+            .await?;
+
+        Ok(display_to_f64(&usd))
+    }
+
+    /// Get token information
+    pub async fn get_token_info(
+        &self,
+        token_address: impl IntoAddress,
+    ) -> Result<(String, String, u8), AutoSwapprError> {
+        let token_felt = token_address.into_address()?.felt();
+
+        let erc20_contract = self.erc20(token_felt);
+
+        // One round trip instead of three
+        Ok(futures::try_join!(
+            erc20_contract.name(&*self.provider),
+            erc20_contract.symbol(&*self.provider),
+            erc20_contract.decimals(&*self.provider),
+        )?)
+    }
+
+    /// Get the name, symbol and decimals of each token (hex address or known symbol),
+    /// keeping the input order.
+    ///
+    /// Starknet has no canonical multicall aggregator for reads, so tokens are fanned out
+    /// with up to `concurrency_limit` in flight, each reading its three fields concurrently.
+    /// Fails on the first token that can't be read.
+    pub async fn get_token_infos(
+        &self,
+        tokens: &[&str],
+    ) -> Result<Vec<TokenInfo>, AutoSwapprError> {
+        stream::iter(tokens)
+            .map(|token| async move {
+                let token_felt = resolve_token(*token)?;
+                let (name, symbol, decimals) = self.get_token_info(token_felt).await?;
+                Ok(TokenInfo {
+                    address: format!("{:#x}", token_felt),
+                    symbol,
+                    name,
+                    decimals,
+                })
+            })
+            .buffered(self.concurrency_limit)
+            .try_collect()
+            .await
+    }
+
+    /// Check whether a token pair can be routed before requesting a quote.
+    ///
+    /// The Ekubo core contract is probed for an initialized pool at each standard fee tier,
+    /// stopping at the first one found.
+    pub async fn is_pair_routable(
+        &self,
+        token_in: impl IntoAddress,
+        token_out: impl IntoAddress,
+    ) -> Result<bool, AutoSwapprError> {
+        let token_in_felt = token_in.into_address()?.felt();
+
+        let token_out_felt = token_out.into_address()?.felt();
+
+        if token_in_felt == token_out_felt {
+            return Ok(false);
+        }
+
+        let (token0, token1) = if token_in_felt < token_out_felt {
+            (token_in_felt, token_out_felt)
+        } else {
+            (token_out_felt, token_in_felt)
+        };
+
+        for (fee, tick_spacing) in ekubo_abi::FEE_TIERS {
+            let pool_key = PoolKey {
+                token0,
+                token1,
+                fee,
+                tick_spacing,
+                extension: Felt::ZERO,
+            };
+
+            let price = self.get_pool_price(&pool_key).await?;
+
+            if price.sqrt_ratio != Uint256::default() {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Find the Ekubo pool with the deepest liquidity between two tokens.
+    ///
+    /// Each standard fee tier is probed on the Ekubo core contract; uninitialized pools are
+    /// skipped. Fails with `InvalidPoolConfig` if the pair has no pool at any tier.
+    pub async fn find_pool(
+        &self,
+        token0: impl IntoAddress,
+        token1: impl IntoAddress,
+    ) -> Result<PoolKey, AutoSwapprError> {
+        self.discover_pool(token0, token1, &ekubo_abi::FEE_TIERS)
+            .await
+    }
+
+    /// Resolve the deepest Ekubo pool (see [`find_pool`](Self::find_pool)), `token_in`'s
+    /// decimals and the swap direction once, for repeated swaps of `token_in` into
+    /// `token_out` with [`SwapPlan::execute`].
+    pub async fn prepare_swap(
+        &self,
+        token_in: impl Into<TokenRef>,
+        token_out: impl Into<TokenRef>,
+    ) -> Result<SwapPlan, AutoSwapprError> {
+        let token_in = resolve_token(token_in)?;
+        let token_out = resolve_token(token_out)?;
+
+        let pool_key = self.find_pool(token_in, token_out).await?;
+        let decimals = self.token_decimals(token_in).await?;
+
+        Ok(SwapPlan {
+            token_in,
+            token_out,
+            is_token1: pool_key.token1 == token_in,
+            pool_key,
+            decimals,
+        })
+    }
+
+    /// Find the Ekubo pool between two tokens at a specific standard fee tier
+    pub async fn find_pool_with_fee(
+        &self,
+        token0: impl IntoAddress,
+        token1: impl IntoAddress,
+        fee: u128,
+    ) -> Result<PoolKey, AutoSwapprError> {
+        let tier = ekubo_abi::FEE_TIERS
+            .into_iter()
+            .find(|(tier_fee, _)| *tier_fee == fee)
+            .ok_or_else(|| AutoSwapprError::InvalidInput {
+                details: format!("{} is not a standard Ekubo fee tier", fee),
+            })?;
+
+        self.discover_pool(token0, token1, &[tier]).await
+    }
+
+    async fn discover_pool(
+        &self,
+        token0: impl IntoAddress,
+        token1: impl IntoAddress,
+        tiers: &[(u128, u128)],
+    ) -> Result<PoolKey, AutoSwapprError> {
+        let token_a = token0.into_address()?.felt();
+        let token_b = token1.into_address()?.felt();
+
+        if token_a == token_b {
+            return Err(AutoSwapprError::InvalidPoolConfig {
+                reason: "Pool tokens must differ".to_string(),
+            });
+        }
+
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        let ekubo_core = self.ekubo_core()?;
+        let mut best: Option<(PoolKey, u128)> = None;
+
+        for &(fee, tick_spacing) in tiers {
+            let pool_key = PoolKey {
+                token0,
+                token1,
+                fee,
+                tick_spacing,
+                extension: Felt::ZERO,
+            };
+
+            let price = ekubo_core
+                .get_pool_price(&*self.provider, &pool_key)
+                .await?;
+            if price.sqrt_ratio == Uint256::default() {
+                continue;
+            }
+
+            let liquidity = ekubo_core
+                .get_pool_liquidity(&*self.provider, &pool_key)
+                .await?;
+            if best
+                .as_ref()
+                .is_none_or(|(_, deepest)| liquidity > *deepest)
+            {
+                best = Some((pool_key, liquidity));
+            }
+        }
+
+        best.map(|(pool_key, _)| pool_key)
+            .ok_or_else(|| AutoSwapprError::InvalidPoolConfig {
+                reason: format!("No Ekubo pool found for {:#x} / {:#x}", token0, token1),
+            })
+    }
+
+    /// Get the current Ekubo price of a pool, usable as the quote for a guarded swap
+    pub async fn get_pool_price(&self, pool_key: &PoolKey) -> Result<PoolPrice, AutoSwapprError> {
+        let ekubo_core = self.ekubo_core()?;
+
+        Ok(ekubo_core.get_pool_price(&*self.provider, pool_key).await?)
+    }
+
+    /// Get the current sqrt ratio of a pool, as a Q128 fixed-point number, for computing
+    /// slippage bounds or price limits client-side.
+    ///
+    /// Unlike [`get_pool_price`](Self::get_pool_price), which reports uninitialized pools
+    /// with a zero ratio, fails with `InvalidPoolConfig` if the pool does not exist.
+    pub async fn get_pool_sqrt_ratio(
+        &self,
+        pool_key: &PoolKey,
+    ) -> Result<Uint256, AutoSwapprError> {
+        let price = self.get_pool_price(pool_key).await?;
+        if price.sqrt_ratio == Uint256::default() {
+            return Err(AutoSwapprError::InvalidPoolConfig {
+                reason: format!(
+                    "No Ekubo pool for {:#x} / {:#x} at fee {} and tick spacing {}",
+                    pool_key.token0, pool_key.token1, pool_key.fee, pool_key.tick_spacing
+                ),
+            });
+        }
+
+        Ok(price.sqrt_ratio)
+    }
+
+    /// Estimate by how many percent swapping `amount` of the input token (token1 if
+    /// `is_token1`) lowers its price in the pool, from 0 to 100.
+    ///
+    /// Simulated from the pool's current sqrt ratio and active liquidity, ignoring the pool
+    /// fee and tick crossings, so large swaps through concentrated liquidity may move the
+    /// price further. Fails with `InvalidPoolConfig` if the pool does not exist.
+    pub async fn estimate_price_impact(
+        &self,
+        pool_key: &PoolKey,
+        amount: u128,
+        is_token1: bool,
+    ) -> Result<f64, AutoSwapprError> {
+        let (sqrt_ratio, liquidity) =
+            futures::try_join!(self.get_pool_sqrt_ratio(pool_key), async {
+                Ok(self
+                    .ekubo_core()?
+                    .get_pool_liquidity(&*self.provider, pool_key)
+                    .await?)
+            },)?;
+
+        Ok(math::price_impact_percent(
+            sqrt_ratio, liquidity, amount, is_token1,
+        ))
+    }
+
+    /// Fail with `SwapFailed` if the pool price has moved more than `max_price_deviation_bps`
+    /// away from `quoted` since it was fetched.
+    pub async fn ensure_price_within(
+        &self,
+        pool_key: &PoolKey,
+        quoted: &PoolPrice,
+        max_price_deviation_bps: u32,
+    ) -> Result<(), AutoSwapprError> {
+        let current = self.get_pool_price(pool_key).await?;
+
+        if price_deviation_bps(quoted.sqrt_ratio, current.sqrt_ratio)
+            > max_price_deviation_bps as f64
+        {
+            return Err(AutoSwapprError::SwapFailed {
+                reason: "price moved beyond tolerance".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Fail with `SwapFailed` if the latest block is already past `deadline` (unix seconds).
+    ///
+    /// The AutoSwappr contract has no on-chain deadline, so this is checked right before submitting.
+    pub async fn ensure_deadline(&self, deadline: Option<u64>) -> Result<(), AutoSwapprError> {
+        let Some(deadline) = deadline else {
+            return Ok(());
+        };
+
+        let block = self
+            .provider
+            .get_block_with_tx_hashes(BlockId::Tag(BlockTag::Latest))
+            .await
+            .map_err(ContractError::from)?;
+
+        let timestamp = match block {
+            MaybePreConfirmedBlockWithTxHashes::Block(block) => block.timestamp,
+            MaybePreConfirmedBlockWithTxHashes::PreConfirmedBlock(block) => block.timestamp,
+        };
+
+        if timestamp > deadline {
+            return Err(AutoSwapprError::SwapFailed {
+                reason: "deadline passed".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Execute ekubo manual swap, rejecting it before submission once `deadline` has passed
+    pub async fn execute_ekubo_manual_swap(
+        &self,
+        swap_data: SwapData,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        self.ensure_deadline(deadline).await?;
+
+        let (token_in, token_out) = swap_data.tokens();
+        let amount = Uint256::from(swap_data.params.amount.mag);
+        let call = self
+            .autoswappr_contract
+            .ekubo_manual_swap_call(&swap_data)?;
+        let tx_hash = self.submit(vec![call]).await?;
+        trace_event!(info, tx_hash = %format!("{:#x}", tx_hash), "ekubo manual swap submitted");
+        self.record_swap(tx_hash, token_in, token_out, amount);
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Execute ekubo swap, rejecting it before submission once `deadline` has passed.
+    ///
+    /// The amount in `swap_data` is in base units; see
+    /// [`swap_whole_tokens`](Self::swap_whole_tokens) to swap a human amount.
+    pub async fn execute_ekubo_swap(
+        &self,
+        swap_data: SwapData,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        self.ensure_deadline(deadline).await?;
+
+        let (token_in, token_out) = swap_data.tokens();
+        let amount = Uint256::from(swap_data.params.amount.mag);
+        let call = self.autoswappr_contract.ekubo_swap_call(&swap_data)?;
+        let tx_hash = self.submit(vec![call]).await?;
+        trace_event!(info, tx_hash = %format!("{:#x}", tx_hash), "ekubo swap submitted");
+        self.record_swap(tx_hash, token_in, token_out, amount);
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Execute ekubo swap and wait for its receipt, returning the transaction hash once the
+    /// swap has executed on-chain.
+    ///
+    /// Fails with `SwapFailed` carrying the revert reason if the transaction was accepted but
+    /// its execution reverted, or if it was dropped in a reorg. In dry-run mode nothing is
+    /// sent, so there is no receipt to wait for.
+    pub async fn execute_ekubo_swap_with_result(
+        &self,
+        swap_data: SwapData,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        let tx_hash = self.execute_ekubo_swap(swap_data, deadline).await?;
+        if self.dry_run {
+            return Ok(tx_hash);
+        }
+
+        match self.wait_for_transaction(&tx_hash, 0).await? {
+            TxStatus::Confirmed { .. } => Ok(tx_hash),
+            TxStatus::Reverted(reason) => Err(AutoSwapprError::SwapFailed { reason }),
+            TxStatus::Reorged => Err(AutoSwapprError::SwapFailed {
+                reason: "transaction was dropped in a reorg".to_string(),
+            }),
+        }
+    }
+
+    /// Execute ekubo swap and deliver the output to `beneficiary` instead of the caller.
+    ///
+    /// The contract's Ekubo entrypoints always pay `swap_data.caller`, so for a different
+    /// beneficiary an ERC20 transfer of `min_amount_out` of the output token is appended to
+    /// the same transaction. The exact output is only known once the swap executes; anything
+    /// received above `min_amount_out` stays with the caller, and the whole batch reverts if
+    /// less is received. With no beneficiary, or the caller itself, this is a plain
+    /// [`execute_ekubo_swap`](Self::execute_ekubo_swap).
+    pub async fn execute_ekubo_swap_to(
+        &self,
+        swap_data: SwapData,
+        beneficiary: Option<Felt>,
+        min_amount_out: u128,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        let beneficiary = match beneficiary {
+            Some(beneficiary) if beneficiary != swap_data.caller => beneficiary,
+            _ => return self.execute_ekubo_swap(swap_data, deadline).await,
+        };
+        if min_amount_out == 0 {
+            return Err(AutoSwapprError::ZeroAmount);
+        }
+        self.ensure_deadline(deadline).await?;
+
+        let (token_in, token_out) = swap_data.tokens();
+        let amount = Uint256::from(swap_data.params.amount.mag);
+        let calls = vec![
+            self.autoswappr_contract.ekubo_swap_call(&swap_data)?,
+            self.erc20(token_out)
+                .transfer_call(beneficiary, Uint256::from_u128(min_amount_out))?,
+        ];
+        let tx_hash = self.submit(calls).await?;
+        trace_event!(
+            info,
+            beneficiary = %format!("{:#x}", beneficiary),
+            tx_hash = %format!("{:#x}", tx_hash),
+            "ekubo swap to beneficiary submitted"
+        );
+        self.record_swap(tx_hash, token_in, token_out, amount);
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Execute ekubo swap only after a fee estimate of the same call succeeds.
+    ///
+    /// Estimation runs the transaction without committing it, so a swap that would revert
+    /// fails here with `SwapFailed` instead of costing fees.
+    pub async fn execute_ekubo_swap_simulated(
+        &self,
+        swap_data: SwapData,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        self.autoswappr_contract
+            .estimate_ekubo_swap_fee(&self.account, &swap_data)
+            .await
+            .map_err(|e| AutoSwapprError::SwapFailed {
+                reason: format!("simulation failed: {}", e),
             })?;
 
-        Ok(tx_hash.to_string())
+        self.execute_ekubo_swap(swap_data, deadline).await
+    }
+
+    /// Execute ekubo swap, re-reading the pool price just before sending and aborting if it
+    /// has moved more than `max_price_deviation_bps` from `quoted`
+    pub async fn execute_ekubo_swap_with_price_guard(
+        &self,
+        swap_data: SwapData,
+        quoted: &PoolPrice,
+        max_price_deviation_bps: u32,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        self.ensure_price_within(&swap_data.pool_key, quoted, max_price_deviation_bps)
+            .await?;
+
+        self.execute_ekubo_swap(swap_data, deadline).await
+    }
+
+    /// Compute `percent_bps` (1 bps = 0.01%) of the account's `token` balance
+    pub async fn percent_of_balance(
+        &self,
+        token: impl IntoAddress,
+        percent_bps: u16,
+    ) -> Result<u128, AutoSwapprError> {
+        if percent_bps > 10_000 {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!("Percentage {} bps exceeds 10000", percent_bps),
+            });
+        }
+
+        let balance = self.get_token_balance(token).await?;
+        let bps = percent_bps as u128;
+
+        // Split the multiplication so large balances can't overflow
+        Ok(balance / 10_000 * bps + balance % 10_000 * bps / 10_000)
+    }
+
+    /// Swap `percent_bps` of the account's token0 balance into token1 through Ekubo
+    pub async fn execute_ekubo_swap_percent(
+        &self,
+        token0: impl Into<TokenRef>,
+        token1: impl Into<TokenRef>,
+        percent_bps: u16,
+    ) -> Result<String, AutoSwapprError> {
+        let token0 = Address::from(resolve_token(token0)?);
+        let token1 = Address::from(resolve_token(token1)?);
+
+        let amount = self.percent_of_balance(token0, percent_bps).await?;
+
+        let swap_data = SwapData::builder()
+            .amount(amount)
+            .pool(token0.felt(), token1.felt())
+            .caller(self.account.address())
+            .build()?;
+
+        self.execute_ekubo_swap(swap_data, None).await
+    }
+
+    /// Swap `whole_amount` of token0 into token1 through Ekubo, e.g. `1.5` ETH.
+    ///
+    /// The amount is scaled to base units by token0's on-chain decimals, which are cached
+    /// per client. Negative, non-finite and over-precise amounts are rejected.
+    pub async fn swap_whole_tokens(
+        &self,
+        token0: impl Into<TokenRef>,
+        token1: impl Into<TokenRef>,
+        whole_amount: f64,
+    ) -> Result<String, AutoSwapprError> {
+        let token0 = Address::from(resolve_token(token0)?);
+        let token1 = Address::from(resolve_token(token1)?);
+
+        if !whole_amount.is_finite() || whole_amount < 0.0 {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!("Invalid amount '{}'", whole_amount),
+            });
+        }
+
+        let decimals = self.token_decimals(token0.felt()).await?;
+        // f64 Display never uses exponent notation, so this parses as a plain decimal
+        let amount = from_display(&whole_amount.to_string(), decimals)?;
+
+        let swap_data = SwapData::builder()
+            .amount(amount)
+            .pool(token0.felt(), token1.felt())
+            .caller(self.account.address())
+            .build()?;
+
+        self.execute_ekubo_swap(swap_data, None).await
+    }
+
+    // Token decimals never change, so each token is read once per client
+    async fn token_decimals(&self, token: Felt) -> Result<u8, AutoSwapprError> {
+        if let Some(decimals) = self.decimals.lock().unwrap().get(&token) {
+            return Ok(*decimals);
+        }
+
+        let decimals = self.erc20(token).decimals(&*self.provider).await?;
+        self.decimals.lock().unwrap().insert(token, decimals);
+
+        Ok(decimals)
+    }
+
+    /// Buy exactly `desired_out` of token1 with token0 through Ekubo, spending at most
+    /// `max_in` of token0 (both in base units).
+    ///
+    /// The amount is negative, which Ekubo reads as exact output, and the price limit is set
+    /// so no unit is bought at a worse rate than `desired_out / max_in`. If the pool can't
+    /// fill the whole amount within that limit, the swap stops early instead of spending more.
+    pub async fn ekubo_swap_exact_output(
+        &self,
+        token0: impl Into<TokenRef>,
+        token1: impl Into<TokenRef>,
+        desired_out: u128,
+        max_in: u128,
+    ) -> Result<String, AutoSwapprError> {
+        let token0 = Address::from(resolve_token(token0)?);
+        let token1 = Address::from(resolve_token(token1)?);
+
+        let (_, input_is_token1) = PoolKey::for_swap(token0.felt(), token1.felt());
+        let sqrt_ratio_limit =
+            sqrt_ratio_limit_for_max_input(desired_out, max_in, input_is_token1)?;
+        let swap_data = SwapData::builder()
+            .amount(desired_out)
+            .sign(true)
+            .pool(token0.felt(), token1.felt())
+            .is_token1(true)
+            .sqrt_ratio_limit(sqrt_ratio_limit)
+            .caller(self.account.address())
+            .build()?;
+
+        self.execute_ekubo_swap(swap_data, None).await
+    }
+
+    /// Estimate the network fee of an ekubo swap without submitting it
+    pub async fn estimate_swap_fee(
+        &self,
+        swap_data: &SwapData,
+    ) -> Result<FeeEstimate, AutoSwapprError> {
+        Ok(self
+            .autoswappr_contract
+            .estimate_ekubo_swap_fee(&self.account, swap_data)
+            .await?)
+    }
+
+    /// Estimate the network fee of an ekubo swap in USD, priced through the AutoSwappr oracle.
+    ///
+    /// `fee_token` is the token the fee is paid in (STRK for v3 transactions) and must have
+    /// an oracle feed on the contract.
+    pub async fn estimate_swap_fee_usd(
+        &self,
+        swap_data: &SwapData,
+        fee_token: impl IntoAddress,
+    ) -> Result<f64, AutoSwapprError> {
+        let fee_token = fee_token.into_address()?;
+
+        let (supported, _) = self
+            .autoswappr_contract
+            .get_token_from_status_and_value(&*self.provider, fee_token.felt())
+            .await?;
+        if !supported {
+            return Err(AutoSwapprError::UnsupportedToken {
+                token: fee_token.to_string(),
+            });
+        }
+
+        let estimate = self.estimate_swap_fee(swap_data).await?;
+        let decimals = self
+            .erc20(fee_token.felt())
+            .decimals(&*self.provider)
+            .await?;
+
+        let usd = self
+            .get_token_amount_in_usd_formatted(fee_token, estimate.overall_fee, decimals)
+            .await?;
+
+        Ok(display_to_f64(&usd))
+    }
+
+    /// Preview the fee the AutoSwappr contract charges on swapping `amount` base units of
+    /// `token` (hex address or known symbol), in base units of that token, rounded down as
+    /// the contract does.
+    ///
+    /// Fails with [`AutoSwapprError::UnsupportedToken`] if the contract doesn't accept
+    /// `token`. Only [`FeeType::Percentage`] can be previewed: `contract_parameters` does not
+    /// expose the flat amount of a fixed fee, so [`FeeType::Fixed`] fails with
+    /// `InvalidInput`.
+    pub async fn preview_fee(&self, amount: u128, token: &str) -> Result<u128, AutoSwapprError> {
+        let token_felt = resolve_token(token)?;
+        let ((supported, _), parameters) = futures::try_join!(
+            async {
+                Ok::<_, AutoSwapprError>(
+                    self.autoswappr_contract
+                        .get_token_from_status_and_value(&*self.provider, token_felt)
+                        .await?,
+                )
+            },
+            self.get_contract_parameters(),
+        )?;
+        if !supported {
+            return Err(AutoSwapprError::UnsupportedToken {
+                token: token.to_string(),
+            });
+        }
+
+        let fee = match parameters.fee_type {
+            FeeType::Percentage => {
+                math::apply_percentage_fee(amount, parameters.percentage_fee, false)
+            }
+            FeeType::Fixed => {
+                return Err(AutoSwapprError::InvalidInput {
+                    details: "the fixed fee amount is not exposed by contract_parameters, so \
+                              it can't be previewed"
+                        .to_string(),
+                });
+            }
+        };
+        trace_event!(debug, token = %token, amount, fee, "fee previewed");
+
+        Ok(fee)
+    }
+
+    /// Execute AVNU swap
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_avnu_swap(
+        &self,
+        protocol_swapper: impl IntoAddress,
+        token_from_address: impl IntoAddress,
+        token_from_amount: u128,
+        token_to_address: impl IntoAddress,
+        token_to_min_amount: u128,
+        beneficiary: impl IntoAddress,
+        integrator_fee_amount_bps: u128,
+        integrator_fee_recipient: impl IntoAddress,
+        routes: Vec<crate::contracts::Route>,
+    ) -> Result<String, AutoSwapprError> {
+        let protocol_swapper_felt = protocol_swapper.into_address()?.felt();
+
+        let token_from_felt = token_from_address.into_address()?.felt();
+
+        let token_to_felt = token_to_address.into_address()?.felt();
+
+        let beneficiary_felt = beneficiary.into_address()?.felt();
+
+        let integrator_fee_recipient_felt = integrator_fee_recipient.into_address()?.felt();
+
+        let from_amount_uint256 = Uint256::from_u128(token_from_amount);
+        let to_min_amount_uint256 = Uint256::from_u128(token_to_min_amount);
+
+        let call = self.autoswappr_contract.avnu_swap_call(
+            protocol_swapper_felt,
+            token_from_felt,
+            crate::contracts::conversions::uint256_to_starknet(&from_amount_uint256),
+            token_to_felt,
+            crate::contracts::conversions::uint256_to_starknet(&to_min_amount_uint256),
+            beneficiary_felt,
+            integrator_fee_amount_bps,
+            integrator_fee_recipient_felt,
+            &routes,
+        )?;
+        let tx_hash = self.submit(vec![call]).await?;
+        trace_event!(info, tx_hash = %format!("{:#x}", tx_hash), "avnu swap submitted");
+        self.record_swap(tx_hash, token_from_felt, token_to_felt, from_amount_uint256);
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Like [`execute_avnu_swap`](Self::execute_avnu_swap), but derives the minimum output
+    /// from a quoted `expected_out` and a slippage tolerance in basis points, rounded down
+    /// as in [`math::min_received`].
+    ///
+    /// Fails with `InvalidInput` if `slippage_bps` exceeds 10 000 (100%).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_avnu_swap_with_slippage(
+        &self,
+        protocol_swapper: impl IntoAddress,
+        token_from_address: impl IntoAddress,
+        token_from_amount: u128,
+        token_to_address: impl IntoAddress,
+        expected_out: u128,
+        slippage_bps: u16,
+        beneficiary: impl IntoAddress,
+        integrator_fee_amount_bps: u128,
+        integrator_fee_recipient: impl IntoAddress,
+        routes: Vec<crate::contracts::Route>,
+    ) -> Result<String, AutoSwapprError> {
+        let token_to_min_amount = math::min_received(expected_out, slippage_bps)?;
+
+        self.execute_avnu_swap(
+            protocol_swapper,
+            token_from_address,
+            token_from_amount,
+            token_to_address,
+            token_to_min_amount,
+            beneficiary,
+            integrator_fee_amount_bps,
+            integrator_fee_recipient,
+            routes,
+        )
+        .await
+    }
+
+    /// Execute Fibrous swap
+    pub async fn execute_fibrous_swap(
+        &self,
+        protocol_swapper: impl IntoAddress,
+        beneficiary: impl IntoAddress,
+        route_params: crate::contracts::RouteParams,
+        swap_params: Vec<crate::contracts::SwapParams>,
+    ) -> Result<String, AutoSwapprError> {
+        let protocol_swapper_felt = protocol_swapper.into_address()?.felt();
+
+        let beneficiary_felt = beneficiary.into_address()?.felt();
+
+        let (token_in, token_out, amount) = (
+            route_params.token_in,
+            route_params.token_out,
+            route_params.amount_in,
+        );
+        let call = self.autoswappr_contract.fibrous_swap_call(
+            &route_params,
+            &swap_params,
+            protocol_swapper_felt,
+            beneficiary_felt,
+        )?;
+        let tx_hash = self.submit(vec![call]).await?;
+        trace_event!(info, tx_hash = %format!("{:#x}", tx_hash), "fibrous swap submitted");
+        self.record_swap(tx_hash, token_in, token_out, amount);
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Check whether `token` (hex address or known symbol such as `"ETH"`) is a supported
+    /// source token, returning its oracle feed value as hex
+    pub async fn token_from_status(&self, token: &str) -> Result<(bool, String), AutoSwapprError> {
+        let token_felt = resolve_token(token)?;
+
+        let (supported, feed) = self
+            .autoswappr_contract
+            .get_token_from_status_and_value(&*self.provider, token_felt)
+            .await?;
+
+        Ok((supported, format!("{:#x}", Felt::try_from(feed)?)))
+    }
+
+    /// List the built-in tokens (see [`TokenAddress`]) the contract accepts as swap sources.
+    ///
+    /// The contract has no getter enumerating supported tokens, so this checks each known
+    /// token with `get_token_from_status_and_value`, one call at a time, and keeps the
+    /// supported ones in [`TokenAddress`] order. Tokens added on-chain that the SDK does
+    /// not know about are not listed.
+    pub async fn supported_source_tokens(&self) -> Result<Vec<Felt>, AutoSwapprError> {
+        let mut supported_tokens = Vec::new();
+        for token in TokenAddress::new().tokens {
+            let (supported, _) = self
+                .autoswappr_contract
+                .get_token_from_status_and_value(&*self.provider, token.address)
+                .await?;
+            if supported {
+                supported_tokens.push(token.address);
+            }
+        }
+
+        Ok(supported_tokens)
+    }
+
+    /// Get the raw oracle price of `token` (hex address or known symbol) as
+    /// `(price, last_updated_timestamp)`.
+    ///
+    /// Reads the Pragma-style oracle from the contract parameters using the token's feed id.
+    /// The timestamp is returned so callers can reject stale prices.
+    pub async fn get_oracle_price(&self, token: &str) -> Result<(u128, u64), AutoSwapprError> {
+        let token_felt = resolve_token(token)?;
+
+        let (parameters, (supported, feed_id)) = futures::try_join!(
+            self.autoswappr_contract
+                .get_contract_parameters(&*self.provider),
+            self.autoswappr_contract
+                .get_token_from_status_and_value(&*self.provider, token_felt),
+        )?;
+        if !supported {
+            return Err(AutoSwapprError::UnsupportedToken {
+                token: token.to_string(),
+            });
+        }
+
+        let feed_id = Felt::try_from(feed_id)?;
+        let oracle_address = Address::from_hex(&parameters.oracle_address)?;
+        let oracle = OracleContract::new(oracle_address.felt()).with_block_id(self.block_id());
+        let (price, timestamp) = oracle.get_spot_median(&*self.provider, feed_id).await?;
+        trace_event!(debug, token = %format!("{:#x}", token_felt), price, timestamp, "oracle price read");
+
+        Ok((price, timestamp))
     }
 
-    /// Execute Fibrous swap
-    pub async fn execute_fibrous_swap(
-        &self,
-        protocol_swapper: &str,
-        beneficiary: &str,
-        route_params: crate::contracts::RouteParams,
-        swap_params: Vec<crate::contracts::SwapParams>,
-    ) -> Result<String, AutoSwapprError> {
-        let protocol_swapper_felt =
-            Felt::from_hex(protocol_swapper).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid protocol swapper address: {}", e),
-            })?;
+    /// Check whether the configured account is the AutoSwappr contract owner
+    pub async fn is_owner(&self) -> Result<bool, AutoSwapprError> {
+        let parameters = self.get_contract_parameters().await?;
+        let owner =
+            parameters
+                .owner
+                .parse::<Felt>()
+                .map_err(|e| AutoSwapprError::ContractError {
+                    message: format!("Invalid owner address: {}", e),
+                })?;
+
+        Ok(owner == self.account.address())
+    }
+
+    async fn ensure_owner(&self, precheck_owner: bool) -> Result<(), AutoSwapprError> {
+        if precheck_owner && !self.is_owner().await? {
+            return Err(AutoSwapprError::Other {
+                message: "not contract owner".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Set the contract fee type (owner only).
+    ///
+    /// With `precheck_owner`, non-owner accounts are rejected before a transaction is sent.
+    pub async fn set_fee_type(
+        &self,
+        fee_type: FeeType,
+        percentage_fee: u16,
+        precheck_owner: bool,
+    ) -> Result<String, AutoSwapprError> {
+        self.ensure_owner(precheck_owner).await?;
+
+        let call = self
+            .autoswappr_contract
+            .set_fee_type_call(fee_type, percentage_fee)?;
+        let tx_hash = self.submit(vec![call]).await?;
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Add a supported input token with its price feed (owner only).
+    ///
+    /// With `precheck_owner`, non-owner accounts are rejected before a transaction is sent.
+    pub async fn support_new_token_from(
+        &self,
+        token_from: impl IntoAddress,
+        feed_id: Felt,
+        precheck_owner: bool,
+    ) -> Result<String, AutoSwapprError> {
+        let token_felt = token_from.into_address()?.felt();
+
+        self.ensure_owner(precheck_owner).await?;
+
+        let call = self
+            .autoswappr_contract
+            .support_new_token_from_call(token_felt, feed_id)?;
+        let tx_hash = self.submit(vec![call]).await?;
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Like [`support_new_token_from`](Self::support_new_token_from), but returns `Ok(None)`
+    /// without sending a transaction if the token is already supported
+    pub async fn support_new_token_from_if_absent(
+        &self,
+        token_from: impl IntoAddress,
+        feed_id: Felt,
+        precheck_owner: bool,
+    ) -> Result<Option<String>, AutoSwapprError> {
+        let token_from = token_from.into_address()?;
+
+        let (supported, _) = self
+            .autoswappr_contract
+            .get_token_from_status_and_value(&*self.provider, token_from.felt())
+            .await?;
+        if supported {
+            return Ok(None);
+        }
+
+        self.support_new_token_from(token_from, feed_id, precheck_owner)
+            .await
+            .map(Some)
+    }
+
+    /// Remove a supported input token (owner only).
+    ///
+    /// With `precheck_owner`, non-owner accounts are rejected before a transaction is sent.
+    pub async fn remove_token_from(
+        &self,
+        token_from: impl IntoAddress,
+        precheck_owner: bool,
+    ) -> Result<String, AutoSwapprError> {
+        let token_felt = token_from.into_address()?.felt();
+
+        self.ensure_owner(precheck_owner).await?;
+
+        let call = self
+            .autoswappr_contract
+            .remove_token_from_call(token_felt)?;
+        let tx_hash = self.submit(vec![call]).await?;
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Execute a complete swap with approval.
+    ///
+    /// Same as [`execute_swap_atomic`](Self::execute_swap_atomic): the approval, when needed,
+    /// is sent in the same transaction as the swap.
+    pub async fn execute_swap_with_approval(
+        &self,
+        token_in: impl Into<TokenRef>,
+        swap_data: SwapData,
+        amount: u128,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        self.execute_swap_atomic(token_in, swap_data, amount, deadline)
+            .await
+    }
+
+    /// Approve `amount` of `token_in` to the contract and execute the ekubo manual swap in a
+    /// single multicall transaction.
+    ///
+    /// The approval is only included when the current allowance is below `amount`. Either
+    /// both calls succeed or the whole transaction reverts, so a failed swap never leaves a
+    /// dangling approval and only one fee is paid. Fails with `InsufficientAllowance` before
+    /// submitting if an exact-input swap spends more than the resulting allowance.
+    pub async fn execute_swap_atomic(
+        &self,
+        token_in: impl Into<TokenRef>,
+        swap_data: SwapData,
+        amount: u128,
+        deadline: Option<u64>,
+    ) -> Result<String, AutoSwapprError> {
+        let token_in = Address::from(resolve_token(token_in)?);
+        // Approving a token the swap doesn't spend would leave the swap unfunded
+        let (swap_in, swap_out) = swap_data.tokens();
+        if swap_in != token_in.felt() {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!(
+                    "swap spends {:#x}, but {} was given as the input token",
+                    swap_in, token_in
+                ),
+            });
+        }
+        // Don't spend gas if the swap can no longer go through
+        self.ensure_deadline(deadline).await?;
+
+        let contract = self.autoswappr_contract.address();
+        let allowance = self
+            .get_allowance(token_in, self.account.address(), contract)
+            .await?;
+
+        // An exact-input swap spends its whole amount, which a mis-scaled `amount` would not
+        // cover, so catch it here instead of paying for an on-chain revert
+        let approved = allowance.max(amount);
+        let swap_amount = swap_data.params.amount.mag;
+        if !swap_data.params.amount.sign && swap_amount > approved {
+            return Err(AutoSwapprError::InsufficientAllowance {
+                required: swap_amount.to_string(),
+                available: approved.to_string(),
+            });
+        }
+
+        let mut calls = Vec::with_capacity(2);
+        if allowance < amount {
+            calls.push(self.erc20(token_in.felt()).approve_call(
+                contract,
+                crate::contracts::conversions::uint256_to_starknet(&Uint256::from_u128(amount)),
+            )?);
+        }
+        calls.push(
+            self.autoswappr_contract
+                .ekubo_manual_swap_call(&swap_data)?,
+        );
+
+        let tx_hash = self.submit(calls).await?;
+        trace_event!(
+            info,
+            token = %token_in,
+            approved = allowance < amount,
+            tx_hash = %format!("{:#x}", tx_hash),
+            "atomic approve and swap submitted"
+        );
+        self.record_swap(
+            tx_hash,
+            swap_in,
+            swap_out,
+            Uint256::from(swap_data.params.amount.mag),
+        );
+
+        Ok(format_tx_hash(tx_hash))
+    }
+
+    /// Start keeping an in-memory record of every swap submitted by this client
+    pub fn enable_history(&self) {
+        self.history.lock().unwrap().get_or_insert_with(Vec::new);
+    }
+
+    /// Swaps submitted since [`enable_history`](Self::enable_history), oldest first.
+    ///
+    /// Returns a copy, as the history is shared behind a lock; empty if history is disabled.
+    pub fn swap_history(&self) -> Vec<SwapRecord> {
+        self.history.lock().unwrap().clone().unwrap_or_default()
+    }
+
+    fn record_swap(&self, tx_hash: Felt, token_in: Felt, token_out: Felt, amount: Uint256) {
+        // Dry runs send nothing, so there is no swap to record
+        if self.dry_run {
+            return;
+        }
+
+        if let Some(history) = self.history.lock().unwrap().as_mut() {
+            history.push(SwapRecord {
+                tx_hash: format_tx_hash(tx_hash),
+                token_in: format!("{:#x}", token_in),
+                token_out: format!("{:#x}", token_out),
+                amount,
+                timestamp: unix_timestamp(),
+            });
+        }
+    }
+
+    /// Wait until a transaction is at least `confirmations` blocks deep.
+    ///
+    /// The chain head must reach `inclusion block + confirmations`, so `0` returns as soon as a
+    /// receipt appears. The receipt is re-checked on every poll, and a transaction that vanishes
+    /// after inclusion is reported as [`TxStatus::Reorged`]. A reverted transaction is reported
+    /// as [`TxStatus::Reverted`] as soon as its receipt appears. Polls every
+    /// `retry_policy.delay`.
+    pub async fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        confirmations: u64,
+    ) -> Result<TxStatus, AutoSwapprError> {
+        let tx_hash = tx_hash
+            .parse::<Felt>()
+            .map_err(|e| AutoSwapprError::InvalidInput {
+                details: format!("Invalid transaction hash: {}", e),
+            })?;
+
+        let mut included = false;
+        for _ in 0..WAIT_MAX_POLLS {
+            match self.provider.get_transaction_receipt(tx_hash).await {
+                Ok(receipt) => {
+                    if let ExecutionResult::Reverted { reason } = receipt.receipt.execution_result()
+                    {
+                        return Ok(TxStatus::Reverted(reason.clone()));
+                    }
+
+                    included = true;
+                    let block_number = receipt.block.block_number();
+                    let head = self
+                        .provider
+                        .block_number()
+                        .await
+                        .map_err(ContractError::from)?;
+
+                    if head >= block_number + confirmations {
+                        return Ok(TxStatus::Confirmed { block_number });
+                    }
+                }
+                Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound))
+                    if included =>
+                {
+                    return Ok(TxStatus::Reorged);
+                }
+                Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {}
+                Err(e) => return Err(ContractError::from(e).into()),
+            }
+
+            sleep(self.retry_policy.delay).await;
+        }
+
+        Err(AutoSwapprError::NetworkError {
+            message: format!("Timed out waiting for transaction {:#x}", tx_hash),
+        })
+    }
+
+    /// Get account address
+    pub fn account_address(&self) -> String {
+        self.account.address().to_string()
+    }
+
+    /// Get contract address
+    pub fn contract_address(&self) -> String {
+        self.autoswappr_contract.address().to_string()
+    }
+
+    /// Get the configured RPC URL
+    pub fn rpc_url(&self) -> &str {
+        &self.config.rpc_url
+    }
+
+    /// Get the retry policy for RPC requests
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Get the maximum number of concurrent RPC requests issued by batch operations
+    pub fn concurrency_limit(&self) -> usize {
+        self.concurrency_limit
+    }
+
+    /// Get the underlying provider
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Get the underlying provider as an opaque `Provider` implementation.
+    ///
+    /// starknet-rs's `Provider` has generic methods and so can't be a `dyn` trait object;
+    /// this lets downstream code be written against `P: Provider` without naming the transport.
+    pub fn provider_impl(&self) -> &(impl Provider + Send + Sync + use<P>) {
+        &*self.provider
+    }
+
+    /// Get account reference for advanced usage
+    pub fn account(&self) -> &SingleOwnerAccount<P, LocalWallet> {
+        &self.account
+    }
+
+    /// Drop the locally tracked nonce so the next transaction fetches it from the provider.
+    ///
+    /// Needed after transactions are sent from the same account outside this client.
+    pub fn reset_nonce(&self) {
+        self.nonce.reset();
+    }
+
+    // Send `calls` as one transaction. In dry-run mode the transaction is only estimated and
+    // a hash of its calldata stands in for the transaction hash.
+    async fn submit(&self, calls: Vec<Call>) -> Result<Felt, AutoSwapprError> {
+        let signer = self.signer();
+        // Only dry runs need the stand-in hash
+        let dry_run_hash = self
+            .dry_run
+            .then(|| compute_hash_on_elements(&signer.encode_calls(&calls)));
+        // Reserved once here so the estimate and the send below share it
+        let nonce = signer.get_nonce().await.map_err(ContractError::from)?;
+        let execution = signer.execute_v3(calls).nonce(nonce);
+
+        // Unsent transactions hand their nonce back; `track` drops it if it was rejected
+        let result = self.send_execution(execution, dry_run_hash).await;
+        if self.dry_run || result.is_err() {
+            self.nonce.release(nonce);
+        }
+        result
+    }
+
+    async fn send_execution(
+        &self,
+        mut execution: ExecutionV3<'_, TrackedAccount<'_, SingleOwnerAccount<P, LocalWallet>>>,
+        dry_run_hash: Option<Felt>,
+    ) -> Result<Felt, AutoSwapprError> {
+        if self.dry_run || self.max_resource_bounds.is_some() {
+            let estimate = execution
+                .estimate_fee()
+                .await
+                .map_err(|e| ContractError::AccountError(e.to_string()))?;
+
+            if let Some(bounds) = &self.max_resource_bounds {
+                let l1_gas = capped_bound(
+                    "L1 gas",
+                    estimate.l1_gas_consumed,
+                    estimate.l1_gas_price,
+                    &bounds.l1_gas,
+                )?;
+                let l2_gas = capped_bound(
+                    "L2 gas",
+                    estimate.l2_gas_consumed,
+                    estimate.l2_gas_price,
+                    &bounds.l2_gas,
+                )?;
+                let l1_data_gas = capped_bound(
+                    "L1 data gas",
+                    estimate.l1_data_gas_consumed,
+                    estimate.l1_data_gas_price,
+                    &bounds.l1_data_gas,
+                )?;
+
+                execution = execution
+                    .l1_gas(l1_gas.max_amount)
+                    .l1_gas_price(l1_gas.max_price_per_unit)
+                    .l2_gas(l2_gas.max_amount)
+                    .l2_gas_price(l2_gas.max_price_per_unit)
+                    .l1_data_gas(l1_data_gas.max_amount)
+                    .l1_data_gas_price(l1_data_gas.max_price_per_unit);
+            }
+        }
+
+        if let Some(tx_hash) = dry_run_hash {
+            trace_event!(info, tx_hash = %format!("{:#x}", tx_hash), "dry run, transaction not sent");
+            return Ok(tx_hash);
+        }
+
+        let execution = self.nonce.track(
+            execution
+                .send()
+                .await
+                .map_err(|e| ContractError::AccountError(e.to_string())),
+        )?;
+
+        Ok(execution.transaction_hash)
+    }
+
+    // The account as used for writes, with its nonce served from the local tracker
+    fn signer(&self) -> TrackedAccount<'_, SingleOwnerAccount<P, LocalWallet>> {
+        TrackedAccount::new(&self.account, &self.nonce)
+    }
+
+    /// Get AutoSwappr contract reference for advanced usage
+    pub fn autoswappr_contract(&self) -> &AutoSwapprContract {
+        &self.autoswappr_contract
+    }
+
+    /// Build and estimate transactions without broadcasting them.
+    ///
+    /// Write methods then return a deterministic hash of the calldata instead of a real
+    /// transaction hash, which is neither recorded in the swap history nor waited on.
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Cap the resource bounds of every transaction at `bounds`, limiting the fee paid during
+    /// congestion.
+    ///
+    /// Each resource is sent with its estimate plus [`FEE_ESTIMATE_MARGIN_PERCENT`] headroom,
+    /// clamped to its cap. Transactions with a resource whose estimated amount or price is
+    /// already above its cap are rejected before signing.
+    pub fn with_max_resource_bounds(mut self, bounds: ResourceBoundsMapping) -> Self {
+        self.max_resource_bounds = Some(bounds);
+        self
+    }
+
+    /// Whether transactions are only estimated, not sent
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Run read calls against `block_id`, e.g. a past `BlockId::Number`, instead of the
+    /// latest block
+    pub fn with_block_id(mut self, block_id: BlockId) -> Self {
+        self.autoswappr_contract = self.autoswappr_contract.with_block_id(block_id);
+        self
+    }
+
+    /// Cache USD values from [`get_token_amount_in_usd`](Self::get_token_amount_in_usd) for
+    /// `ttl`, e.g. 30 seconds, since oracle prices rarely change between blocks.
+    ///
+    /// One price is cached per token, the USD value of one whole token, and other amounts
+    /// are scaled from it. Expired prices are dropped when a new one is cached. The cache is
+    /// off by default.
+    pub fn with_price_ttl(mut self, ttl: Duration) -> Self {
+        self.price_ttl = Some(ttl);
+        self
+    }
+
+    /// Drop all cached USD values, so the next lookups read the oracle again
+    pub fn clear_price_cache(&self) {
+        self.prices.lock().unwrap().clear();
+    }
+
+    /// Shut the client down, consuming it.
+    ///
+    /// Its handle on the provider is released right away, so the HTTP connection pool is
+    /// closed once no clone of this client still shares it. The client runs no background
+    /// tasks; the nonce tracker and the swap history and caches are dropped with it.
+    pub fn shutdown(self) {
+        trace_event!(
+            debug,
+            provider_refs = Arc::strong_count(&self.provider),
+            "shutting down client"
+        );
+        drop(self);
+    }
+
+    /// Get the block that read calls query
+    pub fn block_id(&self) -> BlockId {
+        self.autoswappr_contract.block_id()
+    }
+
+    fn erc20(&self, token: Felt) -> Erc20Contract {
+        Erc20Contract::new(token).with_block_id(self.block_id())
+    }
+
+    // The Ekubo core of the network the client signs for
+    fn ekubo_core(&self) -> Result<EkuboCoreContract, AutoSwapprError> {
+        let address = match self.account.chain_id() {
+            id if id == chain_id::MAINNET => addresses::mainnet::ekubo_core(),
+            id if id == chain_id::SEPOLIA => addresses::testnet::ekubo_core(),
+            id => {
+                return Err(AutoSwapprError::InvalidInput {
+                    details: format!("No known Ekubo core contract on chain {:#x}", id),
+                });
+            }
+        };
+
+        Ok(EkuboCoreContract::new(address).with_block_id(self.block_id()))
+    }
+}
+
+/// Detect which Starknet network an RPC endpoint serves from its chain id.
+///
+/// Unknown chains are returned as [`Network::OtherChain`] holding their chain id.
+pub async fn detect_network<P: Provider>(provider: &P) -> Result<Network, AutoSwapprError> {
+    let chain_id = provider.chain_id().await.map_err(ContractError::from)?;
+
+    Ok(if chain_id == chain_id::MAINNET {
+        Network::Mainnet
+    } else if chain_id == chain_id::SEPOLIA {
+        Network::Testnet
+    } else {
+        Network::OtherChain(chain_id)
+    })
+}
+
+/// Detect how an account expects `__execute__` calldata from its deployed class.
+///
+/// Cairo 0 classes, such as older Braavos and Argent accounts, take the legacy encoding and
+/// Sierra classes the new one. Fails if the account is not deployed.
+pub async fn detect_execution_encoding<P: Provider>(
+    provider: &P,
+    account_address: Felt,
+) -> Result<ExecutionEncoding, AutoSwapprError> {
+    let class = provider
+        .get_class_at(BlockId::Tag(BlockTag::Latest), account_address)
+        .await
+        .map_err(ContractError::from)?;
+
+    Ok(match class {
+        ContractClass::Legacy(_) => ExecutionEncoding::Legacy,
+        ContractClass::Sierra(_) => ExecutionEncoding::New,
+    })
+}
+
+// The encoding `account_type` implies, or the one detected from the account's class for
+// `AccountType::Auto`; legacy accounts take Cairo 0 calldata, and an undetectable class is
+// assumed to be a Cairo 1 account
+async fn resolve_execution_encoding<P: Provider>(
+    provider: &P,
+    account_type: AccountType,
+    account_address: &str,
+) -> Result<ExecutionEncoding, AutoSwapprError> {
+    if let Some(encoding) = account_type.execution_encoding() {
+        return Ok(encoding);
+    }
+
+    let address = account_address.into_address()?.felt();
+    Ok(match detect_execution_encoding(provider, address).await {
+        Ok(encoding) => encoding,
+        Err(_e) => {
+            trace_event!(warn, error = %_e, "account class detection failed, assuming Cairo 1");
+            ExecutionEncoding::New
+        }
+    })
+}
+
+/// Compare the RPC's JSON-RPC spec version with the supported one, skipping the check if
+/// the RPC can't be asked
+async fn check_spec_version<P: Provider>(
+    provider: &P,
+    strict: bool,
+) -> Result<(), AutoSwapprError> {
+    let actual = match provider.spec_version().await {
+        Ok(actual) => actual,
+        Err(_e) => {
+            trace_event!(warn, error = %_e, "spec version check failed, skipping it");
+            return Ok(());
+        }
+    };
+
+    if is_spec_version_supported(&actual) {
+        return Ok(());
+    }
+
+    let message = format!(
+        "RPC serves JSON-RPC spec version {}, but the SDK expects {}",
+        actual, SUPPORTED_SPEC_VERSION
+    );
+    if strict {
+        return Err(AutoSwapprError::ProviderError { message });
+    }
+    trace_event!(warn, "{}", message);
+
+    Ok(())
+}
+
+// Resolve a hex address or a built-in token symbol
+fn resolve_token(token: impl Into<TokenRef>) -> Result<Felt, AutoSwapprError> {
+    token.into().resolve(&TokenAddress::new())
+}
+
+// The bound sent for one resource: its estimate with `FEE_ESTIMATE_MARGIN_PERCENT` headroom,
+// clamped to `cap`, or an error if the estimate alone is already above `cap`
+fn capped_bound(
+    resource: &str,
+    consumed: u64,
+    price: u128,
+    cap: &ResourceBounds,
+) -> Result<ResourceBounds, AutoSwapprError> {
+    if consumed > cap.max_amount || price > cap.max_price_per_unit {
+        return Err(AutoSwapprError::InvalidInput {
+            details: format!(
+                "Estimated {} of {} at {} per unit exceeds the maximum of {} at {}",
+                resource, consumed, price, cap.max_amount, cap.max_price_per_unit
+            ),
+        });
+    }
+
+    let with_margin = |value: u128| value.saturating_mul(FEE_ESTIMATE_MARGIN_PERCENT) / 100;
+    Ok(ResourceBounds {
+        max_amount: u64::try_from(with_margin(consumed.into()))
+            .unwrap_or(u64::MAX)
+            .min(cap.max_amount),
+        max_price_per_unit: with_margin(price).min(cap.max_price_per_unit),
+    })
+}
+
+fn network_chain_id(network: &Network) -> Result<Felt, AutoSwapprError> {
+    match network {
+        Network::Mainnet => Ok(chain_id::MAINNET),
+        Network::Testnet => Ok(chain_id::SEPOLIA),
+        Network::OtherChain(chain_id) => Ok(*chain_id),
+        Network::Custom(url) => Err(AutoSwapprError::InvalidInput {
+            details: format!("RPC URL {} doesn't identify a chain", url),
+        }),
+    }
+}
+
+fn build_wallet(private_key: &str) -> Result<LocalWallet, AutoSwapprError> {
+    let private_key = Felt::from_hex(private_key).map_err(|e| AutoSwapprError::InvalidInput {
+        details: format!("Invalid private key: {}", e),
+    })?;
+
+    Ok(LocalWallet::from(SigningKey::from_secret_scalar(
+        private_key,
+    )))
+}
+
+fn build_account<P: Provider + Send + Sync + Clone>(
+    provider: &Arc<P>,
+    account_address: &str,
+    signer: LocalWallet,
+    chain_id: Felt,
+    encoding: ExecutionEncoding,
+) -> Result<SingleOwnerAccount<P, LocalWallet>, AutoSwapprError> {
+    // Parse account address
+    let account_address = account_address.into_address()?.felt();
+
+    Ok(SingleOwnerAccount::new(
+        (**provider).clone(),
+        signer,
+        account_address,
+        chain_id,
+        encoding,
+    ))
+}
+
+// Nearest f64 to a decimal string from `to_display`, which is always a valid number
+fn display_to_f64(display: &str) -> f64 {
+    display.parse().unwrap_or(f64::NAN)
+}
+
+// Deviation in bps between the prices implied by two Ekubo sqrt ratios (price = sqrt_ratio^2)
+fn price_deviation_bps(quoted: Uint256, current: Uint256) -> f64 {
+    let to_f64 = |value: Uint256| value.high as f64 * 2f64.powi(128) + value.low as f64;
+
+    let quoted = to_f64(quoted);
+    if quoted == 0.0 {
+        return f64::INFINITY;
+    }
+
+    let ratio = to_f64(current) / quoted;
+    (ratio * ratio - 1.0).abs() * 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::{erc20_abi, oracle_abi};
+    use crate::test_utils::{
+        MockRpc, MockTransport, block_json, fee_estimate_json, invoke_calldata, submitting_server,
+        test_config,
+    };
+    use crate::types::connector::{
+        AutoSwapprConfig, FeeType, I129, PoolKey, SwapData, SwapParameters, Uint256,
+    };
+
+    fn create_test_config() -> AutoSwapprConfig {
+        AutoSwapprConfig {
+            account_type: AccountType::Auto,
+            fee_token: FeeToken::Strk,
+            contract_address: "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b"
+                .to_string(),
+            rpc_url: "https://starknet-mainnet.public.blastapi.io/rpc/v0_9".to_string(),
+            account_address: "0x0234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
+                .to_string(),
+            private_key: "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
+                .to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_creation() {
+        let config = create_test_config();
+        let client = AutoSwapprClient::new(config).await;
+        // This should work now with real implementation
+        assert!(client.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_creation_with_invalid_address() {
+        let mut config = create_test_config();
+        config.account_address = "invalid_address".to_string();
+
+        let client = AutoSwapprClient::new(config).await;
+        assert!(client.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_creation_with_invalid_private_key() {
+        let mut config = create_test_config();
+        config.private_key = "invalid_key".to_string();
+
+        let client = AutoSwapprClient::new(config).await;
+        assert!(client.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_creation_with_invalid_contract_address() {
+        let mut config = create_test_config();
+        config.contract_address = "invalid_contract".to_string();
+
+        let client = AutoSwapprClient::new(config).await;
+        assert!(client.is_err());
+
+        // The field prime parses as a felt but wraps around to zero
+        let mut config = create_test_config();
+        config.contract_address =
+            "0x800000000000011000000000000000000000000000000000000000000000001".to_string();
+
+        let client = AutoSwapprClient::new(config).await;
+        assert!(matches!(client, Err(AutoSwapprError::InvalidInput { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_client_creation_with_invalid_rpc_url() {
+        let mut config = create_test_config();
+        config.rpc_url = "invalid_url".to_string();
+
+        let client = AutoSwapprClient::new(config).await;
+        assert!(client.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_contract_parameters() {
+        let config = create_test_config();
+        let contract = Felt::from_hex(&config.contract_address).unwrap();
+        let provider = MockRpc::new()
+            .on_call(
+                contract,
+                "contract_parameters",
+                contract_parameters_with_owner(Felt::from(5u8)),
+            )
+            .into_provider();
+        let client = AutoSwapprClient::from_provider(provider, config)
+            .await
+            .unwrap();
+
+        let params = client.get_contract_parameters().await.unwrap();
+        assert_eq!(params.oracle_address, Felt::from(4u8).to_string());
+        assert_eq!(params.owner, Felt::from(5u8).to_string());
+        assert_eq!(params.fee_type, FeeType::Percentage);
+        assert_eq!(params.percentage_fee, 100);
+    }
+
+    #[tokio::test]
+    async fn test_get_token_amount_in_usd() {
+        let config = create_test_config();
+        let client = AutoSwapprClient::new(config).await.unwrap();
+
+        let eth_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+        let amount = 1000000000000000000u128; // 1 ETH
+
+        let result = client.get_token_amount_in_usd(eth_address, amount).await;
+        // This will make a real contract call
+        assert!(result.is_ok() || result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_account_type_selects_encoding() {
+        let call = Call {
+            to: Felt::from(0x123u32),
+            selector: Felt::from(0x456u32),
+            calldata: vec![Felt::from(7u8)],
+        };
+        let client = |account_type| async move {
+            let config = AutoSwapprConfig {
+                account_type,
+                ..create_test_config()
+            };
+            AutoSwapprClient::from_provider(MockRpc::new().into_provider(), config)
+                .await
+                .unwrap()
+        };
+
+        // Legacy calldata lists the calls first and appends all their calldata at the end
+        let braavos = client(AccountType::Braavos).await;
+        assert_eq!(
+            braavos.account.encode_calls(std::slice::from_ref(&call)),
+            [1u16, 0x123, 0x456, 0, 1, 1, 7].map(Felt::from).to_vec()
+        );
+
+        let argent = client(AccountType::Argent).await;
+        assert_eq!(
+            argent.account.encode_calls(std::slice::from_ref(&call)),
+            [1u16, 0x123, 0x456, 1, 7].map(Felt::from).to_vec()
+        );
+
+        // An account class that can't be read falls back to the Cairo 1 encoding
+        let auto = client(AccountType::Auto).await;
+        assert_eq!(
+            auto.account.encode_calls(std::slice::from_ref(&call)),
+            argent.account.encode_calls(std::slice::from_ref(&call))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_account_detects_encoding() {
+        let legacy = Felt::from(0xabcu32);
+        let provider = MockRpc::new()
+            .on_method(
+                "starknet_getClassAt",
+                serde_json::json!({
+                    "program": "",
+                    "entry_points_by_type": {
+                        "CONSTRUCTOR": [],
+                        "EXTERNAL": [],
+                        "L1_HANDLER": []
+                    },
+                    "abi": []
+                }),
+            )
+            .into_provider();
+        let config = AutoSwapprConfig {
+            account_type: AccountType::Argent,
+            ..create_test_config()
+        };
+        let client = AutoSwapprClient::from_provider(provider, config)
+            .await
+            .unwrap();
+        let call = Call {
+            to: Felt::from(0x123u32),
+            selector: Felt::from(0x456u32),
+            calldata: vec![Felt::from(7u8)],
+        };
+
+        // An explicit account type is kept...
+        let other = client
+            .with_account(&format!("{:#x}", legacy), "0xdef")
+            .await
+            .unwrap();
+        assert_eq!(
+            other
+                .account
+                .encode_calls(std::slice::from_ref(&call))
+                .len(),
+            5
+        );
+
+        // ...and `Auto` reads the new account's Cairo 0 class
+        let mut client = client;
+        client.config.account_type = AccountType::Auto;
+        let other = client
+            .with_account(&format!("{:#x}", legacy), "0xdef")
+            .await
+            .unwrap();
+        assert_eq!(
+            other.account.encode_calls(std::slice::from_ref(&call)),
+            [1u16, 0x123, 0x456, 0, 1, 1, 7].map(Felt::from).to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_token_amount_in_usd_unsupported_token() {
+        let config = create_test_config();
+        let contract = Felt::from_hex(&config.contract_address).unwrap();
+        // The contract answers zero for tokens without a feed
+        let provider = MockRpc::new()
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ZERO, Felt::ZERO],
+            )
+            .on_call(
+                contract,
+                "get_token_amount_in_usd",
+                vec![Felt::ZERO, Felt::ZERO],
+            )
+            .into_provider();
+        let client = AutoSwapprClient::from_provider(provider, config)
+            .await
+            .unwrap();
+
+        let result = client.get_token_amount_in_usd("0x123", 1_000).await;
+        match result {
+            Err(AutoSwapprError::UnsupportedToken { token }) => {
+                assert_eq!(token, Address::from_hex("0x123").unwrap().to_string())
+            }
+            other => panic!("expected unsupported token error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_price_cache_ttl() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(token, erc20_abi::DECIMALS, vec![Felt::from(6u8)])
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ONE, Felt::from(7u8)],
+            )
+            .on_call(
+                contract,
+                "get_token_amount_in_usd",
+                vec![Felt::from(2_500u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let usd_requests = || server.call_count("get_token_amount_in_usd");
+
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap()
+            .with_price_ttl(Duration::from_secs(30));
+        assert_eq!(
+            client
+                .get_token_amount_in_usd("0x123", 1_000_000)
+                .await
+                .unwrap(),
+            2_500
+        );
+        // Other amounts are priced from the cached price of one token
+        assert_eq!(
+            client
+                .get_token_amount_in_usd("0x123", 500_000)
+                .await
+                .unwrap(),
+            1_250
+        );
+        assert_eq!(usd_requests(), 1);
+        // The oracle is asked for one whole token
+        let selector = starknet::core::utils::get_selector_from_name("get_token_amount_in_usd")
+            .map(|selector| format!("{:#x}", selector))
+            .unwrap();
+        assert!(server.requests("starknet_call").iter().any(|r| {
+            r["params"]["request"]["entry_point_selector"] == selector
+                && r["params"]["request"]["calldata"][1] == "0xf4240"
+        }));
+
+        client.clear_price_cache();
+        client
+            .get_token_amount_in_usd("0x123", 1_000)
+            .await
+            .unwrap();
+        assert_eq!(usd_requests(), 2);
+
+        // Once the TTL has passed the oracle is read again, and the expired price is dropped
+        let client = client.with_price_ttl(Duration::from_millis(1));
+        client.clear_price_cache();
+        client
+            .get_token_amount_in_usd("0x123", 1_000)
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(5)).await;
+        client
+            .get_token_amount_in_usd("0x123", 1_000)
+            .await
+            .unwrap();
+        assert_eq!(usd_requests(), 4);
+        assert_eq!(client.prices.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_token_amount_in_usd_formatted() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ONE, Felt::from(7u8)],
+            )
+            .on_call(
+                contract,
+                "get_token_amount_in_usd",
+                vec![Felt::from(2_500_000u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let usd = client
+            .get_token_amount_in_usd_formatted("0x123", 1_000, 6)
+            .await
+            .unwrap();
+
+        assert_eq!(usd, "2.5");
+    }
+
+    #[tokio::test]
+    async fn test_get_allowance() {
+        let token =
+            Felt::from_hex("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7")
+                .unwrap();
+        let provider = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::ALLOWANCE,
+                vec![Felt::from(1_000u32), Felt::ZERO],
+            )
+            .into_provider();
+        let client = AutoSwapprClient::from_provider(provider, create_test_config())
+            .await
+            .unwrap();
+
+        let owner = "0x0234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
+        let spender = "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b";
+
+        let allowance = client.get_allowance(token, owner, spender).await.unwrap();
+        assert_eq!(allowance, 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_block_id_forwarded_to_read_calls() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                Felt::from_hex("0x123").unwrap(),
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(5_000u32), Felt::ZERO],
+            )
+            .on_call(
+                contract,
+                "contract_parameters",
+                contract_parameters_with_owner(Felt::ONE),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        assert_eq!(client.block_id(), BlockId::Tag(BlockTag::Latest));
+
+        let client = client.with_block_id(BlockId::Number(1234));
+        client.get_token_balance("0x123").await.unwrap();
+        client.get_contract_parameters().await.unwrap();
+
+        let requests = server.requests("starknet_call");
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            assert_eq!(
+                request["params"]["block_id"],
+                serde_json::json!({ "block_number": 1234 })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_allowance_with_invalid_address() {
+        let config = create_test_config();
+        let client = AutoSwapprClient::new(config).await.unwrap();
+
+        let result = client
+            .get_allowance("invalid_address", "owner", "spender")
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_token_balance() {
+        let config = create_test_config();
+        let client = AutoSwapprClient::new(config).await.unwrap();
+
+        let token_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+
+        let result = client.get_token_balance(token_address).await;
+        // This will make a real contract call
+        assert!(result.is_ok() || result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_token_info() {
+        let config = create_test_config();
+        let client = AutoSwapprClient::new(config).await.unwrap();
+
+        let token_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+
+        let result = client.get_token_info(token_address).await;
+        // This will make a real contract call
+        assert!(result.is_ok() || result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_token_info_with_invalid_address() {
+        let config = create_test_config();
+        let client = AutoSwapprClient::new(config).await.unwrap();
+
+        let result = client.get_token_info("invalid_address").await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_client_addresses() {
+        let config = create_test_config();
+        // We can't create the client in a sync test, but we can test the config
+        assert_eq!(
+            config.contract_address,
+            "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b"
+        );
+        assert_eq!(
+            config.rpc_url,
+            "https://starknet-mainnet.public.blastapi.io/rpc/v0_9"
+        );
+    }
+
+    #[test]
+    fn test_swap_data_creation() {
+        let swap_data = SwapData {
+            params: SwapParameters {
+                amount: I129 {
+                    mag: 1000000000000000000u128, // 1 ETH
+                    sign: false,
+                },
+                sqrt_ratio_limit: Uint256::default(),
+                is_token1: false,
+                skip_ahead: 0,
+            },
+            pool_key: PoolKey {
+                token0: Felt::from_hex(
+                    "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
+                )
+                .unwrap(),
+                token1: Felt::from_hex(
+                    "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                )
+                .unwrap(),
+                fee: 3000,
+                tick_spacing: 60,
+                extension: Felt::ZERO,
+            },
+            caller: Felt::from_hex(
+                "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            )
+            .unwrap(),
+        };
+
+        assert_eq!(swap_data.params.amount.mag, 1000000000000000000u128);
+        assert_eq!(swap_data.pool_key.fee, 3000);
+        assert_eq!(swap_data.pool_key.tick_spacing, 60);
+    }
+
+    #[test]
+    fn test_uint256_from_u128() {
+        let amount = 1000000000000000000u128; // 1 ETH
+        let uint256 = Uint256::from_u128(amount);
+
+        assert_eq!(uint256.low, amount);
+        assert_eq!(uint256.high, 0);
+    }
+
+    #[tokio::test]
+    async fn test_is_pair_routable_known_pair() {
+        let server = MockRpc::new()
+            .on_call(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![Felt::ZERO, Felt::ONE, Felt::ZERO, Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let strk = "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d";
+        let usdc = "0x053c91253bc9682c04929ca02ed00b3e423f6710d2ee7e0d5ebb06f3ecf368a8";
+
+        // Known pairs are checked on-chain too, stopping at the first initialized pool
+        assert!(client.is_pair_routable(strk, usdc).await.unwrap());
+        assert_eq!(server.call_count(ekubo_abi::GET_POOL_PRICE), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pool_queries_use_the_networks_ekubo_core() {
+        let server = MockRpc::new()
+            .on_method(
+                "starknet_chainId",
+                serde_json::json!(format!("{:#x}", chain_id::SEPOLIA)),
+            )
+            .on_call(
+                addresses::testnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![Felt::ZERO, Felt::ONE, Felt::ZERO, Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        assert!(client.is_pair_routable("0x123", "0x456").await.unwrap());
+
+        let server = MockRpc::new()
+            .on_method("starknet_chainId", serde_json::json!("0x1234"))
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            client.is_pair_routable("0x123", "0x456").await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_is_pair_routable_unknown_pair() {
+        let server = MockRpc::new()
+            .on_call(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![Felt::ZERO; 4],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let routable = client.is_pair_routable("0x123", "0x456").await.unwrap();
+        assert!(!routable);
+        assert_eq!(
+            server.call_count(ekubo_abi::GET_POOL_PRICE),
+            ekubo_abi::FEE_TIERS.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_swap_plan_resolves_pool_once() {
+        let server = submitting_server()
+            .on_call(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![Felt::ZERO, Felt::ONE, Felt::ZERO, Felt::ZERO],
+            )
+            .on_call(
+                addresses::mainnet::ekubo_core(),
+                "get_pool_liquidity",
+                vec![Felt::from(1_000u32)],
+            )
+            .on_call(*crate::STRK, erc20_abi::DECIMALS, vec![Felt::from(18u8)])
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let plan = client.prepare_swap("STRK", "USDC").await.unwrap();
+        plan.execute(&client, 1_000).await.unwrap();
+        plan.execute_whole_tokens(&client, 0.5).await.unwrap();
+
+        assert_eq!(plan.decimals, 18);
+        assert_eq!(plan.is_token1, *crate::STRK > *crate::USDC);
+        assert_eq!(
+            server.call_count("get_pool_liquidity"),
+            ekubo_abi::FEE_TIERS.len()
+        );
+        assert_eq!(server.call_count(erc20_abi::DECIMALS), 1);
+
+        // Both swaps go through the pool that was found, at the lowest tier
+        let requests = server.requests("starknet_addInvokeTransaction");
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            let calldata: Vec<Felt> =
+                serde_json::from_value(request["params"]["invoke_transaction"]["calldata"].clone())
+                    .unwrap();
+            assert!(calldata.contains(&Felt::from(ekubo_abi::FEE_TIERS[0].0)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_price_impact() {
+        let server = MockRpc::new()
+            .on_call(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                // Price 1: sqrt ratio 2^128, tick 0
+                vec![Felt::ZERO, Felt::ONE, Felt::ZERO, Felt::ZERO],
+            )
+            .on_call(
+                addresses::mainnet::ekubo_core(),
+                "get_pool_liquidity",
+                vec![Felt::from(1_000_000u32)],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let pool_key = PoolKey::new(*crate::STRK, *crate::USDC);
+
+        let impact = client
+            .estimate_price_impact(&pool_key, 10_000, false)
+            .await
+            .unwrap();
+        assert!((impact - 1.97).abs() < 0.01, "{}", impact);
+
+        let impact = client
+            .estimate_price_impact(&pool_key, 1_000_000_000, false)
+            .await
+            .unwrap();
+        assert!(impact > 99.0 && impact <= 100.0, "{}", impact);
+    }
+
+    fn swap_event_json(amount_from: u32, transaction_hash: &str) -> serde_json::Value {
+        serde_json::json!({
+            "from_address": crate::test_utils::TEST_CONTRACT,
+            "keys": [format!(
+                "{:#x}",
+                starknet::core::utils::get_selector_from_name("SwapSuccessful").unwrap()
+            )],
+            "data": [
+                format!("{:#x}", *crate::STRK),
+                format!("{:#x}", amount_from),
+                "0x0",
+                format!("{:#x}", *crate::USDC),
+                "0x7",
+                "0x1",
+                "0x123",
+                "0x456"
+            ],
+            "block_hash": "0x1",
+            "block_number": 10,
+            "transaction_hash": transaction_hash
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_swap_events_follows_pages_and_chunks() {
+        let server = MockRpc::new()
+            .on_method_seq(
+                "starknet_getEvents",
+                vec![
+                    serde_json::json!({
+                        "events": [swap_event_json(1_000, "0xa1")],
+                        "continuation_token": "page-2"
+                    }),
+                    serde_json::json!({ "events": [swap_event_json(2_000, "0xa2")] }),
+                    serde_json::json!({ "events": [] }),
+                ],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        // Spans two chunks: 5..=10_004 over two pages, then 10_005..=15_004
+        let swaps = client.get_swap_events(5, 15_004).await.unwrap();
+
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(swaps[0].token_from, *crate::STRK);
+        assert_eq!(swaps[0].amount_from, Uint256::from(1_000u128));
+        assert_eq!(swaps[0].amount_to, Uint256 { low: 7, high: 1 });
+        assert_eq!(swaps[0].provider, Felt::from(0x456u32));
+        assert_eq!(swaps[1].amount_from, Uint256::from(2_000u128));
+        assert_eq!(swaps[1].block_number, Some(10));
+        assert_eq!(swaps[1].transaction_hash, Felt::from(0xa2u32));
+
+        let requests = server.requests("starknet_getEvents");
+        assert_eq!(requests.len(), 3);
+        let filters: Vec<_> = requests.iter().map(|r| &r["params"]["filter"]).collect();
+        assert_eq!(filters[0]["from_block"]["block_number"], 5);
+        assert_eq!(filters[0]["to_block"]["block_number"], 10_004);
+        assert_eq!(filters[1]["continuation_token"], "page-2");
+        assert_eq!(filters[2]["from_block"]["block_number"], 10_005);
+        assert_eq!(filters[2]["to_block"]["block_number"], 15_004);
+
+        assert!(matches!(
+            client.get_swap_events(10, 5).await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_pool_sqrt_ratio() {
+        let server = MockRpc::new()
+            .on_call_seq(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![
+                    // sqrt_ratio (low, high), tick (mag, sign)
+                    vec![Felt::from(5u8), Felt::from(2u8), Felt::from(9u8), Felt::ONE],
+                    vec![Felt::ZERO; 4],
+                ],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let pool_key = PoolKey::new(*crate::STRK, *crate::USDC);
+
+        assert_eq!(
+            client.get_pool_sqrt_ratio(&pool_key).await.unwrap(),
+            Uint256 { low: 5, high: 2 }
+        );
+        assert!(matches!(
+            client.get_pool_sqrt_ratio(&pool_key).await,
+            Err(AutoSwapprError::InvalidPoolConfig { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_find_pool_picks_deepest_liquidity() {
+        let initialized = vec![Felt::from(1u128 << 64), Felt::ZERO, Felt::ZERO, Felt::ZERO];
+        // 0.01% and 1% tiers are uninitialized; 0.05% and 0.3% both exist
+        let server = MockRpc::new()
+            .on_call_seq(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![
+                    vec![Felt::ZERO; 4],
+                    initialized.clone(),
+                    initialized,
+                    vec![Felt::ZERO; 4],
+                ],
+            )
+            .on_call_seq(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_LIQUIDITY,
+                vec![vec![Felt::from(5_000u128)], vec![Felt::from(90_000u128)]],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let pool = client.find_pool("0x2", "0x1").await.unwrap();
+
+        assert_eq!(pool.token0, Felt::ONE);
+        assert_eq!(pool.token1, Felt::TWO);
+        assert_eq!(
+            (pool.fee, pool.tick_spacing),
+            ekubo_abi::FEE_TIERS[2],
+            "the 0.3% pool has more liquidity"
+        );
+        assert_eq!(server.call_count(ekubo_abi::GET_POOL_LIQUIDITY), 2);
+    }
+
+    #[tokio::test]
+    async fn test_find_pool_without_pool() {
+        let server = MockRpc::new()
+            .on_call(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![Felt::ZERO; 4],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let result = client.find_pool("0x1", "0x2").await;
+        assert!(matches!(
+            result,
+            Err(AutoSwapprError::InvalidPoolConfig { .. })
+        ));
+
+        let result = client.find_pool_with_fee("0x1", "0x2", 42).await;
+        assert!(matches!(result, Err(AutoSwapprError::InvalidInput { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_swap_aborted_when_price_moves() {
+        let quoted = vec![Felt::from(1u128 << 64), Felt::ZERO, Felt::ZERO, Felt::ZERO];
+        // sqrt ratio up 1%, so the price is up ~2%
+        let moved = vec![
+            Felt::from((1u128 << 64) / 100 * 101),
+            Felt::ZERO,
+            Felt::ZERO,
+            Felt::ZERO,
+        ];
+        let server = MockRpc::new()
+            .on_call_seq(
+                addresses::mainnet::ekubo_core(),
+                ekubo_abi::GET_POOL_PRICE,
+                vec![quoted.clone(), moved],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*crate::STRK, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
+
+        let quote = client.get_pool_price(&swap_data.pool_key).await.unwrap();
+        let result = client
+            .execute_ekubo_swap_with_price_guard(swap_data, &quote, 100, None)
+            .await;
+
+        assert!(
+            matches!(result, Err(AutoSwapprError::SwapFailed { reason }) if reason == "price moved beyond tolerance")
+        );
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+    }
+
+    #[test]
+    fn test_price_deviation_bps() {
+        let base = Uint256::from(1u128 << 64);
+        assert_eq!(price_deviation_bps(base, base), 0.0);
+
+        let up = price_deviation_bps(base, Uint256::from((1u128 << 64) / 100 * 101));
+        assert!((up - 201.0).abs() < 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_get_balances_preserves_order() {
+        let server = MockRpc::new()
+            .on_call(
+                Felt::from_hex("0x111").unwrap(),
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(100u32), Felt::ZERO],
+            )
+            .on_call(
+                Felt::from_hex("0x222").unwrap(),
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(200u32), Felt::ZERO],
+            )
+            .on_call(
+                Felt::from_hex("0x333").unwrap(),
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(300u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let balances = client
+            .get_balances(&["0x333", "0x111", "0x444", "0x222"])
+            .await;
+
+        let tokens: Vec<_> = balances.iter().map(|(token, _)| token.as_str()).collect();
+        assert_eq!(tokens, ["0x333", "0x111", "0x444", "0x222"]);
+        assert_eq!(balances[0].1.as_ref().unwrap(), &300);
+        assert_eq!(balances[1].1.as_ref().unwrap(), &100);
+        // Unknown contract fails only its own entry
+        assert!(balances[2].1.is_err());
+        assert_eq!(balances[3].1.as_ref().unwrap(), &200);
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_usd_sums_holdings() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let usdc = Felt::from_hex("0x111").unwrap();
+        let wbtc = Felt::from_hex("0x222").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                usdc,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(5u8), Felt::ZERO],
+            )
+            .on_call(usdc, erc20_abi::DECIMALS, vec![Felt::from(6u8)])
+            .on_call(
+                wbtc,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(7u8), Felt::ZERO],
+            )
+            .on_call(wbtc, erc20_abi::DECIMALS, vec![Felt::from(5u8)])
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ONE, Felt::from_bytes_be_slice(b"USD")],
+            )
+            .on_call(
+                contract,
+                "get_token_amount_in_usd",
+                vec![Felt::from(12_500_000u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let portfolio = client.portfolio_usd(&["0x111", "0x222"]).await.unwrap();
+
+        assert_eq!(
+            portfolio.breakdown,
+            vec![("0x111".to_string(), 12.5), ("0x222".to_string(), 125.0)]
+        );
+        assert_eq!(portfolio.total_usd, 137.5);
+        assert!(portfolio.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cloned_client_reads_from_spawned_task() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(42u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let cloned = client.clone();
+        let spawned = tokio::spawn(async move { cloned.get_token_balance("0x123").await });
+
+        assert_eq!(client.get_token_balance("0x123").await.unwrap(), 42);
+        assert_eq!(spawned.await.unwrap().unwrap(), 42);
+        assert_eq!(server.call_count(erc20_abi::BALANCE_OF), 2);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_block_and_chain() {
+        let server = MockRpc::new()
+            .on_method("starknet_blockNumber", serde_json::json!(4242))
+            .on_method(
+                "starknet_chainId",
+                serde_json::json!(format!("{:#x}", chain_id::SEPOLIA)),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let status = client.health_check().await.unwrap();
+        assert_eq!(status.block_number, 4242);
+        assert_eq!(status.chain_id, format!("{:#x}", chain_id::SEPOLIA));
+
+        let unreachable = AutoSwapprClient::new(test_config("http://127.0.0.1:1"))
+            .await
+            .unwrap();
+        assert!(unreachable.health_check().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provider_impl_reads_through_trait() {
+        async fn latest_block<P: Provider + Send + Sync>(provider: &P) -> u64 {
+            provider.block_number().await.unwrap()
+        }
+
+        let server = MockRpc::new()
+            .on_method("starknet_blockNumber", serde_json::json!(4242))
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        assert_eq!(latest_block(client.provider_impl()).await, 4242);
+    }
+
+    #[tokio::test]
+    async fn test_client_with_injected_provider() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let transport = MockRpc::new()
+            .on_method(
+                "starknet_chainId",
+                serde_json::json!(format!("{:#x}", chain_id::SEPOLIA)),
+            )
+            .on_call(
+                token,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(77u32), Felt::ZERO],
+            )
+            .transport();
+
+        let client: AutoSwapprClient<JsonRpcClient<MockTransport>> =
+            AutoSwapprClient::from_provider(JsonRpcClient::new(transport), test_config("unused"))
+                .await
+                .unwrap();
+
+        assert_eq!(client.account().chain_id(), chain_id::SEPOLIA);
+        assert_eq!(client.get_token_balance("0x123").await.unwrap(), 77);
+    }
+
+    #[tokio::test]
+    async fn test_get_token_info_fetches_concurrently() {
+        let token = Felt::from_hex("0x123").unwrap();
+        // Each call waits until all three are in flight, so sequential fetching would hang
+        let transport = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::NAME,
+                vec![Felt::from_hex("0x4574686572").unwrap()],
+            )
+            .on_call(
+                token,
+                erc20_abi::SYMBOL,
+                vec![Felt::from_hex("0x455448").unwrap()],
+            )
+            .on_call(token, erc20_abi::DECIMALS, vec![Felt::from(18u8)])
+            .transport()
+            .with_call_barrier(3);
+        let client =
+            AutoSwapprClient::from_provider(JsonRpcClient::new(transport), test_config("unused"))
+                .await
+                .unwrap();
+
+        let info = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            client.get_token_info("0x123"),
+        )
+        .await
+        .expect("token info calls were not issued concurrently")
+        .unwrap();
+
+        assert_eq!(info, ("Ether".to_string(), "ETH".to_string(), 18));
+    }
+
+    #[tokio::test]
+    async fn test_get_token_infos_keeps_order() {
+        let eth = Felt::from_hex("0x123").unwrap();
+        let usdc = Felt::from_hex("0x456").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                eth,
+                erc20_abi::NAME,
+                vec![Felt::from_hex("0x4574686572").unwrap()],
+            )
+            .on_call(
+                eth,
+                erc20_abi::SYMBOL,
+                vec![Felt::from_hex("0x455448").unwrap()],
+            )
+            .on_call(eth, erc20_abi::DECIMALS, vec![Felt::from(18u8)])
+            .on_call(
+                usdc,
+                erc20_abi::NAME,
+                vec![Felt::from_hex("0x55534420436f696e").unwrap()],
+            )
+            .on_call(
+                usdc,
+                erc20_abi::SYMBOL,
+                vec![Felt::from_hex("0x55534443").unwrap()],
+            )
+            .on_call(usdc, erc20_abi::DECIMALS, vec![Felt::from(6u8)])
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let infos = client.get_token_infos(&["0x456", "0x123"]).await.unwrap();
+
+        let fields: Vec<_> = infos
+            .iter()
+            .map(|info| {
+                (
+                    info.address.as_str(),
+                    info.name.as_str(),
+                    info.symbol.as_str(),
+                    info.decimals,
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("0x456", "USD Coin", "USDC", 6),
+                ("0x123", "Ether", "ETH", 18)
+            ]
+        );
+        assert!(client.get_token_infos(&["0x123", "0x789"]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_releases_provider() {
+        let server = MockRpc::new().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let clone = client.clone();
+        let provider = Arc::downgrade(&client.provider);
+
+        client.shutdown();
+        // The clone still shares the provider
+        assert!(provider.upgrade().is_some());
+
+        clone.shutdown();
+        assert!(provider.upgrade().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_is_account_deployed() {
+        let server = MockRpc::new()
+            .on_method_error("starknet_getClassHashAt", 20, "Contract not found")
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        assert!(!client.is_account_deployed().await.unwrap());
+
+        let result = AutoSwapprClient::new_checked(test_config(&server.url)).await;
+        assert!(matches!(result, Err(AutoSwapprError::InvalidInput { .. })));
+
+        let server = MockRpc::new()
+            .on_method("starknet_getClassHashAt", serde_json::json!("0xabc"))
+            .on_call(
+                *crate::STRK,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::ONE, Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new_checked(test_config(&server.url))
+            .await
+            .unwrap();
+        assert!(client.is_account_deployed().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_new_checked_requires_fee_token_balance() {
+        let server = MockRpc::new()
+            .on_method("starknet_getClassHashAt", serde_json::json!("0xabc"))
+            .on_call(
+                *crate::STRK,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::ZERO, Felt::ZERO],
+            )
+            .start()
+            .await;
+
+        let result = AutoSwapprClient::new_checked(test_config(&server.url)).await;
+
+        assert!(
+            matches!(result, Err(AutoSwapprError::InvalidInput { details }) if details.contains("STRK"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_verified_checks_contract_parameters() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+
+        // A contract answering with the wrong number of fields is not AutoSwappr
+        let mut parameters = contract_parameters_with_owner(Felt::ONE);
+        parameters.push(Felt::ZERO);
+        let server = MockRpc::new()
+            .on_call(contract, "contract_parameters", parameters)
+            .start()
+            .await;
+        let result = AutoSwapprClient::new_verified(test_config(&server.url)).await;
+        assert!(
+            matches!(result, Err(AutoSwapprError::InvalidInput { details }) if details.contains("not an AutoSwappr contract"))
+        );
+
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "contract_parameters",
+                contract_parameters_with_owner(Felt::ONE),
+            )
+            .start()
+            .await;
+        assert!(
+            AutoSwapprClient::new_verified(test_config(&server.url))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_resource_bounds_are_sent_and_enforced() {
+        use starknet::core::types::ResourceBounds;
+
+        let bound = |max_amount: u64, max_price_per_unit: u128| ResourceBounds {
+            max_amount,
+            max_price_per_unit,
+        };
+        // Every resource is estimated at 100 units for 10 each
+        let estimate = serde_json::json!([{
+            "l1_gas_consumed": "0x64",
+            "l1_gas_price": "0xa",
+            "l2_gas_consumed": "0x64",
+            "l2_gas_price": "0xa",
+            "l1_data_gas_consumed": "0x64",
+            "l1_data_gas_price": "0xa",
+            "overall_fee": "0xbb8",
+            "unit": "FRI"
+        }]);
+
+        let server = submitting_server()
+            .on_method("starknet_estimateFee", estimate.clone())
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap()
+            .with_max_resource_bounds(ResourceBoundsMapping {
+                l1_gas: bound(1_000, 100),
+                l2_gas: bound(120, 12),
+                l1_data_gas: bound(1_000, 100),
+            });
+
+        client.revoke_approval("0x123", "0x456").await.unwrap();
+
+        // The estimate plus 50% headroom, clamped to each cap
+        let requests = server.requests("starknet_addInvokeTransaction");
+        let sent = &requests[0]["params"]["invoke_transaction"]["resource_bounds"];
+        assert_eq!(sent["l1_gas"]["max_amount"], "0x96");
+        assert_eq!(sent["l1_gas"]["max_price_per_unit"], "0xf");
+        assert_eq!(sent["l2_gas"]["max_amount"], "0x78");
+        assert_eq!(sent["l2_gas"]["max_price_per_unit"], "0xc");
+        assert_eq!(sent["l1_data_gas"]["max_amount"], "0x96");
+        assert_eq!(sent["l1_data_gas"]["max_price_per_unit"], "0xf");
+
+        // A single resource estimated above its cap rejects the transaction
+        let server = submitting_server()
+            .on_method("starknet_estimateFee", estimate)
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap()
+            .with_max_resource_bounds(ResourceBoundsMapping {
+                l1_gas: bound(1_000, 100),
+                l2_gas: bound(1_000, 100),
+                l1_data_gas: bound(1_000, 5),
+            });
+
+        let result = client.revoke_approval("0x123", "0x456").await;
+
+        assert!(
+            matches!(result, Err(AutoSwapprError::InvalidInput { details }) if details.contains("L1 data gas"))
+        );
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fees_are_paid_in_strk_by_v3_transactions() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        client.revoke_approval("0x123", "0x456").await.unwrap();
+
+        // STRK fees go out as a v3 invoke with resource bounds
+        assert_eq!(client.config.fee_token.address(), *crate::STRK);
+        let requests = server.requests("starknet_addInvokeTransaction");
+        let invoke = &requests[0]["params"]["invoke_transaction"];
+        assert_eq!(invoke["version"], "0x3");
+        assert!(invoke["resource_bounds"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_eth_fee_token_is_rejected() {
+        let server = submitting_server().start().await;
+        let config = AutoSwapprConfig {
+            fee_token: FeeToken::Eth,
+            ..test_config(&server.url)
+        };
+
+        let result = AutoSwapprClient::new(config).await;
+
+        assert!(
+            matches!(result, Err(AutoSwapprError::InvalidInput { details }) if details.contains("ETH"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_token_balance_pending_reads_pre_confirmed_block() {
+        let server = MockRpc::new()
+            .on_call(
+                *crate::ETH,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(42u8), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        assert_eq!(client.get_token_balance_pending("ETH").await.unwrap(), 42);
+        client.get_token_balance(*crate::ETH).await.unwrap();
+
+        let calls = server.requests("starknet_call");
+        assert_eq!(calls[0]["params"]["block_id"], "pre_confirmed");
+        assert_eq!(calls[1]["params"]["block_id"], "latest");
+    }
+
+    #[tokio::test]
+    async fn test_percent_of_balance() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(1_000_003u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client.percent_of_balance("0x123", 2_500).await.unwrap(),
+            250_000
+        );
+        assert_eq!(
+            client.percent_of_balance("0x123", 10_000).await.unwrap(),
+            1_000_003
+        );
+        assert!(matches!(
+            client.percent_of_balance("0x123", 10_001).await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_swap_percent_rejects_zero_amount() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(3u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let result = client
+            .execute_ekubo_swap_percent("0x123", "0x456", 100)
+            .await;
+        assert!(matches!(result, Err(AutoSwapprError::ZeroAmount)));
+    }
+
+    #[tokio::test]
+    async fn test_with_account_reuses_provider() {
+        let client = AutoSwapprClient::new(test_config("http://127.0.0.1:5050"))
+            .await
+            .unwrap();
+
+        let other = client.with_account("0xabc", "0xdef").await.unwrap();
+
+        assert_eq!(
+            other.account_address(),
+            Felt::from_hex("0xabc").unwrap().to_string()
+        );
+        assert_ne!(other.account_address(), client.account_address());
+        assert_eq!(other.contract_address(), client.contract_address());
+        assert!(Arc::ptr_eq(&client.provider, &other.provider));
+        assert!(matches!(
+            client.with_account("0xabc", "not-hex").await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_allowance_check_is_traced() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::ALLOWANCE,
+                vec![Felt::from(42u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        client.get_allowance("0x123", "0x1", "0x2").await.unwrap();
+
+        assert!(logs_contain("allowance checked"));
+        assert!(logs_contain("allowance=42"));
+    }
+
+    fn receipt_in_block(block_number: u64) -> serde_json::Value {
+        serde_json::json!({
+            "type": "INVOKE",
+            "transaction_hash": "0xabc",
+            "actual_fee": { "amount": "0x1", "unit": "FRI" },
+            "finality_status": "ACCEPTED_ON_L2",
+            "messages_sent": [],
+            "events": [],
+            "execution_resources": { "l1_gas": 0, "l1_data_gas": 0, "l2_gas": 0 },
+            "execution_status": "SUCCEEDED",
+            "block_hash": "0x2",
+            "block_number": block_number
+        })
+    }
+
+    async fn fast_polling_client(url: &str) -> AutoSwapprClient {
+        let mut client = AutoSwapprClient::new(test_config(url)).await.unwrap();
+        client.retry_policy = RetryPolicy {
+            max_retries: 3,
+            delay: std::time::Duration::from_millis(1),
+        };
+        client
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_transaction_waits_for_depth() {
+        let server = MockRpc::new()
+            .on_method("starknet_getTransactionReceipt", receipt_in_block(10))
+            .on_method_seq(
+                "starknet_blockNumber",
+                vec![
+                    serde_json::json!(10),
+                    serde_json::json!(11),
+                    serde_json::json!(12),
+                    serde_json::json!(13),
+                ],
+            )
+            .start()
+            .await;
+        let client = fast_polling_client(&server.url).await;
+
+        let status = client.wait_for_transaction("0xabc", 2).await.unwrap();
+
+        assert_eq!(status, TxStatus::Confirmed { block_number: 10 });
+        assert_eq!(server.requests("starknet_blockNumber").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_transaction_detects_reorg() {
+        let server = MockRpc::new()
+            .on_method_responses(
+                "starknet_getTransactionReceipt",
+                vec![
+                    Err((29, "Transaction hash not found".to_string())),
+                    Ok(receipt_in_block(10)),
+                    Err((29, "Transaction hash not found".to_string())),
+                ],
+            )
+            .on_method("starknet_blockNumber", serde_json::json!(10))
+            .start()
+            .await;
+        let client = fast_polling_client(&server.url).await;
+
+        let status = client.wait_for_transaction("0xabc", 5).await.unwrap();
+
+        assert_eq!(status, TxStatus::Reorged);
+    }
+
+    #[tokio::test]
+    async fn test_swap_with_result_reports_revert() {
+        let mut receipt = receipt_in_block(10);
+        receipt["execution_status"] = serde_json::json!("REVERTED");
+        receipt["revert_reason"] = serde_json::json!("Insufficient output amount");
+        let server = submitting_server()
+            .on_method("starknet_getTransactionReceipt", receipt)
+            .on_method("starknet_blockNumber", serde_json::json!(10))
+            .start()
+            .await;
+        let client = fast_polling_client(&server.url).await;
+        assert_eq!(
+            client.wait_for_transaction("0xa0", 0).await.unwrap(),
+            TxStatus::Reverted("Insufficient output amount".to_string())
+        );
+
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(Felt::from(0x111u32), Felt::from(0x222u32))
+            .caller(client.account().address())
+            .build()
+            .unwrap();
+        let result = client.execute_ekubo_swap_with_result(swap_data, None).await;
+
+        match result {
+            Err(AutoSwapprError::SwapFailed { reason }) => {
+                assert_eq!(reason, "Insufficient output amount")
+            }
+            other => panic!("expected a reverted swap, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_swap_fee_usd() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let strk = *crate::STRK;
+        let server = MockRpc::new()
+            .on_method("starknet_getNonce", serde_json::json!("0x1"))
+            .on_method(
+                "starknet_estimateFee",
+                serde_json::json!([{
+                    "l1_gas_consumed": "0x0",
+                    "l1_gas_price": "0x0",
+                    "l2_gas_consumed": "0x0",
+                    "l2_gas_price": "0x0",
+                    "l1_data_gas_consumed": "0x0",
+                    "l1_data_gas_price": "0x0",
+                    "overall_fee": "0x6f05b59d3b20000",
+                    "unit": "FRI"
+                }]),
+            )
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ONE, Felt::from_hex("0x5354524b").unwrap()],
+            )
+            .on_call(
+                contract,
+                "get_token_amount_in_usd",
+                vec![Felt::from(75_000_000_000_000_000u128), Felt::ZERO],
+            )
+            .on_call(strk, erc20_abi::DECIMALS, vec![Felt::from(18u8)])
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(strk, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
+
+        let fee = client
+            .estimate_swap_fee(&swap_data)
+            .await
+            .unwrap()
+            .overall_fee;
+        assert_eq!(fee, 500_000_000_000_000_000);
+
+        let usd = client
+            .estimate_swap_fee_usd(&swap_data, &format!("{:#x}", strk))
+            .await
+            .unwrap();
+        assert!((usd - 0.075).abs() < 1e-9);
+
+        let amount_request = server
+            .requests("starknet_call")
+            .into_iter()
+            .find(|r| {
+                r["params"]["request"]["entry_point_selector"]
+                    == format!(
+                        "{:#x}",
+                        starknet::core::utils::get_selector_from_name("get_token_amount_in_usd")
+                            .unwrap()
+                    )
+            })
+            .unwrap();
+        assert_eq!(
+            amount_request["params"]["request"]["calldata"][1],
+            "0x6f05b59d3b20000"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimate_swap_fee_usd_unsupported_token() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ZERO, Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*crate::STRK, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
+
+        let result = client.estimate_swap_fee_usd(&swap_data, "0x123").await;
+        assert!(matches!(
+            result,
+            Err(AutoSwapprError::UnsupportedToken { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_detect_network() {
+        let server = MockRpc::new()
+            .on_method("starknet_chainId", serde_json::json!("0x534e5f4d41494e"))
+            .start()
+            .await;
+        let provider = server.provider();
+        assert!(matches!(
+            detect_network(&provider).await.unwrap(),
+            Network::Mainnet
+        ));
+
+        let server = MockRpc::new()
+            .on_method(
+                "starknet_chainId",
+                serde_json::json!("0x534e5f5345504f4c4941"),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        assert_eq!(client.account().chain_id(), chain_id::SEPOLIA);
+
+        // Other chains keep their own id rather than passing for mainnet
+        let server = MockRpc::new()
+            .on_method("starknet_chainId", serde_json::json!("0x1234"))
+            .start()
+            .await;
+        let provider = server.provider();
+        assert!(matches!(
+            detect_network(&provider).await.unwrap(),
+            Network::OtherChain(chain_id) if chain_id == Felt::from(0x1234u32)
+        ));
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        assert_eq!(client.account().chain_id(), Felt::from(0x1234u32));
+        assert!(network_chain_id(&Network::Custom(server.url.clone())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_from_status_by_symbol() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ONE, Felt::from_hex("0x4554482f555344").unwrap()],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let (supported, feed) = client.token_from_status("eth").await.unwrap();
+        assert!(supported);
+        assert_eq!(feed, "0x4554482f555344");
+
+        let request = &server.requests("starknet_call")[0];
+        assert_eq!(
+            Felt::from_hex(
+                request["params"]["request"]["calldata"][0]
+                    .as_str()
+                    .unwrap()
+            )
+            .unwrap(),
+            *crate::ETH
+        );
+
+        assert!(matches!(
+            client.token_from_status("0xzz").await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            client.token_from_status("DOGE").await,
+            Err(AutoSwapprError::UnsupportedToken { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_supported_source_tokens_filters_unsupported() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        // Statuses in TokenAddress order: ETH, USDC, USDT, WBTC, STRK
+        let server = MockRpc::new()
+            .on_call_seq(
+                contract,
+                "get_token_from_status_and_value",
+                vec![
+                    vec![Felt::ONE, Felt::from(1u8)],
+                    vec![Felt::ZERO, Felt::ZERO],
+                    vec![Felt::ZERO, Felt::ZERO],
+                    vec![Felt::ZERO, Felt::ZERO],
+                    vec![Felt::ONE, Felt::from(2u8)],
+                ],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let tokens = client.supported_source_tokens().await.unwrap();
+
+        assert_eq!(tokens, vec![*crate::ETH, *crate::STRK]);
+        assert_eq!(server.call_count("get_token_from_status_and_value"), 5);
+    }
+
+    #[tokio::test]
+    async fn test_support_token_if_absent_skips_supported_token() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ONE, Felt::from(7u8)],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let result = client
+            .support_new_token_from_if_absent("0x123", Felt::from(7u8), false)
+            .await
+            .unwrap();
+
+        assert_eq!(result, None);
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_support_token_if_absent_submits_once() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ZERO, Felt::ZERO],
+            )
+            .on_method("starknet_getNonce", serde_json::json!("0x0"))
+            .on_method("starknet_estimateFee", fee_estimate_json())
+            .on_method("starknet_getBlockWithTxs", block_json(2_000))
+            .on_method(
+                "starknet_addInvokeTransaction",
+                serde_json::json!({ "transaction_hash": "0xfeed" }),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let result = client
+            .support_new_token_from_if_absent("0x123", Felt::from(7u8), false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            Some(format_tx_hash(Felt::from_hex("0xfeed").unwrap()))
+        );
+        assert_eq!(server.requests("starknet_addInvokeTransaction").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_swap_history_records_in_order() {
+        let server = MockRpc::new()
+            .on_method("starknet_getNonce", serde_json::json!("0x0"))
+            .on_method("starknet_estimateFee", fee_estimate_json())
+            .on_method("starknet_getBlockWithTxs", block_json(2_000))
+            .on_method_seq(
+                "starknet_addInvokeTransaction",
+                vec![
+                    serde_json::json!({ "transaction_hash": "0xa0" }),
+                    serde_json::json!({ "transaction_hash": "0xa1" }),
+                    serde_json::json!({ "transaction_hash": "0xa2" }),
+                ],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap = |amount, is_token1| {
+            SwapData::builder()
+                .amount(amount)
+                .pool(*crate::STRK, *crate::USDC)
+                .is_token1(is_token1)
+                .caller(Felt::ONE)
+                .build()
+                .unwrap()
+        };
+
+        // Nothing is recorded until history is enabled
+        client
+            .execute_ekubo_swap(swap(1, false), None)
+            .await
+            .unwrap();
+        assert!(client.swap_history().is_empty());
+
+        client.enable_history();
+        client
+            .execute_ekubo_swap(swap(1_000, false), None)
+            .await
+            .unwrap();
+        client
+            .execute_ekubo_manual_swap(swap(2_000, true), None)
+            .await
+            .unwrap();
+
+        let history = client.swap_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0].tx_hash,
+            format_tx_hash(Felt::from_hex("0xa1").unwrap())
+        );
+        assert_eq!(history[0].token_in, format!("{:#x}", *crate::STRK));
+        assert_eq!(history[0].amount, Uint256::from(1_000u128));
+        assert_eq!(
+            history[1].tx_hash,
+            format_tx_hash(Felt::from_hex("0xa2").unwrap())
+        );
+        assert_eq!(history[1].token_in, format!("{:#x}", *crate::USDC));
+        assert_eq!(history[1].token_out, format!("{:#x}", *crate::STRK));
+        assert!(history[1].timestamp >= history[0].timestamp);
+    }
+
+    #[tokio::test]
+    async fn test_avnu_swap_with_slippage_derives_min_amount() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        client
+            .execute_avnu_swap_with_slippage(
+                "0x1",
+                "0x2",
+                1_000,
+                "0x3",
+                1_000_000,
+                300,
+                "0x4",
+                0,
+                "0x5",
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        // [n_calls, to, selector, len, protocol_swapper, token_from, amount (2), token_to,
+        //  token_to_min_amount (2), ...]
+        let calldata = invoke_calldata(&server);
+        let min_amount = crate::math::min_received(1_000_000, 300).unwrap();
+        assert_eq!(min_amount, 970_000);
+        assert_eq!(calldata[9], Felt::from(min_amount));
+        assert_eq!(calldata[10], Felt::ZERO);
+
+        let result = client
+            .execute_avnu_swap_with_slippage(
+                "0x1",
+                "0x2",
+                1_000,
+                "0x3",
+                1_000_000,
+                10_001,
+                "0x4",
+                0,
+                "0x5",
+                vec![],
+            )
+            .await;
+        assert!(matches!(result, Err(AutoSwapprError::InvalidInput { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_approval_approves_zero() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        client.revoke_approval("0x123", "0x456").await.unwrap();
+
+        let approve = starknet::core::utils::get_selector_from_name(erc20_abi::APPROVE).unwrap();
+        assert_eq!(
+            invoke_calldata(&server),
+            [
+                Felt::ONE,
+                Felt::from(0x123u32),
+                approve,
+                Felt::THREE,
+                Felt::from(0x456u32),
+                Felt::ZERO,
+                Felt::ZERO,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_swap_whole_tokens_scales_by_decimals() {
+        let eth = Felt::from_hex("0x123").unwrap();
+        let server = submitting_server()
+            .on_call(eth, erc20_abi::DECIMALS, vec![Felt::from(18u8)])
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        client
+            .swap_whole_tokens("0x123", "0x456", 1.5)
+            .await
+            .unwrap();
+
+        // [n_calls, to, selector, calldata_len, amount.mag, ...]
+        assert_eq!(
+            invoke_calldata(&server)[4],
+            Felt::from(1_500_000_000_000_000_000u128)
+        );
+
+        // Bad amounts fail before reading decimals
+        assert!(matches!(
+            client.swap_whole_tokens("0x123", "0x456", -1.0).await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            client.swap_whole_tokens("0x123", "0x456", f64::NAN).await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+        assert_eq!(server.call_count(erc20_abi::DECIMALS), 1);
+    }
+
+    #[tokio::test]
+    async fn test_approve_via_permit_signs_or_falls_back() {
+        let (with_permit, without_permit, broken) = (
+            Felt::from(0x111u32),
+            Felt::from(0x222u32),
+            Felt::from(0x333u32),
+        );
+        let (name, version) = (
+            starknet::core::utils::cairo_short_string_to_felt("Token").unwrap(),
+            starknet::core::utils::cairo_short_string_to_felt("v1").unwrap(),
+        );
+        let provider = MockRpc::new()
+            .on_call(with_permit, erc20_abi::NONCES, vec![Felt::from(3u8)])
+            .on_call(with_permit, erc20_abi::SNIP12_METADATA, vec![name, version])
+            .on_call_error(
+                without_permit,
+                erc20_abi::NONCES,
+                21,
+                "Requested entrypoint does not exist in the contract",
+            )
+            .on_call_error(broken, erc20_abi::NONCES, 20, "Contract not found")
+            .into_provider();
+        let config = create_test_config();
+        let key = SigningKey::from_secret_scalar(Felt::from_hex(&config.private_key).unwrap());
+        let client = AutoSwapprClient::from_provider(provider, config)
+            .await
+            .unwrap();
+        let spender = Felt::from(0x456u32);
+
+        let call = client
+            .approve_via_permit(with_permit, spender, 1_000, 1_700_000_000)
+            .await
+            .unwrap();
+        assert_eq!(
+            call.selector,
+            starknet::core::utils::get_selector_from_name(erc20_abi::PERMIT).unwrap()
+        );
+        // The signature covers the SNIP-12 permit hash under the token's domain and the
+        // owner's current nonce
+        let domain = Domain {
+            name,
+            version,
+            chain_id: client.account().chain_id(),
+            revision: Revision::V1,
+        };
+        let hash = client
+            .erc20(with_permit)
+            .permit_hash(
+                client.account().address(),
+                spender,
+                Uint256::from(1_000u128),
+                Felt::from(3u8),
+                1_700_000_000,
+                &domain,
+            )
+            .unwrap();
+        let signature = starknet::core::crypto::Signature {
+            r: call.calldata[6],
+            s: call.calldata[7],
+        };
+        assert!(key.verifying_key().verify(&hash, &signature).unwrap());
+
+        let call = client
+            .approve_via_permit(without_permit, spender, 1_000, 1_700_000_000)
+            .await
+            .unwrap();
+        assert_eq!(
+            call.selector,
+            starknet::core::utils::get_selector_from_name(erc20_abi::APPROVE).unwrap()
+        );
+
+        // Other failures aren't mistaken for a token without permit
+        assert!(matches!(
+            client
+                .approve_via_permit(broken, spender, 1_000, 1_700_000_000)
+                .await,
+            Err(AutoSwapprError::ProviderError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_swap_atomic_batches_approval() {
+        let token_in = Felt::from(0x111u32);
+        let server = submitting_server()
+            .on_call(token_in, erc20_abi::ALLOWANCE, vec![Felt::ZERO, Felt::ZERO])
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(token_in, Felt::from(0x222u32))
+            .caller(client.account().address())
+            .build()
+            .unwrap();
+
+        client
+            .execute_swap_atomic(token_in, swap_data, 1_000, None)
+            .await
+            .unwrap();
+
+        // One transaction: [2, token_in, approve, 3, contract, 1000, 0, contract, swap, ...]
+        let calldata = invoke_calldata(&server);
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let approve = starknet::core::utils::get_selector_from_name(erc20_abi::APPROVE).unwrap();
+        assert_eq!(
+            calldata[..9],
+            [
+                Felt::TWO,
+                token_in,
+                approve,
+                Felt::THREE,
+                contract,
+                Felt::from(1_000u32),
+                Felt::ZERO,
+                contract,
+                starknet::core::utils::get_selector_from_name("ekubo_manual_swap").unwrap(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_swap_atomic_rejects_swap_above_approval() {
+        let token_in = Felt::from(0x111u32);
+        let server = submitting_server()
+            .on_call(token_in, erc20_abi::ALLOWANCE, vec![Felt::ZERO, Felt::ZERO])
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        // 1.5 tokens scaled to 6 decimals for the swap, but approved unscaled
+        let swap_data = SwapData::builder()
+            .amount(from_display("1.5", 6).unwrap())
+            .pool(token_in, Felt::from(0x222u32))
+            .caller(client.account().address())
+            .build()
+            .unwrap();
+
+        let result = client
+            .execute_swap_with_approval(token_in, swap_data, 1_500, None)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AutoSwapprError::InsufficientAllowance { required, available })
+                if required == "1500000" && available == "1500"
+        ));
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_swap_atomic_rejects_other_input_token() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let (token_in, token_out) = (Felt::from(0x111u32), Felt::from(0x222u32));
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(token_in, token_out)
+            .caller(client.account().address())
+            .build()
+            .unwrap();
+
+        let result = client
+            .execute_swap_atomic(token_out, swap_data, 1_000, None)
+            .await;
 
-        let beneficiary_felt =
-            Felt::from_hex(beneficiary).map_err(|e| AutoSwapprError::InvalidInput {
-                details: format!("Invalid beneficiary address: {}", e),
-            })?;
+        assert!(matches!(result, Err(AutoSwapprError::InvalidInput { .. })));
+        assert_eq!(server.call_count(erc20_abi::ALLOWANCE), 0);
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+    }
 
-        let tx_hash = self
-            .autoswappr_contract
-            .fibrous_swap(
-                &self.account,
-                route_params,
-                swap_params,
-                protocol_swapper_felt,
-                beneficiary_felt,
+    #[tokio::test]
+    async fn test_execute_swap_atomic_skips_sufficient_approval() {
+        let token_in = Felt::from(0x111u32);
+        let server = submitting_server()
+            .on_call(
+                token_in,
+                erc20_abi::ALLOWANCE,
+                vec![Felt::from(5_000u32), Felt::ZERO],
             )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
             .await
-            .map_err(|e| AutoSwapprError::Other {
-                message: e.to_string(),
-            })?;
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(token_in, Felt::from(0x222u32))
+            .caller(client.account().address())
+            .build()
+            .unwrap();
 
-        Ok(tx_hash.to_string())
+        client
+            .execute_swap_atomic(token_in, swap_data, 1_000, None)
+            .await
+            .unwrap();
+
+        let calldata = invoke_calldata(&server);
+        assert_eq!(calldata[0], Felt::ONE);
+        assert_eq!(
+            calldata[1],
+            Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap()
+        );
     }
 
-    /// Execute a complete swap with approval
-    pub async fn execute_swap_with_approval(
-        &self,
-        token_in: &str,
-        swap_data: SwapData,
-        amount: u128,
-    ) -> Result<String, AutoSwapprError> {
-        // First approve the token
-        let _approve_result = self
-            .approve_token(token_in, &self.config.contract_address, amount)
-            .await?;
+    #[tokio::test]
+    async fn test_swap_to_beneficiary_appends_transfer() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let caller = client.account().address();
+        let (token_in, token_out) = (Felt::from(0x111u32), Felt::from(0x222u32));
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(token_in, token_out)
+            .caller(caller)
+            .build()
+            .unwrap();
+        let cold_wallet = Felt::from(0xc01du32);
 
-        // Then execute the swap
-        let swap_result = self.execute_ekubo_manual_swap(swap_data).await?;
+        client
+            .execute_ekubo_swap_to(swap_data, Some(cold_wallet), 950, None)
+            .await
+            .unwrap();
 
-        Ok(swap_result)
+        let calldata = invoke_calldata(&server);
+        assert_eq!(calldata[0], Felt::TWO);
+        let transfer = starknet::core::utils::get_selector_from_name(erc20_abi::TRANSFER).unwrap();
+        assert_eq!(
+            calldata[calldata.len() - 6..],
+            [
+                token_out,
+                transfer,
+                Felt::THREE,
+                cold_wallet,
+                Felt::from(950u32),
+                Felt::ZERO,
+            ]
+        );
     }
 
-    /// Get account address
-    pub fn account_address(&self) -> String {
-        self.account.address().to_string()
-    }
+    #[tokio::test]
+    async fn test_swap_to_caller_sends_swap_only() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let caller = client.account().address();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(Felt::from(0x111u32), Felt::from(0x222u32))
+            .caller(caller)
+            .build()
+            .unwrap();
 
-    /// Get contract address
-    pub fn contract_address(&self) -> String {
-        self.autoswappr_contract.address().to_string()
-    }
+        client
+            .execute_ekubo_swap_to(swap_data, Some(caller), 950, None)
+            .await
+            .unwrap();
 
-    /// Get the underlying provider
-    pub fn provider(&self) -> &JsonRpcClient<HttpTransport> {
-        &self.provider
+        assert_eq!(invoke_calldata(&server)[0], Felt::ONE);
     }
 
-    /// Get account reference for advanced usage
-    pub fn account(&self) -> &SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> {
-        &self.account
-    }
+    #[tokio::test]
+    async fn test_exact_output_swap_sets_sign_and_limit() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
 
-    /// Get AutoSwappr contract reference for advanced usage
-    pub fn autoswappr_contract(&self) -> &AutoSwapprContract {
-        &self.autoswappr_contract
+        client
+            .ekubo_swap_exact_output("0x111", "0x222", 100, 400)
+            .await
+            .unwrap();
+
+        // [n_calls, to, selector, calldata_len, mag, sign, is_token1, limit.low, limit.high, ...]
+        let calldata = invoke_calldata(&server);
+        assert_eq!(calldata[4], Felt::from(100u32));
+        assert_eq!(calldata[5], Felt::ONE);
+        assert_eq!(calldata[6], Felt::ONE);
+        // Price floor of 100 / 400 bounds the input to 400
+        assert_eq!(calldata[7], Felt::from((1u128 << 127) + 1));
+        assert_eq!(calldata[8], Felt::ZERO);
+
+        assert!(matches!(
+            client
+                .ekubo_swap_exact_output("0x111", "0x222", 0, 400)
+                .await,
+            Err(AutoSwapprError::ZeroAmount)
+        ));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types::connector::{
-        Amount, AutoSwappr, PoolKey, SwapData, SwapParameters, Uint256,
-    };
+    #[tokio::test]
+    async fn test_revoke_all_approvals_batches_calls() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
 
-    fn create_test_config() -> AutoSwapprConfig {
-        AutoSwapprConfig {
-            contract_address: "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b"
-                .to_string(),
-            rpc_url: "https://starknet-mainnet.public.blastapi.io/rpc/v0_7".to_string(),
-            account_address: "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
-                .to_string(),
-            private_key: "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
-                .to_string(),
+        client
+            .revoke_all_approvals(&["0x1", "0x2", "0x3"], "0x456")
+            .await
+            .unwrap();
+
+        let calldata = invoke_calldata(&server);
+        assert_eq!(calldata[0], Felt::THREE);
+        // Each call is [to, selector, 3, spender, 0, 0]
+        for (i, call) in calldata[1..].chunks(6).enumerate() {
+            assert_eq!(call[0], Felt::from(i as u32 + 1));
+            assert_eq!(call[3..], [Felt::from(0x456u32), Felt::ZERO, Felt::ZERO]);
         }
+
+        assert!(matches!(
+            client.revoke_all_approvals(&[], "0x456").await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
     }
 
     #[tokio::test]
-    async fn test_client_creation() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await;
-        // This should work now with real implementation
-        assert!(client.is_ok());
+    async fn test_dry_run_estimates_without_sending() {
+        let server = submitting_server().start().await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap()
+            .dry_run(true);
+        client.enable_history();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*crate::STRK, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
+
+        let first = client
+            .execute_ekubo_swap(swap_data.clone(), None)
+            .await
+            .unwrap();
+        let second = client
+            .execute_ekubo_swap_with_result(swap_data, None)
+            .await
+            .unwrap();
+
+        assert!(client.is_dry_run());
+        assert_eq!(first, second);
+        assert_ne!(first, format_tx_hash(Felt::from_hex("0xa0").unwrap()));
+        assert_eq!(server.requests("starknet_estimateFee").len(), 2);
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+        assert!(server.requests("starknet_getTransactionReceipt").is_empty());
+        assert!(client.swap_history().is_empty());
     }
 
     #[tokio::test]
-    async fn test_client_creation_with_invalid_address() {
-        let mut config = create_test_config();
-        config.account_address = "invalid_address".to_string();
+    async fn test_sequential_swaps_use_incrementing_nonces() {
+        let server = MockRpc::new()
+            .on_method_seq(
+                "starknet_getNonce",
+                vec![serde_json::json!("0x5"), serde_json::json!("0x9")],
+            )
+            .on_method("starknet_estimateFee", fee_estimate_json())
+            .on_method("starknet_getBlockWithTxs", block_json(2_000))
+            .on_method(
+                "starknet_addInvokeTransaction",
+                serde_json::json!({ "transaction_hash": "0xa0" }),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*crate::STRK, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
 
-        let client = AutoSwapprClient::new(config).await;
-        assert!(client.is_err());
+        for _ in 0..3 {
+            client
+                .execute_ekubo_swap(swap_data.clone(), None)
+                .await
+                .unwrap();
+        }
+        client.reset_nonce();
+        client.execute_ekubo_swap(swap_data, None).await.unwrap();
+
+        let nonces: Vec<_> = server
+            .requests("starknet_addInvokeTransaction")
+            .iter()
+            .map(|request| request["params"]["invoke_transaction"]["nonce"].clone())
+            .collect();
+        assert_eq!(nonces, ["0x5", "0x6", "0x7", "0x9"]);
+        assert_eq!(server.requests("starknet_getNonce").len(), 2);
     }
 
     #[tokio::test]
-    async fn test_client_creation_with_invalid_private_key() {
-        let mut config = create_test_config();
-        config.private_key = "invalid_key".to_string();
+    async fn test_concurrent_swaps_from_clones_use_distinct_nonces() {
+        let server = MockRpc::new()
+            .on_method("starknet_getNonce", serde_json::json!("0x5"))
+            .on_method("starknet_estimateFee", fee_estimate_json())
+            .on_method("starknet_getBlockWithTxs", block_json(2_000))
+            .on_method(
+                "starknet_addInvokeTransaction",
+                serde_json::json!({ "transaction_hash": "0xa0" }),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*crate::STRK, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
 
-        let client = AutoSwapprClient::new(config).await;
-        assert!(client.is_err());
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let client = client.clone();
+                let swap_data = swap_data.clone();
+                tokio::spawn(async move { client.execute_ekubo_swap(swap_data, None).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let mut nonces: Vec<_> = server
+            .requests("starknet_addInvokeTransaction")
+            .iter()
+            .map(|request| request["params"]["invoke_transaction"]["nonce"].clone())
+            .collect();
+        nonces.sort_by_key(|nonce| nonce.to_string());
+        assert_eq!(nonces, ["0x5", "0x6", "0x7", "0x8", "0x9"]);
     }
 
     #[tokio::test]
-    async fn test_client_creation_with_invalid_contract_address() {
-        let mut config = create_test_config();
-        config.contract_address = "invalid_contract".to_string();
+    async fn test_simulated_swap_not_sent_when_simulation_fails() {
+        let server = MockRpc::new()
+            .on_method("starknet_getNonce", serde_json::json!("0x0"))
+            .on_method_error("starknet_estimateFee", 41, "Transaction execution error")
+            .on_method("starknet_getBlockWithTxs", block_json(2_000))
+            .on_method(
+                "starknet_addInvokeTransaction",
+                serde_json::json!({ "transaction_hash": "0xfeed" }),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*crate::STRK, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
 
-        let client = AutoSwapprClient::new(config).await;
-        assert!(client.is_err());
+        let result = client.execute_ekubo_swap_simulated(swap_data, None).await;
+
+        assert!(
+            matches!(result, Err(AutoSwapprError::SwapFailed { reason }) if reason.starts_with("simulation failed"))
+        );
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
     }
 
     #[tokio::test]
-    async fn test_client_creation_with_invalid_rpc_url() {
-        let mut config = create_test_config();
-        config.rpc_url = "invalid_url".to_string();
+    async fn test_builder_with_defaults() {
+        let client = AutoSwapprClient::builder()
+            .account_address("0x1234")
+            .private_key("0x5678")
+            .build()
+            .await
+            .unwrap();
 
-        let client = AutoSwapprClient::new(config).await;
-        assert!(client.is_err());
+        assert_eq!(
+            client.contract_address(),
+            addresses::mainnet::autoswappr().to_string()
+        );
+        assert_eq!(client.rpc_url(), Network::Mainnet.rpc_url());
+        assert_eq!(client.retry_policy(), &RetryPolicy::default());
+        assert_eq!(client.concurrency_limit(), DEFAULT_CONCURRENCY_LIMIT);
     }
 
     #[tokio::test]
-    async fn test_contract_parameters() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
-        let params = client.get_contract_parameters().await;
-        // This will make a real contract call, so it might fail in tests
-        // but the method should exist and be callable
-        assert!(params.is_ok() || params.is_err());
+    async fn test_builder_with_overrides() {
+        let retry_policy = RetryPolicy {
+            max_retries: 1,
+            delay: std::time::Duration::from_millis(10),
+        };
+        let client = AutoSwapprClient::builder()
+            .account_address("0x1234")
+            .private_key("0x5678")
+            .network(Network::Custom("http://localhost:5050".to_string()))
+            .retry_policy(retry_policy.clone())
+            .concurrency_limit(2)
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(client.rpc_url(), "http://localhost:5050");
+        assert_eq!(client.retry_policy(), &retry_policy);
+        assert_eq!(client.concurrency_limit(), 2);
     }
 
     #[tokio::test]
-    async fn test_get_token_amount_in_usd() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
-
-        let eth_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
-        let amount = 1000000000000000000u128; // 1 ETH
+    async fn test_builder_requires_private_key() {
+        let client = AutoSwapprClient::builder()
+            .account_address("0x1234")
+            .build()
+            .await;
 
-        let result = client.get_token_amount_in_usd(eth_address, amount).await;
-        // This will make a real contract call
-        assert!(result.is_ok() || result.is_err());
+        assert!(matches!(client, Err(AutoSwapprError::InvalidInput { .. })));
     }
 
     #[tokio::test]
-    async fn test_get_token_amount_in_usd_formatted() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
+    async fn test_spec_version_mismatch() {
+        let server = MockRpc::new()
+            .on_method("starknet_specVersion", serde_json::json!("0.8.1"))
+            .start()
+            .await;
 
-        let eth_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
-        let amount = 1000000000000000000u128; // 1 ETH
-        let decimals = 18u8;
+        let result = AutoSwapprClient::new(test_config(&server.url)).await;
+        assert!(matches!(
+            result,
+            Err(AutoSwapprError::ProviderError { message })
+                if message.contains("0.8.1") && message.contains(SUPPORTED_SPEC_VERSION)
+        ));
 
-        let result = client
-            .get_token_amount_in_usd_formatted(eth_address, amount, decimals)
+        // Downgraded to a warning when mismatches are allowed
+        let client = AutoSwapprClient::builder()
+            .account_address(crate::test_utils::TEST_ACCOUNT)
+            .private_key("0x5678")
+            .network(Network::Custom(server.url.clone()))
+            .allow_spec_version_mismatch(true)
+            .build()
             .await;
-        // This will make a real contract call
-        assert!(result.is_ok() || result.is_err());
+        assert!(client.is_ok());
     }
 
     #[tokio::test]
-    async fn test_get_allowance() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
-
-        let token_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
-        let owner = "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
-        let spender = "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b";
+    async fn test_verify_decimals_mismatch() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(token, erc20_abi::DECIMALS, vec![Felt::from(6u8)])
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
 
-        let result = client.get_allowance(token_address, owner, spender).await;
-        // This will make a real contract call
-        assert!(result.is_ok() || result.is_err());
+        assert!(!client.verify_decimals("0x123", 18).await.unwrap());
+        assert!(client.verify_decimals("0x123", 6).await.unwrap());
     }
 
     #[tokio::test]
-    async fn test_get_allowance_with_invalid_address() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
-
-        let result = client
-            .get_allowance("invalid_address", "owner", "spender")
+    async fn test_swap_rejected_after_deadline() {
+        let server = MockRpc::new()
+            .on_method("starknet_getBlockWithTxHashes", block_json(2_000))
+            .start()
             .await;
-        assert!(result.is_err());
-    }
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*crate::STRK, *crate::USDC)
+            .caller(Felt::ONE)
+            .build()
+            .unwrap();
 
-    #[tokio::test]
-    async fn test_get_token_balance() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
+        let result = client.execute_ekubo_swap(swap_data, Some(1_000)).await;
 
-        let token_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+        assert!(
+            matches!(result, Err(AutoSwapprError::SwapFailed { reason }) if reason == "deadline passed")
+        );
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+        assert!(client.ensure_deadline(Some(3_000)).await.is_ok());
+        assert!(client.ensure_deadline(None).await.is_ok());
+    }
 
-        let result = client.get_token_balance(token_address).await;
-        // This will make a real contract call
-        assert!(result.is_ok() || result.is_err());
+    fn contract_parameters_with_owner(owner: Felt) -> Vec<Felt> {
+        vec![
+            Felt::from(1u8),
+            Felt::from(2u8),
+            Felt::from(3u8),
+            Felt::from(4u8),
+            owner,
+            Felt::ONE,
+            Felt::from(100u16),
+        ]
     }
 
     #[tokio::test]
-    async fn test_get_token_info() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
+    async fn test_preview_fee_percentage() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        // Percentage fee of 100 bps, on ETH only
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "contract_parameters",
+                contract_parameters_with_owner(Felt::ONE),
+            )
+            .on_call_seq(
+                contract,
+                "get_token_from_status_and_value",
+                vec![
+                    vec![Felt::ONE, Felt::ZERO],
+                    vec![Felt::ONE, Felt::ZERO],
+                    vec![Felt::ONE, Felt::ZERO],
+                    vec![Felt::ZERO, Felt::ZERO],
+                ],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
 
-        let token_address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+        assert_eq!(client.preview_fee(1_000_000, "ETH").await.unwrap(), 10_000);
+        assert_eq!(client.preview_fee(99, "ETH").await.unwrap(), 0);
+        assert_eq!(
+            client.preview_fee(u128::MAX, "ETH").await.unwrap(),
+            u128::MAX / 100
+        );
+        assert!(matches!(
+            client.preview_fee(1_000_000, "USDC").await,
+            Err(AutoSwapprError::UnsupportedToken { .. })
+        ));
+    }
 
-        let result = client.get_token_info(token_address).await;
-        // This will make a real contract call
-        assert!(result.is_ok() || result.is_err());
+    #[tokio::test]
+    async fn test_preview_fee_fixed_is_not_exposed() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let mut parameters = contract_parameters_with_owner(Felt::ONE);
+        parameters[5] = Felt::ZERO;
+        parameters[6] = Felt::ZERO;
+        let server = MockRpc::new()
+            .on_call(contract, "contract_parameters", parameters)
+            .on_call(
+                contract,
+                "get_token_from_status_and_value",
+                vec![Felt::ONE, Felt::ZERO],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            client.preview_fee(1_000_000, "ETH").await,
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
     }
 
     #[tokio::test]
-    async fn test_get_token_info_with_invalid_address() {
-        let config = create_test_config();
-        let client = AutoSwapprClient::new(config).await.unwrap();
+    async fn test_is_owner() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let account = Felt::from_hex(crate::test_utils::TEST_ACCOUNT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "contract_parameters",
+                contract_parameters_with_owner(account),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
 
-        let result = client.get_token_info("invalid_address").await;
-        assert!(result.is_err());
+        assert!(client.is_owner().await.unwrap());
     }
 
-    #[test]
-    fn test_client_addresses() {
-        let config = create_test_config();
-        // We can't create the client in a sync test, but we can test the config
-        assert_eq!(
-            config.contract_address,
-            "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b"
-        );
+    #[tokio::test]
+    async fn test_get_oracle_price() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let feed_id = Felt::from_bytes_be_slice(b"ETH/USD");
+        // oracle_address is the fourth contract parameter
+        let oracle = Felt::from(4u8);
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "contract_parameters",
+                contract_parameters_with_owner(Felt::ONE),
+            )
+            .on_call_seq(
+                contract,
+                "get_token_from_status_and_value",
+                vec![vec![Felt::ONE, feed_id], vec![Felt::ZERO, Felt::ZERO]],
+            )
+            .on_call(
+                oracle,
+                oracle_abi::GET_DATA_MEDIAN,
+                vec![
+                    Felt::from(250_000_000_000u128),
+                    Felt::from(8u8),
+                    Felt::from(1_700_000_000u64),
+                    Felt::from(5u8),
+                    Felt::ONE,
+                ],
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
+
+        let (price, timestamp) = client.get_oracle_price("ETH").await.unwrap();
+        assert_eq!(price, 250_000_000_000);
+        assert_eq!(timestamp, 1_700_000_000);
+
+        let requests = server.requests("starknet_call");
+        let request = requests
+            .iter()
+            .find(|request| request["params"]["request"]["contract_address"] == "0x4")
+            .unwrap();
         assert_eq!(
-            config.rpc_url,
-            "https://starknet-mainnet.public.blastapi.io/rpc/v0_7"
+            request["params"]["request"]["calldata"],
+            serde_json::json!(["0x0", format!("{:#x}", feed_id)])
         );
+
+        let result = client.get_oracle_price("ETH").await;
+        assert!(matches!(
+            result,
+            Err(AutoSwapprError::UnsupportedToken { .. })
+        ));
     }
 
-    #[test]
-    fn test_swap_data_creation() {
-        let swap_data = SwapData {
-            params: SwapParameters {
-                amount: Amount {
-                    mag: Uint256::from_u128(1000000000000000000u128), // 1 ETH
-                    sign: false,
-                },
-                sqrt_ratio_limit: Uint256::from_u128(0),
-                is_token1: false,
-                skip_ahead: 0,
-            },
-            pool_key: PoolKey {
-                token0: "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"
-                    .to_string(),
-                token1: "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"
-                    .to_string(),
-                fee: 3000,
-                tick_spacing: 60,
-                extension: "0x0".to_string(),
-            },
-            caller: "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
-                .to_string(),
-        };
+    #[tokio::test]
+    async fn test_admin_call_rejected_for_non_owner() {
+        let contract = Felt::from_hex(crate::test_utils::TEST_CONTRACT).unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract,
+                "contract_parameters",
+                contract_parameters_with_owner(Felt::from_hex("0xdead").unwrap()),
+            )
+            .start()
+            .await;
+        let client = AutoSwapprClient::new(test_config(&server.url))
+            .await
+            .unwrap();
 
-        assert_eq!(swap_data.params.amount.mag.low, 1000000000000000000u128);
-        assert_eq!(swap_data.pool_key.fee, 3000);
-        assert_eq!(swap_data.pool_key.tick_spacing, 60);
+        assert!(!client.is_owner().await.unwrap());
+
+        let result = client.set_fee_type(FeeType::Fixed, 0, true).await;
+        assert!(
+            matches!(result, Err(AutoSwapprError::Other { message }) if message == "not contract owner")
+        );
+        let result = client.remove_token_from("0x123", true).await;
+        assert!(matches!(result, Err(AutoSwapprError::Other { .. })));
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
     }
 
     #[test]
-    fn test_uint256_from_u128() {
-        let amount = 1000000000000000000u128; // 1 ETH
-        let uint256 = Uint256::from_u128(amount);
+    fn test_snapshot_to_json() {
+        let snapshot = ClientSnapshot {
+            account_address: "0x456".to_string(),
+            contract_address: "0x123".to_string(),
+            contract_parameters: ContractInfo {
+                fees_collector: "0xaaa".to_string(),
+                fibrous_exchange_address: "0xbbb".to_string(),
+                avnu_exchange_address: "0xccc".to_string(),
+                oracle_address: "0xddd".to_string(),
+                owner: "0xeee".to_string(),
+                fee_type: FeeType::Percentage,
+                percentage_fee: 250,
+            },
+        };
 
-        assert_eq!(uint256.low, amount);
-        assert_eq!(uint256.high, 0);
+        let json: serde_json::Value = serde_json::from_str(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(json["account_address"], "0x456");
+        assert_eq!(json["contract_address"], "0x123");
+        assert_eq!(json["contract_parameters"]["owner"], "0xeee");
+        assert_eq!(json["contract_parameters"]["fee_type"], "Percentage");
+        assert_eq!(json["contract_parameters"]["percentage_fee"], 250);
     }
 }
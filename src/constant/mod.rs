@@ -37,6 +37,17 @@ pub struct TokenInfo<'a> {
     name: &'a str,
 }
 
+impl From<TokenInfo<'_>> for crate::types::connector::TokenInfo {
+    fn from(token: TokenInfo<'_>) -> Self {
+        Self {
+            address: format!("{:#x}", token.address),
+            symbol: token.symbol.to_string(),
+            name: token.name.to_string(),
+            decimals: token.decimals,
+        }
+    }
+}
+
 impl Default for TokenAddress<'static> {
     fn default() -> Self {
         Self::new()
@@ -128,4 +139,16 @@ mod tests {
         let strk = TokenAddress::new().get_token_info("sol");
         assert_eq!(strk.unwrap().address, *STRK);
     }
+
+    #[test]
+    fn converts_to_connector_token_info() {
+        let eth = TokenAddress::new().get_token_info("ETH").unwrap();
+        let info: crate::types::connector::TokenInfo = eth.into();
+
+        assert!(info.address.starts_with("0x"));
+        assert_eq!(Felt::from_hex(&info.address).unwrap(), *ETH);
+        assert_eq!(info.symbol, "ETH");
+        assert_eq!(info.name, "Ether");
+        assert_eq!(info.decimals, 18);
+    }
 }
@@ -2,17 +2,19 @@
 // Based on the actual Cairo contract ABI
 
 use crate::types::connector::Uint256 as StarknetUint256;
-use starknet::providers::jsonrpc::HttpTransport;
 use starknet::{
     accounts::ConnectedAccount,
     core::{
-        types::{BlockId, BlockTag, Call, Felt, FunctionCall},
+        codec::Encode,
+        types::{
+            BlockId, BlockTag, Call, ContractExecutionError, EmittedEvent, EventFilter,
+            FeeEstimate, Felt, FunctionCall, StarknetError, TypedData, typed_data::Domain,
+        },
         utils::get_selector_from_name,
     },
     macros::selector,
-    providers::{JsonRpcClient, Provider},
+    providers::{Provider, ProviderError},
 };
-use std::sync::Arc;
 
 // Type aliases for compatibility
 type FieldElement = Felt;
@@ -20,7 +22,11 @@ type ContractAddress = Felt;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::types::connector::{ContractInfo, FeeType, SwapData};
+use crate::types::connector::{
+    AutoSwapprError, ContractInfo, FeeType, PoolKey, SwapData, SwapExecuted,
+};
+// Ekubo types shared with the connector module, so swap data has a single definition
+pub use crate::types::connector::{Delta, I129, SwapParameters, SwapResult};
 
 /// AutoSwappr Contract ABI definitions
 pub mod abi {
@@ -35,16 +41,91 @@ pub mod abi {
     pub const SET_FEE_TYPE: &str = "set_fee_type";
     pub const SUPPORT_NEW_TOKEN_FROM: &str = "support_new_token_from";
     pub const REMOVE_TOKEN_FROM: &str = "remove_token_from";
+
+    /// Event names
+    pub const SWAP_SUCCESSFUL: &str = "SwapSuccessful";
+}
+
+/// Entrypoint selectors of an AutoSwappr contract deployment.
+///
+/// [`ContractAbi::v1`] matches the current contract; a redeployment with renamed methods can
+/// be targeted by overriding fields and passing the ABI to
+/// [`AutoSwapprContract::new_with_abi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractAbi {
+    pub ekubo_swap: Felt,
+    pub ekubo_manual_swap: Felt,
+    pub avnu_swap: Felt,
+    pub fibrous_swap: Felt,
+    pub contract_parameters: Felt,
+    pub get_token_amount_in_usd: Felt,
+    pub get_token_from_status_and_value: Felt,
+    pub set_fee_type: Felt,
+    pub support_new_token_from: Felt,
+    pub remove_token_from: Felt,
+}
+
+impl ContractAbi {
+    /// Selectors of the method names in [`abi`]
+    pub fn v1() -> Self {
+        Self {
+            ekubo_swap: selector!("ekubo_swap"),
+            ekubo_manual_swap: selector!("ekubo_manual_swap"),
+            avnu_swap: selector!("avnu_swap"),
+            fibrous_swap: selector!("fibrous_swap"),
+            contract_parameters: selector!("contract_parameters"),
+            get_token_amount_in_usd: selector!("get_token_amount_in_usd"),
+            get_token_from_status_and_value: selector!("get_token_from_status_and_value"),
+            set_fee_type: selector!("set_fee_type"),
+            support_new_token_from: selector!("support_new_token_from"),
+            remove_token_from: selector!("remove_token_from"),
+        }
+    }
+}
+
+impl Default for ContractAbi {
+    fn default() -> Self {
+        Self::v1()
+    }
 }
 
 /// ERC20 Token ABI definitions
 pub mod erc20_abi {
     pub const APPROVE: &str = "approve";
+    pub const TRANSFER: &str = "transfer";
+    pub const TRANSFER_FROM: &str = "transfer_from";
     pub const ALLOWANCE: &str = "allowance";
     pub const BALANCE_OF: &str = "balance_of";
+    pub const TOTAL_SUPPLY: &str = "total_supply";
+    pub const TOTAL_SUPPLY_CAMEL: &str = "totalSupply";
     pub const DECIMALS: &str = "decimals";
     pub const SYMBOL: &str = "symbol";
     pub const NAME: &str = "name";
+    pub const PERMIT: &str = "permit";
+    pub const NONCES: &str = "nonces";
+    pub const SNIP12_METADATA: &str = "snip12_metadata";
+}
+
+/// Ekubo core contract ABI definitions
+pub mod ekubo_abi {
+    pub const GET_POOL_PRICE: &str = "get_pool_price";
+    pub const GET_POOL_LIQUIDITY: &str = "get_pool_liquidity";
+
+    /// Standard Ekubo fee tiers as (fee, tick_spacing)
+    pub const FEE_TIERS: [(u128, u128); 4] = [
+        (34028236692093847977029636859101184, 200),     // 0.01%
+        (170141183460469235273462165868118016, 1000),   // 0.05%
+        (1020847100762815390390123822295304634, 5982),  // 0.3%
+        (3402823669209384634633746074317682114, 19802), // 1%
+    ];
+}
+
+/// Pragma oracle ABI definitions
+pub mod oracle_abi {
+    pub const GET_DATA_MEDIAN: &str = "get_data_median";
+
+    /// Index of the `SpotEntry` variant of Pragma's `DataType` enum
+    pub const SPOT_ENTRY: u8 = 0;
 }
 
 /// Cairo type definitions matching the ABI
@@ -76,41 +157,202 @@ pub struct SwapParams {
     pub extra_data: Vec<FieldElement>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SwapResult {
-    pub delta: Delta,
+/// Ekubo pool price state
+#[derive(Debug, Clone)]
+pub struct PoolPrice {
+    pub sqrt_ratio: StarknetUint256,
+    pub tick: I129,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Delta {
-    pub amount0: I129,
-    pub amount1: I129,
+/// Build the `ekubo_swap`/`ekubo_manual_swap` calldata: the Cairo serialization of `SwapData`.
+///
+/// Layout: `[mag, sign, is_token1, sqrt_ratio_limit (low, high), skip_ahead, token0, token1,
+/// fee, tick_spacing, extension, caller]`.
+pub fn build_ekubo_calldata(swap_data: &SwapData) -> Result<Vec<Felt>, ContractError> {
+    let mut calldata = Vec::new();
+    swap_data
+        .encode(&mut calldata)
+        .map_err(|e| ContractError::SerializationError(e.to_string()))?;
+
+    Ok(calldata)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct I129 {
-    pub mag: u128,
-    pub sign: bool,
+/// Build the `avnu_swap` calldata
+#[allow(clippy::too_many_arguments)]
+pub fn build_avnu_calldata(
+    protocol_swapper: ContractAddress,
+    token_from_address: ContractAddress,
+    token_from_amount: StarknetUint256,
+    token_to_address: ContractAddress,
+    token_to_min_amount: StarknetUint256,
+    beneficiary: ContractAddress,
+    integrator_fee_amount_bps: u128,
+    integrator_fee_recipient: ContractAddress,
+    routes: &[Route],
+) -> Vec<Felt> {
+    // Convert amounts to (low, high) format
+    let (token_from_low, token_from_high) = token_from_amount.to_felts();
+    let (token_to_min_low, token_to_min_high) = token_to_min_amount.to_felts();
+
+    // Build calldata with proper serialization
+    let mut calldata = vec![
+        protocol_swapper,
+        token_from_address,
+        token_from_low,
+        token_from_high,
+        token_to_address,
+        token_to_min_low,
+        token_to_min_high,
+        beneficiary,
+        Felt::from(integrator_fee_amount_bps),
+        integrator_fee_recipient,
+    ];
+
+    // Add routes count first
+    calldata.push(Felt::from(routes.len()));
+
+    // Serialize each route: (token_from: felt, token_to: felt, exchange_address: felt, percent: u128, additional_swap_params: Array<felt>)
+    for route in routes {
+        calldata.push(route.token_from);
+        calldata.push(route.token_to);
+        calldata.push(route.exchange_address);
+        calldata.push(Felt::from(route.percent));
+
+        // Add additional_swap_params array length and data
+        calldata.push(Felt::from(route.additional_swap_params.len()));
+        calldata.extend_from_slice(&route.additional_swap_params);
+    }
+
+    calldata
+}
+
+/// Build the `fibrous_swap` calldata
+pub fn build_fibrous_calldata(
+    route_params: &RouteParams,
+    swap_params: &[SwapParams],
+    protocol_swapper: ContractAddress,
+    beneficiary: ContractAddress,
+) -> Vec<Felt> {
+    // Build calldata with proper serialization
+    let mut calldata = vec![protocol_swapper, beneficiary];
+
+    // Serialize route_params: (token_in: felt, token_out: felt, amount_in: u256, min_received: u256, destination: felt)
+    calldata.push(route_params.token_in);
+    calldata.push(route_params.token_out);
+
+    // Serialize amount_in and min_received (u256: low, high)
+    let (amount_in_low, amount_in_high) = route_params.amount_in.to_felts();
+    let (min_received_low, min_received_high) = route_params.min_received.to_felts();
+    calldata.extend([
+        amount_in_low,
+        amount_in_high,
+        min_received_low,
+        min_received_high,
+    ]);
+
+    calldata.push(route_params.destination);
+
+    // Add swap_params count
+    calldata.push(Felt::from(swap_params.len()));
+
+    // Serialize each swap_param: (token_in: felt, token_out: felt, rate: u32, protocol_id: u32, pool_address: felt, extra_data: Array<felt>)
+    for swap_param in swap_params {
+        calldata.push(swap_param.token_in);
+        calldata.push(swap_param.token_out);
+        calldata.push(Felt::from(swap_param.rate));
+        calldata.push(Felt::from(swap_param.protocol_id));
+        calldata.push(swap_param.pool_address);
+
+        // Add extra_data array length and data
+        calldata.push(Felt::from(swap_param.extra_data.len()));
+        calldata.extend_from_slice(&swap_param.extra_data);
+    }
+
+    calldata
+}
+
+/// Build the `set_fee_type` calldata, rejecting a percentage above 10000 bps (100%).
+///
+/// The contract takes no fixed fee amount in this call, so a fixed fee type must not carry a
+/// percentage either.
+pub fn build_set_fee_type_calldata(
+    fee_type: FeeType,
+    percentage_fee: u16,
+) -> Result<Vec<Felt>, ContractError> {
+    let fee_type_felt = match fee_type {
+        FeeType::Fixed if percentage_fee != 0 => {
+            return Err(ContractError::SerializationError(format!(
+                "Fixed fee type takes no percentage, got {} bps",
+                percentage_fee
+            )));
+        }
+        FeeType::Percentage if percentage_fee > 10_000 => {
+            return Err(ContractError::SerializationError(format!(
+                "Percentage fee {} bps exceeds 10000",
+                percentage_fee
+            )));
+        }
+        FeeType::Fixed => Felt::from(0),
+        FeeType::Percentage => Felt::from(1),
+    };
+
+    Ok(vec![fee_type_felt, Felt::from(percentage_fee)])
+}
+
+/// Submit a single call from `account`, returning the transaction hash
+async fn send_call<A: ConnectedAccount + Sync + Send>(
+    account: &A,
+    call: Call,
+) -> Result<Felt, ContractError> {
+    let execution = account
+        .execute_v3(vec![call])
+        .send()
+        .await
+        .map_err(|e| ContractError::AccountError(e.to_string()))?;
+
+    Ok(execution.transaction_hash)
 }
 
 /// Real AutoSwappr Contract implementation
+#[derive(Clone)]
 pub struct AutoSwapprContract {
     contract_address: ContractAddress,
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    block_id: BlockId,
+    abi: ContractAbi,
 }
 
 impl AutoSwapprContract {
     /// Create a new AutoSwappr contract instance
-    pub fn new(
-        contract_address: ContractAddress,
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-    ) -> Self {
+    pub fn new(contract_address: ContractAddress) -> Self {
+        Self::new_with_abi(contract_address, ContractAbi::v1())
+    }
+
+    /// Create a contract instance calling the selectors in `abi`, for deployments whose
+    /// methods differ from [`ContractAbi::v1`]
+    pub fn new_with_abi(contract_address: ContractAddress, abi: ContractAbi) -> Self {
         Self {
             contract_address,
-            provider,
+            block_id: BlockId::Tag(BlockTag::Latest),
+            abi,
         }
     }
 
+    /// Get the selectors this instance calls
+    pub fn abi(&self) -> &ContractAbi {
+        &self.abi
+    }
+
+    /// Read state at `block_id` instead of the latest block
+    pub fn with_block_id(mut self, block_id: BlockId) -> Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Get the block that read calls query
+    pub fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+
     /// Get the contract address
     pub fn address(&self) -> ContractAddress {
         self.contract_address
@@ -121,53 +363,111 @@ impl AutoSwapprContract {
         &self,
         provider: &P,
     ) -> Result<ContractInfo, ContractError> {
-        let result = provider
+        let result = self.call_contract_parameters(provider).await?;
+
+        parse_contract_parameters(&result)
+    }
+
+    /// Read `contract_parameters` and check the response has exactly the shape an AutoSwappr
+    /// deployment returns, to catch a wrong contract address before swapping through it
+    pub async fn verify_contract_parameters<P: Provider>(
+        &self,
+        provider: &P,
+    ) -> Result<ContractInfo, ContractError> {
+        let result = self.call_contract_parameters(provider).await?;
+        if result.len() != CONTRACT_PARAMETERS_LEN {
+            return Err(ContractError::DeserializationError(format!(
+                "contract_parameters returned {} values, expected {}",
+                result.len(),
+                CONTRACT_PARAMETERS_LEN
+            )));
+        }
+
+        parse_contract_parameters(&result)
+    }
+
+    /// Get the `SwapSuccessful` events emitted between `from_block` and `to_block`
+    /// (inclusive), following continuation tokens until the range is exhausted
+    pub async fn get_swap_events<P: Provider>(
+        &self,
+        provider: &P,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<SwapExecuted>, ContractError> {
+        let swap_successful = get_selector_from_name(abi::SWAP_SUCCESSFUL)
+            .map_err(|e| ContractError::CallFailed(e.to_string()))?;
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(from_block)),
+            to_block: Some(BlockId::Number(to_block)),
+            address: Some(self.contract_address),
+            keys: Some(vec![vec![swap_successful]]),
+        };
+
+        let mut swaps = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let page = provider
+                .get_events(filter.clone(), continuation_token, EVENTS_PAGE_SIZE)
+                .await
+                .map_err(ContractError::ProviderError)?;
+            for event in &page.events {
+                swaps.push(parse_swap_event(event)?);
+            }
+
+            match page.continuation_token {
+                Some(token) => continuation_token = Some(token),
+                None => return Ok(swaps),
+            }
+        }
+    }
+
+    async fn call_contract_parameters<P: Provider>(
+        &self,
+        provider: &P,
+    ) -> Result<Vec<Felt>, ContractError> {
+        provider
             .call(
                 FunctionCall {
                     contract_address: self.contract_address,
-                    entry_point_selector: selector!("contract_parameters"),
+                    entry_point_selector: self.abi.contract_parameters,
                     calldata: vec![],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
+            .map_err(ContractError::ProviderError)
+    }
 
-        // Parse the result according to the actual Cairo contract return type
-        // Expected return: (fees_collector: felt, fibrous_exchange_address: felt,
-        // avnu_exchange_address: felt, oracle_address: felt, owner: felt, fee_type: u8, percentage_fee: u16)
-        if result.len() < 7 {
-            return Err(ContractError::DeserializationError(
-                "Insufficient return values from contract_parameters".to_string(),
-            ));
+    fn contract_call(&self, selector: Felt, calldata: Vec<Felt>) -> Call {
+        Call {
+            to: self.contract_address,
+            selector,
+            calldata,
         }
+    }
 
-        let fees_collector = result[0].to_string();
-        let fibrous_exchange_address = result[1].to_string();
-        let avnu_exchange_address = result[2].to_string();
-        let oracle_address = result[3].to_string();
-        let owner = result[4].to_string();
-
-        // Parse fee_type (0 = Fixed, 1 = Percentage)
-        let fee_type_raw: u8 = result[5].try_into().unwrap_or(0);
-        let fee_type = match fee_type_raw {
-            0 => FeeType::Fixed,
-            1 => FeeType::Percentage,
-            _ => FeeType::Fixed, // Default to Fixed for unknown values
-        };
+    /// Build the `ekubo_swap` call for `swap_data` without sending it.
+    ///
+    /// Pass the returned [`Call`] to `execute_v3` alongside other protocol calls to
+    /// compose an atomic multicall (e.g. unwrap, swap, stake).
+    pub fn ekubo_swap_call(&self, swap_data: &SwapData) -> Result<Call, ContractError> {
+        let calldata = build_ekubo_calldata(swap_data)?;
+        Ok(self.contract_call(self.abi.ekubo_swap, calldata))
+    }
 
-        // Parse percentage_fee
-        let percentage_fee: u16 = result[6].try_into().unwrap_or(0);
+    /// Estimate the fee of an ekubo swap without submitting it
+    pub async fn estimate_ekubo_swap_fee<A: ConnectedAccount + Sync + Send>(
+        &self,
+        account: &A,
+        swap_data: &SwapData,
+    ) -> Result<FeeEstimate, ContractError> {
+        let call = self.ekubo_swap_call(swap_data)?;
 
-        Ok(ContractInfo {
-            fees_collector,
-            fibrous_exchange_address,
-            avnu_exchange_address,
-            oracle_address,
-            owner,
-            fee_type,
-            percentage_fee,
-        })
+        account
+            .execute_v3(vec![call])
+            .estimate_fee()
+            .await
+            .map_err(|e| ContractError::AccountError(e.to_string()))
     }
 
     /// Execute ekubo swap
@@ -176,65 +476,14 @@ impl AutoSwapprContract {
         account: &A,
         swap_data: SwapData,
     ) -> Result<Felt, ContractError> {
-        // Properly serialize SwapData according to Cairo ABI
-        // Expected calldata: (amount: I129, sqrt_ratio_limit: u256, is_token1: bool, skip_ahead: u32, pool_key: PoolKey, caller: felt)
-        let mut calldata = Vec::new();
-
-        // Serialize amount (I129: mag: u128, sign: bool)
-        let (amount_low, amount_high) =
-            conversions::u128_to_uint256(swap_data.params.amount.mag.low);
-        calldata.push(amount_low);
-        calldata.push(amount_high);
-        calldata.push(Felt::from(if swap_data.params.amount.sign { 1 } else { 0 }));
-
-        // Serialize sqrt_ratio_limit (u256: low, high)
-        let (sqrt_low, sqrt_high) =
-            conversions::u128_to_uint256(swap_data.params.sqrt_ratio_limit.low);
-        calldata.push(sqrt_low);
-        calldata.push(sqrt_high);
-
-        // Serialize is_token1 (bool)
-        calldata.push(Felt::from(if swap_data.params.is_token1 { 1 } else { 0 }));
-
-        // Serialize skip_ahead (u32)
-        calldata.push(Felt::from(swap_data.params.skip_ahead));
-
-        // Serialize pool_key (PoolKey: token0, token1, fee, tick_spacing, extension)
-        calldata.push(
-            Felt::from_hex(&swap_data.pool_key.token0)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
-        );
-        calldata.push(
-            Felt::from_hex(&swap_data.pool_key.token1)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
-        );
-        calldata.push(Felt::from(swap_data.pool_key.fee));
-        calldata.push(Felt::from(swap_data.pool_key.tick_spacing));
-        calldata.push(
-            Felt::from_hex(&swap_data.pool_key.extension)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
-        );
-
-        // Serialize caller (felt)
-        calldata.push(
-            Felt::from_hex(&swap_data.caller)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
-        );
-
-        let call = Call {
-            to: self.contract_address,
-            selector: get_selector_from_name(abi::EKUBO_SWAP)
-                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
-            calldata,
-        };
-
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
+        let call = self.ekubo_swap_call(&swap_data)?;
+        send_call(account, call).await
+    }
 
-        Ok(execution.transaction_hash)
+    /// Build the `ekubo_manual_swap` call for `swap_data`
+    pub fn ekubo_manual_swap_call(&self, swap_data: &SwapData) -> Result<Call, ContractError> {
+        let calldata = build_ekubo_calldata(swap_data)?;
+        Ok(self.contract_call(self.abi.ekubo_manual_swap, calldata))
     }
 
     /// Execute ekubo manual swap
@@ -243,67 +492,40 @@ impl AutoSwapprContract {
         account: &A,
         swap_data: SwapData,
     ) -> Result<Felt, ContractError> {
-        // Same serialization as ekubo_swap but for manual execution
-        let mut calldata = Vec::new();
-
-        // Serialize amount (I129: mag: u128, sign: bool)
-        let (amount_low, amount_high) =
-            conversions::u128_to_uint256(swap_data.params.amount.mag.low);
-        calldata.push(amount_low);
-        calldata.push(amount_high);
-        calldata.push(Felt::from(if swap_data.params.amount.sign { 1 } else { 0 }));
-
-        // Serialize sqrt_ratio_limit (u256: low, high)
-        let (sqrt_low, sqrt_high) =
-            conversions::u128_to_uint256(swap_data.params.sqrt_ratio_limit.low);
-        calldata.push(sqrt_low);
-        calldata.push(sqrt_high);
-
-        // Serialize is_token1 (bool)
-        calldata.push(Felt::from(if swap_data.params.is_token1 { 1 } else { 0 }));
-
-        // Serialize skip_ahead (u32)
-        calldata.push(Felt::from(swap_data.params.skip_ahead));
-
-        // Serialize pool_key (PoolKey: token0, token1, fee, tick_spacing, extension)
-        calldata.push(
-            Felt::from_hex(&swap_data.pool_key.token0)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
-        );
-        calldata.push(
-            Felt::from_hex(&swap_data.pool_key.token1)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
-        );
-        calldata.push(Felt::from(swap_data.pool_key.fee));
-        calldata.push(Felt::from(swap_data.pool_key.tick_spacing));
-        calldata.push(
-            Felt::from_hex(&swap_data.pool_key.extension)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
-        );
+        let call = self.ekubo_manual_swap_call(&swap_data)?;
+        send_call(account, call).await
+    }
 
-        // Serialize caller (felt)
-        calldata.push(
-            Felt::from_hex(&swap_data.caller)
-                .map_err(|e| ContractError::InvalidAddress(e.to_string()))?,
+    /// Build the `avnu_swap` call without sending it, for composing a multicall
+    #[allow(clippy::too_many_arguments)]
+    pub fn avnu_swap_call(
+        &self,
+        protocol_swapper: ContractAddress,
+        token_from_address: ContractAddress,
+        token_from_amount: StarknetUint256,
+        token_to_address: ContractAddress,
+        token_to_min_amount: StarknetUint256,
+        beneficiary: ContractAddress,
+        integrator_fee_amount_bps: u128,
+        integrator_fee_recipient: ContractAddress,
+        routes: &[Route],
+    ) -> Result<Call, ContractError> {
+        let calldata = build_avnu_calldata(
+            protocol_swapper,
+            token_from_address,
+            token_from_amount,
+            token_to_address,
+            token_to_min_amount,
+            beneficiary,
+            integrator_fee_amount_bps,
+            integrator_fee_recipient,
+            routes,
         );
-
-        let call = Call {
-            to: self.contract_address,
-            selector: get_selector_from_name(abi::EKUBO_MANUAL_SWAP)
-                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
-            calldata,
-        };
-
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
-
-        Ok(execution.transaction_hash)
+        Ok(self.contract_call(self.abi.avnu_swap, calldata))
     }
 
     /// Execute AVNU swap
+    #[allow(clippy::too_many_arguments)]
     pub async fn avnu_swap<A: ConnectedAccount + Sync + Send>(
         &self,
         account: &A,
@@ -317,56 +539,31 @@ impl AutoSwapprContract {
         integrator_fee_recipient: ContractAddress,
         routes: Vec<Route>,
     ) -> Result<Felt, ContractError> {
-        // Convert amounts to (low, high) format
-        let (token_from_low, token_from_high) = conversions::u128_to_uint256(token_from_amount.low);
-        let (token_to_min_low, token_to_min_high) =
-            conversions::u128_to_uint256(token_to_min_amount.low);
-
-        // Build calldata with proper serialization
-        let mut calldata = vec![
+        let call = self.avnu_swap_call(
             protocol_swapper,
             token_from_address,
-            token_from_low,
-            token_from_high,
+            token_from_amount,
             token_to_address,
-            token_to_min_low,
-            token_to_min_high,
+            token_to_min_amount,
             beneficiary,
-            Felt::from(integrator_fee_amount_bps),
+            integrator_fee_amount_bps,
             integrator_fee_recipient,
-        ];
-
-        // Add routes count first
-        calldata.push(Felt::from(routes.len()));
-
-        // Serialize each route: (token_from: felt, token_to: felt, exchange_address: felt, percent: u128, additional_swap_params: Array<felt>)
-        for route in routes {
-            calldata.push(route.token_from);
-            calldata.push(route.token_to);
-            calldata.push(route.exchange_address);
-            calldata.push(Felt::from(route.percent));
-
-            // Add additional_swap_params array length and data
-            calldata.push(Felt::from(route.additional_swap_params.len()));
-            for param in route.additional_swap_params {
-                calldata.push(param);
-            }
-        }
-
-        let call = Call {
-            to: self.contract_address,
-            selector: get_selector_from_name(abi::AVNU_SWAP)
-                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
-            calldata,
-        };
-
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
+            &routes,
+        )?;
+        send_call(account, call).await
+    }
 
-        Ok(execution.transaction_hash)
+    /// Build the `fibrous_swap` call without sending it, for composing a multicall
+    pub fn fibrous_swap_call(
+        &self,
+        route_params: &RouteParams,
+        swap_params: &[SwapParams],
+        protocol_swapper: ContractAddress,
+        beneficiary: ContractAddress,
+    ) -> Result<Call, ContractError> {
+        let calldata =
+            build_fibrous_calldata(route_params, swap_params, protocol_swapper, beneficiary);
+        Ok(self.contract_call(self.abi.fibrous_swap, calldata))
     }
 
     /// Execute Fibrous swap
@@ -378,59 +575,9 @@ impl AutoSwapprContract {
         protocol_swapper: ContractAddress,
         beneficiary: ContractAddress,
     ) -> Result<Felt, ContractError> {
-        // Build calldata with proper serialization
-        let mut calldata = vec![protocol_swapper, beneficiary];
-
-        // Serialize route_params: (token_in: felt, token_out: felt, amount_in: u256, min_received: u256, destination: felt)
-        calldata.push(route_params.token_in);
-        calldata.push(route_params.token_out);
-
-        // Serialize amount_in (u256: low, high)
-        let (amount_in_low, amount_in_high) =
-            conversions::u128_to_uint256(route_params.amount_in.low);
-        calldata.push(amount_in_low);
-        calldata.push(amount_in_high);
-
-        // Serialize min_received (u256: low, high)
-        let (min_received_low, min_received_high) =
-            conversions::u128_to_uint256(route_params.min_received.low);
-        calldata.push(min_received_low);
-        calldata.push(min_received_high);
-
-        calldata.push(route_params.destination);
-
-        // Add swap_params count
-        calldata.push(Felt::from(swap_params.len()));
-
-        // Serialize each swap_param: (token_in: felt, token_out: felt, rate: u32, protocol_id: u32, pool_address: felt, extra_data: Array<felt>)
-        for swap_param in swap_params {
-            calldata.push(swap_param.token_in);
-            calldata.push(swap_param.token_out);
-            calldata.push(Felt::from(swap_param.rate));
-            calldata.push(Felt::from(swap_param.protocol_id));
-            calldata.push(swap_param.pool_address);
-
-            // Add extra_data array length and data
-            calldata.push(Felt::from(swap_param.extra_data.len()));
-            for data in swap_param.extra_data {
-                calldata.push(data);
-            }
-        }
-
-        let call = Call {
-            to: self.contract_address,
-            selector: get_selector_from_name(abi::FIBROUS_SWAP)
-                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
-            calldata,
-        };
-
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
-
-        Ok(execution.transaction_hash)
+        let call =
+            self.fibrous_swap_call(&route_params, &swap_params, protocol_swapper, beneficiary)?;
+        send_call(account, call).await
     }
 
     /// Get token amount in USD
@@ -441,55 +588,85 @@ impl AutoSwapprContract {
         token_amount: StarknetUint256,
     ) -> Result<StarknetUint256, ContractError> {
         // Convert token_amount to (low, high) felts for uint256
-        let (amount_low, amount_high) = conversions::u128_to_uint256(token_amount.low);
+        let (amount_low, amount_high) = token_amount.to_felts();
 
         let result = provider
             .call(
                 FunctionCall {
                     contract_address: self.contract_address,
-                    entry_point_selector: selector!("get_token_amount_in_usd"),
+                    entry_point_selector: self.abi.get_token_amount_in_usd,
                     calldata: vec![token, amount_low, amount_high],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
+            .map_err(ContractError::ProviderError)?;
 
         // Parse the result - should return a uint256 (low, high)
-        let usd_amount_low = result.get(0).copied().unwrap_or(Felt::ZERO);
-        let usd_amount_high = result.get(1).copied().unwrap_or(Felt::ZERO);
-
-        Ok(StarknetUint256 {
-            low: usd_amount_low.try_into().unwrap_or(0),
-            high: usd_amount_high.try_into().unwrap_or(0),
-        })
+        conversions::felts_to_uint256(&result, "get_token_amount_in_usd")
     }
 
-    /// Get token from status and value
+    /// Get token from status and value.
+    ///
+    /// The value is read as a u256 `(low, high)` pair; deployments returning a single felt
+    /// value are still accepted.
     pub async fn get_token_from_status_and_value<P: Provider>(
         &self,
         provider: &P,
         token_from: ContractAddress,
-    ) -> Result<(bool, FieldElement), ContractError> {
+    ) -> Result<(bool, StarknetUint256), ContractError> {
         let result = provider
             .call(
                 FunctionCall {
                     contract_address: self.contract_address,
-                    entry_point_selector: selector!("get_token_from_status_and_value"),
+                    entry_point_selector: self.abi.get_token_from_status_and_value,
                     calldata: vec![token_from],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
+            .map_err(ContractError::ProviderError)?;
+
+        // Parse the result - should return (bool, u256), or (bool, felt) on older deployments
+        let value = match result.len() {
+            0 | 1 => {
+                return Err(ContractError::DeserializationError(
+                    "Insufficient return values from get_token_from_status_and_value".to_string(),
+                ));
+            }
+            2 => StarknetUint256::from(result[1]),
+            _ => conversions::felts_to_uint256(&result[1..], "get_token_from_status_and_value")?,
+        };
 
-        // Parse the result - should return (bool, felt)
-        let status = result.get(0).map(|f| f != &Felt::ZERO).unwrap_or(false);
-        let value = result.get(1).copied().unwrap_or(FieldElement::ZERO);
+        Ok((result[0] != Felt::ZERO, value))
+    }
+
+    /// Get token from status and value, with the value as a single felt
+    #[deprecated(note = "use `get_token_from_status_and_value`, which reads the full u256 value")]
+    pub async fn get_token_from_status_and_value_felt<P: Provider>(
+        &self,
+        provider: &P,
+        token_from: ContractAddress,
+    ) -> Result<(bool, FieldElement), ContractError> {
+        let (status, value) = self
+            .get_token_from_status_and_value(provider, token_from)
+            .await?;
+        let value = Felt::try_from(value)
+            .map_err(|e| ContractError::DeserializationError(e.to_string()))?;
 
         Ok((status, value))
     }
 
+    /// Build the `set_fee_type` call
+    pub fn set_fee_type_call(
+        &self,
+        fee_type: FeeType,
+        percentage_fee: u16,
+    ) -> Result<Call, ContractError> {
+        let calldata = build_set_fee_type_calldata(fee_type, percentage_fee)?;
+        Ok(self.contract_call(self.abi.set_fee_type, calldata))
+    }
+
     /// Set fee type
     pub async fn set_fee_type<A: ConnectedAccount + Sync + Send>(
         &self,
@@ -497,26 +674,17 @@ impl AutoSwapprContract {
         fee_type: FeeType,
         percentage_fee: u16,
     ) -> Result<Felt, ContractError> {
-        // Convert fee_type to felt (assuming it's an enum with numeric values)
-        let fee_type_felt = match fee_type {
-            FeeType::Fixed => Felt::from(0),
-            FeeType::Percentage => Felt::from(1),
-        };
-
-        let call = Call {
-            to: self.contract_address,
-            selector: get_selector_from_name(abi::SET_FEE_TYPE)
-                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
-            calldata: vec![fee_type_felt, Felt::from(percentage_fee)],
-        };
-
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
+        let call = self.set_fee_type_call(fee_type, percentage_fee)?;
+        send_call(account, call).await
+    }
 
-        Ok(execution.transaction_hash)
+    /// Build the `support_new_token_from` call
+    pub fn support_new_token_from_call(
+        &self,
+        token_from: ContractAddress,
+        feed_id: FieldElement,
+    ) -> Result<Call, ContractError> {
+        Ok(self.contract_call(self.abi.support_new_token_from, vec![token_from, feed_id]))
     }
 
     /// Support new token from
@@ -526,20 +694,16 @@ impl AutoSwapprContract {
         token_from: ContractAddress,
         feed_id: FieldElement,
     ) -> Result<Felt, ContractError> {
-        let call = Call {
-            to: self.contract_address,
-            selector: get_selector_from_name(abi::SUPPORT_NEW_TOKEN_FROM)
-                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
-            calldata: vec![token_from, feed_id],
-        };
-
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
+        let call = self.support_new_token_from_call(token_from, feed_id)?;
+        send_call(account, call).await
+    }
 
-        Ok(execution.transaction_hash)
+    /// Build the `remove_token_from` call
+    pub fn remove_token_from_call(
+        &self,
+        token_from: ContractAddress,
+    ) -> Result<Call, ContractError> {
+        Ok(self.contract_call(self.abi.remove_token_from, vec![token_from]))
     }
 
     /// Remove token from
@@ -548,73 +712,391 @@ impl AutoSwapprContract {
         account: &A,
         token_from: ContractAddress,
     ) -> Result<Felt, ContractError> {
-        let call = Call {
-            to: self.contract_address,
-            selector: get_selector_from_name(abi::REMOVE_TOKEN_FROM)
-                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
-            calldata: vec![token_from],
-        };
+        let call = self.remove_token_from_call(token_from)?;
+        send_call(account, call).await
+    }
+}
 
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
+// Events requested per `starknet_getEvents` page
+const EVENTS_PAGE_SIZE: u64 = 100;
+
+// Parse a `SwapSuccessful` event, whose fields are all in its data: token_from_address,
+// token_from_amount (u256), token_to_address, token_to_amount (u256), beneficiary, provider
+fn parse_swap_event(event: &EmittedEvent) -> Result<SwapExecuted, ContractError> {
+    let data = &event.data;
+    if data.len() < 8 {
+        return Err(ContractError::DeserializationError(format!(
+            "SwapSuccessful event has {} data values, expected 8",
+            data.len()
+        )));
+    }
+
+    Ok(SwapExecuted {
+        token_from: data[0],
+        amount_from: conversions::felts_to_uint256(&data[1..3], abi::SWAP_SUCCESSFUL)?,
+        token_to: data[3],
+        amount_to: conversions::felts_to_uint256(&data[4..6], abi::SWAP_SUCCESSFUL)?,
+        beneficiary: data[6],
+        provider: data[7],
+        block_number: event.block_number,
+        transaction_hash: event.transaction_hash,
+    })
+}
+
+// Cairo 1 contracts report a missing entrypoint as a revert rather than with
+// `EntrypointNotFound`, so look for it in the innermost revert message
+fn is_entrypoint_not_found(error: &ContractExecutionError) -> bool {
+    match error {
+        ContractExecutionError::Nested(inner) => is_entrypoint_not_found(&inner.error),
+        ContractExecutionError::Message(message) => {
+            message.contains("ENTRYPOINT_NOT_FOUND")
+                || (message.contains("Entry point") && message.contains("not found"))
+        }
+    }
+}
 
-        Ok(execution.transaction_hash)
+// Number of values `contract_parameters` returns
+const CONTRACT_PARAMETERS_LEN: usize = 7;
+
+// Parse `contract_parameters` output, tolerating extra trailing values
+fn parse_contract_parameters(result: &[Felt]) -> Result<ContractInfo, ContractError> {
+    // Parse the result according to the actual Cairo contract return type
+    // Expected return: (fees_collector: felt, fibrous_exchange_address: felt,
+    // avnu_exchange_address: felt, oracle_address: felt, owner: felt, fee_type: u8, percentage_fee: u16)
+    if result.len() < CONTRACT_PARAMETERS_LEN {
+        return Err(ContractError::DeserializationError(
+            "Insufficient return values from contract_parameters".to_string(),
+        ));
     }
+
+    let fees_collector = result[0].to_string();
+    let fibrous_exchange_address = result[1].to_string();
+    let avnu_exchange_address = result[2].to_string();
+    let oracle_address = result[3].to_string();
+    let owner = result[4].to_string();
+
+    // Parse fee_type (0 = Fixed, 1 = Percentage); anything else is a corrupted response
+    let fee_type = if result[5] == Felt::ZERO {
+        FeeType::Fixed
+    } else if result[5] == Felt::ONE {
+        FeeType::Percentage
+    } else {
+        return Err(ContractError::DeserializationError(format!(
+            "Invalid fee_type in contract_parameters: {:#x}",
+            result[5]
+        )));
+    };
+
+    let percentage_fee: u16 = result[6].try_into().map_err(|_| {
+        ContractError::DeserializationError(format!(
+            "percentage_fee in contract_parameters exceeds u16: {:#x}",
+            result[6]
+        ))
+    })?;
+
+    Ok(ContractInfo {
+        fees_collector,
+        fibrous_exchange_address,
+        avnu_exchange_address,
+        oracle_address,
+        owner,
+        fee_type,
+        percentage_fee,
+    })
 }
 
 /// Real ERC20 Token contract implementation
 pub struct Erc20Contract {
     contract_address: ContractAddress,
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    block_id: BlockId,
 }
 
 impl Erc20Contract {
     /// Create a new ERC20 contract instance
-    pub fn new(
-        contract_address: ContractAddress,
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-    ) -> Self {
+    pub fn new(contract_address: ContractAddress) -> Self {
         Self {
             contract_address,
-            provider,
+            block_id: BlockId::Tag(BlockTag::Latest),
         }
     }
 
+    /// Read state at `block_id` instead of the latest block
+    pub fn with_block_id(mut self, block_id: BlockId) -> Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Get the block that read calls query
+    pub fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+
     /// Get the contract address
     pub fn address(&self) -> ContractAddress {
         self.contract_address
     }
 
-    /// Approve token spending
-    pub async fn approve<A: ConnectedAccount + Sync + Send>(
+    /// Build the `approve` call granting `spender` an allowance of `amount`
+    pub fn approve_call(
         &self,
-        account: &A,
         spender: ContractAddress,
         amount: StarknetUint256,
-    ) -> Result<Felt, ContractError> {
+    ) -> Result<Call, ContractError> {
         // Convert amount to (low, high) felts for uint256
-        let (amount_low, amount_high) = conversions::u128_to_uint256(amount.low);
+        let (amount_low, amount_high) = amount.to_felts();
 
         // Prepare the calldata: [spender, amount_low, amount_high]
         let calldata = vec![spender, amount_low, amount_high];
 
-        let call = Call {
+        Ok(Call {
             to: self.contract_address,
             selector: get_selector_from_name(erc20_abi::APPROVE)
                 .map_err(|e| ContractError::CallFailed(e.to_string()))?,
             calldata,
-        };
+        })
+    }
 
-        let execution = account
-            .execute_v3(vec![call])
-            .send()
-            .await
-            .map_err(|e| ContractError::AccountError(e.to_string()))?;
+    /// Approve token spending
+    pub async fn approve<A: ConnectedAccount + Sync + Send>(
+        &self,
+        account: &A,
+        spender: ContractAddress,
+        amount: StarknetUint256,
+    ) -> Result<Felt, ContractError> {
+        let call = self.approve_call(spender, amount)?;
+
+        send_call(account, call).await
+    }
+
+    /// Build the `transfer` call sending `amount` to `to`
+    pub fn transfer_call(
+        &self,
+        to: ContractAddress,
+        amount: StarknetUint256,
+    ) -> Result<Call, ContractError> {
+        // Prepare the calldata: [to, amount_low, amount_high]
+        let (amount_low, amount_high) = amount.to_felts();
+
+        Ok(Call {
+            to: self.contract_address,
+            selector: get_selector_from_name(erc20_abi::TRANSFER)
+                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
+            calldata: vec![to, amount_low, amount_high],
+        })
+    }
+
+    /// Transfer tokens from the account to `to`
+    pub async fn transfer<A: ConnectedAccount + Sync + Send>(
+        &self,
+        account: &A,
+        to: ContractAddress,
+        amount: StarknetUint256,
+    ) -> Result<Felt, ContractError> {
+        let call = self.transfer_call(to, amount)?;
+
+        send_call(account, call).await
+    }
+
+    /// Build the `transfer_from` call moving `amount` from `from` to `to`
+    pub fn transfer_from_call(
+        &self,
+        from: ContractAddress,
+        to: ContractAddress,
+        amount: StarknetUint256,
+    ) -> Result<Call, ContractError> {
+        // Prepare the calldata: [from, to, amount_low, amount_high]
+        let (amount_low, amount_high) = amount.to_felts();
+
+        Ok(Call {
+            to: self.contract_address,
+            selector: get_selector_from_name(erc20_abi::TRANSFER_FROM)
+                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
+            calldata: vec![from, to, amount_low, amount_high],
+        })
+    }
+
+    /// Transfer tokens from `from` to `to` using the account's allowance
+    pub async fn transfer_from<A: ConnectedAccount + Sync + Send>(
+        &self,
+        account: &A,
+        from: ContractAddress,
+        to: ContractAddress,
+        amount: StarknetUint256,
+    ) -> Result<Felt, ContractError> {
+        let call = self.transfer_from_call(from, to, amount)?;
+
+        send_call(account, call).await
+    }
+
+    /// Build the `permit` call letting `spender` spend `amount` of `owner`'s tokens until
+    /// `deadline` (unix seconds), authorized by the owner's off-chain `signature` instead of
+    /// an `approve` transaction.
+    ///
+    /// Calldata is `[owner, spender, amount_low, amount_high, deadline, signature_len,
+    /// ...signature]`, so anyone, e.g. a relayer, can submit it.
+    pub fn permit_call(
+        &self,
+        owner: ContractAddress,
+        spender: ContractAddress,
+        amount: StarknetUint256,
+        deadline: u64,
+        signature: &[Felt],
+    ) -> Result<Call, ContractError> {
+        let (amount_low, amount_high) = amount.to_felts();
+
+        let mut calldata = vec![
+            owner,
+            spender,
+            amount_low,
+            amount_high,
+            Felt::from(deadline),
+            Felt::from(signature.len()),
+        ];
+        calldata.extend_from_slice(signature);
+
+        Ok(Call {
+            to: self.contract_address,
+            selector: get_selector_from_name(erc20_abi::PERMIT)
+                .map_err(|e| ContractError::CallFailed(e.to_string()))?,
+            calldata,
+        })
+    }
+
+    /// Hash an owner signs to authorize [`permit_call`](Self::permit_call): the SNIP-12
+    /// (revision 1) message hash of OpenZeppelin's `Permit { token, spender, amount, nonce,
+    /// deadline }` under the token's `StarknetDomain`, as checked by `ERC20Permit`.
+    ///
+    /// `domain` carries the token's [`snip12_metadata`](Self::snip12_metadata) and the chain
+    /// id; its revision must be
+    /// [`Revision::V1`](starknet::core::types::typed_data::Revision::V1).
+    pub fn permit_hash(
+        &self,
+        owner: ContractAddress,
+        spender: ContractAddress,
+        amount: StarknetUint256,
+        nonce: Felt,
+        deadline: u64,
+        domain: &Domain,
+    ) -> Result<Felt, ContractError> {
+        let (amount_low, amount_high) = amount.to_felts();
+        let hex = |felt: Felt| format!("{:#x}", felt);
+
+        let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "StarknetDomain": [
+                    { "name": "name", "type": "shortstring" },
+                    { "name": "version", "type": "shortstring" },
+                    { "name": "chainId", "type": "shortstring" },
+                    { "name": "revision", "type": "shortstring" }
+                ],
+                "Permit": [
+                    { "name": "token", "type": "ContractAddress" },
+                    { "name": "spender", "type": "ContractAddress" },
+                    { "name": "amount", "type": "u256" },
+                    { "name": "nonce", "type": "felt" },
+                    { "name": "deadline", "type": "u128" }
+                ]
+            },
+            "primaryType": "Permit",
+            "domain": {
+                "name": hex(domain.name),
+                "version": hex(domain.version),
+                "chainId": hex(domain.chain_id),
+                "revision": domain.revision,
+            },
+            "message": {
+                "token": hex(self.contract_address),
+                "spender": hex(spender),
+                "amount": { "low": hex(amount_low), "high": hex(amount_high) },
+                "nonce": hex(nonce),
+                "deadline": hex(Felt::from(deadline)),
+            }
+        }))
+        .map_err(|e| ContractError::SerializationError(e.to_string()))?;
+
+        typed_data
+            .message_hash(owner)
+            .map_err(|e| ContractError::SerializationError(e.to_string()))
+    }
+
+    /// Read the token's SNIP-12 `(name, version)`, which its permit messages are signed under
+    pub async fn snip12_metadata<P: Provider>(
+        &self,
+        provider: &P,
+    ) -> Result<(Felt, Felt), ContractError> {
+        let metadata = provider
+            .call(
+                FunctionCall {
+                    contract_address: self.contract_address,
+                    entry_point_selector: selector!("snip12_metadata"),
+                    calldata: vec![],
+                },
+                self.block_id,
+            )
+            .await?;
+
+        match metadata[..] {
+            [name, version] => Ok((name, version)),
+            _ => Err(ContractError::DeserializationError(format!(
+                "snip12_metadata returned {} felts, expected 2",
+                metadata.len()
+            ))),
+        }
+    }
 
-        Ok(execution.transaction_hash)
+    /// Read `owner`'s permit nonce, or `None` if the token has no `nonces` entrypoint
+    pub async fn permit_nonce<P: Provider>(
+        &self,
+        provider: &P,
+        owner: ContractAddress,
+    ) -> Result<Option<Felt>, ContractError> {
+        let nonce = provider
+            .call(
+                FunctionCall {
+                    contract_address: self.contract_address,
+                    entry_point_selector: selector!("nonces"),
+                    calldata: vec![owner],
+                },
+                self.block_id,
+            )
+            .await;
+
+        match nonce {
+            // Signing for a made-up nonce 0 would only produce a permit the token rejects
+            Ok(nonce) => nonce.first().copied().map(Some).ok_or_else(|| {
+                ContractError::DeserializationError("nonces returned no value".to_string())
+            }),
+            Err(ProviderError::StarknetError(StarknetError::EntrypointNotFound)) => Ok(None),
+            Err(ProviderError::StarknetError(StarknetError::ContractError(data)))
+                if is_entrypoint_not_found(&data.revert_error) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(ContractError::ProviderError(e)),
+        }
+    }
+
+    /// Build the `permit` call for `account`'s tokens with an already made `signature`, or
+    /// `None` if the token has no permit support, in which case use
+    /// [`approve`](Self::approve) instead
+    pub async fn permit<A: ConnectedAccount + Sync>(
+        &self,
+        account: &A,
+        spender: ContractAddress,
+        amount: StarknetUint256,
+        deadline: u64,
+        signature: &[Felt],
+    ) -> Result<Option<Call>, ContractError> {
+        if self
+            .permit_nonce(account.provider(), account.address())
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        self.permit_call(account.address(), spender, amount, deadline, signature)
+            .map(Some)
     }
 
     /// Check token allowance
@@ -631,20 +1113,13 @@ impl Erc20Contract {
                     entry_point_selector: selector!("allowance"),
                     calldata: vec![owner, spender],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
-
-        // Parse the result - allowance should return a single felt
-        let allowance_value = allowance[0];
-        let allowance_u128: u128 = allowance_value.try_into().unwrap_or(0);
-        let (low, high) = conversions::u128_to_uint256(allowance_u128);
+            .map_err(ContractError::ProviderError)?;
 
-        Ok(StarknetUint256 {
-            low: low.try_into().unwrap_or(0),
-            high: high.try_into().unwrap_or(0),
-        })
+        // Parse the result - allowance returns a u256 (low, high)
+        conversions::felts_to_uint256(&allowance, "allowance")
     }
 
     /// Get token balance
@@ -660,20 +1135,49 @@ impl Erc20Contract {
                     entry_point_selector: selector!("balance_of"),
                     calldata: vec![account],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
+            .map_err(ContractError::ProviderError)?;
 
-        // Parse the result - balance should return a single felt
-        let balance_value = balance[0];
-        let balance_u128: u128 = balance_value.try_into().unwrap_or(0);
-        let (low, high) = conversions::u128_to_uint256(balance_u128);
+        // Parse the result - balance_of returns a u256 (low, high)
+        conversions::felts_to_uint256(&balance, "balance_of")
+    }
 
-        Ok(StarknetUint256 {
-            low: low.try_into().unwrap_or(0),
-            high: high.try_into().unwrap_or(0),
-        })
+    /// Get token total supply, falling back to the camel case `totalSupply` entrypoint
+    /// used by older tokens
+    pub async fn total_supply<P: Provider>(
+        &self,
+        provider: &P,
+    ) -> Result<StarknetUint256, ContractError> {
+        let call = |entry_point_selector| {
+            provider.call(
+                FunctionCall {
+                    contract_address: self.contract_address,
+                    entry_point_selector,
+                    calldata: vec![],
+                },
+                self.block_id,
+            )
+        };
+
+        // Only fall back to the camelCase name when the snake_case entrypoint is missing, so a
+        // real revert isn't hidden behind a second call
+        let supply = match call(selector!("total_supply")).await {
+            Err(ProviderError::StarknetError(StarknetError::EntrypointNotFound)) => {
+                call(selector!("totalSupply")).await
+            }
+            Err(ProviderError::StarknetError(StarknetError::ContractError(data)))
+                if is_entrypoint_not_found(&data.revert_error) =>
+            {
+                call(selector!("totalSupply")).await
+            }
+            result => result,
+        }
+        .map_err(ContractError::ProviderError)?;
+
+        // Parse the result - total_supply returns a u256 (low, high)
+        conversions::felts_to_uint256(&supply, "total_supply")
     }
 
     /// Get token decimals
@@ -685,10 +1189,10 @@ impl Erc20Contract {
                     entry_point_selector: selector!("decimals"),
                     calldata: vec![],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
+            .map_err(ContractError::ProviderError)?;
 
         // Parse the result - decimals should return a single felt
         let decimals_value = decimals[0];
@@ -706,10 +1210,10 @@ impl Erc20Contract {
                     entry_point_selector: selector!("symbol"),
                     calldata: vec![],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
+            .map_err(ContractError::ProviderError)?;
 
         // Parse the result - symbol should return a single felt
         let symbol_value = symbol[0];
@@ -730,10 +1234,10 @@ impl Erc20Contract {
                     entry_point_selector: selector!("name"),
                     calldata: vec![],
                 },
-                BlockId::Tag(BlockTag::Latest),
+                self.block_id,
             )
             .await
-            .map_err(|e| ContractError::ProviderError(e))?;
+            .map_err(ContractError::ProviderError)?;
 
         // Parse the result - name should return a single felt
         let name_value = name[0];
@@ -746,6 +1250,178 @@ impl Erc20Contract {
     }
 }
 
+/// Ekubo core contract implementation
+pub struct EkuboCoreContract {
+    contract_address: ContractAddress,
+    block_id: BlockId,
+}
+
+impl EkuboCoreContract {
+    /// Create a new Ekubo core contract instance
+    pub fn new(contract_address: ContractAddress) -> Self {
+        Self {
+            contract_address,
+            block_id: BlockId::Tag(BlockTag::Latest),
+        }
+    }
+
+    /// Read state at `block_id` instead of the latest block
+    pub fn with_block_id(mut self, block_id: BlockId) -> Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Get the block that read calls query
+    pub fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+
+    /// Get the contract address
+    pub fn address(&self) -> ContractAddress {
+        self.contract_address
+    }
+
+    /// Get the current price of a pool. Uninitialized pools report a zero sqrt ratio.
+    pub async fn get_pool_price<P: Provider>(
+        &self,
+        provider: &P,
+        pool_key: &PoolKey,
+    ) -> Result<PoolPrice, ContractError> {
+        let mut calldata = vec![];
+        pool_key
+            .encode(&mut calldata)
+            .map_err(|e| ContractError::SerializationError(e.to_string()))?;
+
+        let result = provider
+            .call(
+                FunctionCall {
+                    contract_address: self.contract_address,
+                    entry_point_selector: selector!("get_pool_price"),
+                    calldata,
+                },
+                self.block_id,
+            )
+            .await
+            .map_err(ContractError::ProviderError)?;
+
+        // Parse the result - PoolPrice { sqrt_ratio: u256, tick: i129 }
+        if result.len() < 4 {
+            return Err(ContractError::DeserializationError(
+                "Insufficient return values from get_pool_price".to_string(),
+            ));
+        }
+
+        let parse_u128 = |felt: Felt| {
+            u128::try_from(felt).map_err(|e| ContractError::DeserializationError(e.to_string()))
+        };
+
+        Ok(PoolPrice {
+            sqrt_ratio: conversions::felts_to_uint256(&result, "get_pool_price")?,
+            tick: I129 {
+                mag: parse_u128(result[2])?,
+                sign: result[3] != Felt::ZERO,
+            },
+        })
+    }
+
+    /// Get the active liquidity of a pool at its current tick
+    pub async fn get_pool_liquidity<P: Provider>(
+        &self,
+        provider: &P,
+        pool_key: &PoolKey,
+    ) -> Result<u128, ContractError> {
+        let mut calldata = vec![];
+        pool_key
+            .encode(&mut calldata)
+            .map_err(|e| ContractError::SerializationError(e.to_string()))?;
+
+        let result = provider
+            .call(
+                FunctionCall {
+                    contract_address: self.contract_address,
+                    entry_point_selector: selector!("get_pool_liquidity"),
+                    calldata,
+                },
+                self.block_id,
+            )
+            .await
+            .map_err(ContractError::ProviderError)?;
+
+        let liquidity = result.first().ok_or_else(|| {
+            ContractError::DeserializationError("Empty result from get_pool_liquidity".to_string())
+        })?;
+
+        u128::try_from(*liquidity).map_err(|e| ContractError::DeserializationError(e.to_string()))
+    }
+}
+
+/// Pragma-style price oracle contract implementation
+pub struct OracleContract {
+    contract_address: ContractAddress,
+    block_id: BlockId,
+}
+
+impl OracleContract {
+    /// Create a new oracle contract instance
+    pub fn new(contract_address: ContractAddress) -> Self {
+        Self {
+            contract_address,
+            block_id: BlockId::Tag(BlockTag::Latest),
+        }
+    }
+
+    /// Read state at `block_id` instead of the latest block
+    pub fn with_block_id(mut self, block_id: BlockId) -> Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Get the block that read calls query
+    pub fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+
+    /// Get the contract address
+    pub fn address(&self) -> ContractAddress {
+        self.contract_address
+    }
+
+    /// Get the median spot price for `pair_id` as `(price, last_updated_timestamp)`.
+    ///
+    /// The price is scaled by the feed's own decimals, as returned by the oracle.
+    pub async fn get_spot_median<P: Provider>(
+        &self,
+        provider: &P,
+        pair_id: FieldElement,
+    ) -> Result<(u128, u64), ContractError> {
+        let result = provider
+            .call(
+                FunctionCall {
+                    contract_address: self.contract_address,
+                    entry_point_selector: selector!("get_data_median"),
+                    calldata: vec![Felt::from(oracle_abi::SPOT_ENTRY), pair_id],
+                },
+                self.block_id,
+            )
+            .await
+            .map_err(ContractError::ProviderError)?;
+
+        // PragmaPricesResponse { price, decimals, last_updated_timestamp, num_sources, expiration }
+        if result.len() < 3 {
+            return Err(ContractError::DeserializationError(
+                "Insufficient return values from get_data_median".to_string(),
+            ));
+        }
+
+        let price = u128::try_from(result[0])
+            .map_err(|e| ContractError::DeserializationError(e.to_string()))?;
+        let timestamp = u64::try_from(result[2])
+            .map_err(|e| ContractError::DeserializationError(e.to_string()))?;
+
+        Ok((price, timestamp))
+    }
+}
+
 /// Contract address constants for different networks
 pub mod addresses {
     use starknet::core::types::Felt;
@@ -760,7 +1436,8 @@ pub mod addresses {
         // AutoSwappr contract addresses
         pub const AUTOSWAPPR: &str =
             "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b";
-        pub const EKUBO_CORE: &str = "0xe0e0e08a6a4b9dc7bd67bcb7aade5cf48157d444";
+        pub const EKUBO_CORE: &str =
+            "0x00000005dd3d2f4429af886cd1a3b08289dbcea99a294197e9eb43b0e0325b4b";
         pub const FIBROUS_EXCHANGE: &str = "0x546f9e447a0bce431949233e3139fe68ec85089e";
         pub const AVNU_EXCHANGE: &str = "0x6712811c214C50b9E12678327Bae02E44Efc357A";
 
@@ -816,7 +1493,9 @@ pub mod addresses {
         // AutoSwappr contract addresses
         pub const AUTOSWAPPR: &str =
             "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b";
-        pub const EKUBO_CORE: &str = "0xe0e0e08a6a4b9dc7bd67bcb7aade5cf48157d444";
+        // Ekubo's Sepolia deployment
+        pub const EKUBO_CORE: &str =
+            "0x0444a09d96389aa7148f1aada508e30b71299ffe650d9c97fdaae38cb9a23384";
         pub const FIBROUS_EXCHANGE: &str = "0x546f9e447a0bce431949233e3139fe68ec85089e";
         pub const AVNU_EXCHANGE: &str = "0x6712811c214C50b9E12678327Bae02E44Efc357A";
 
@@ -883,25 +1562,67 @@ pub enum ContractError {
     DeserializationError(String),
 }
 
+impl From<ContractError> for AutoSwapprError {
+    fn from(error: ContractError) -> Self {
+        match error {
+            ContractError::ProviderError(e) => AutoSwapprError::ProviderError {
+                message: e.to_string(),
+            },
+            ContractError::AccountError(message) | ContractError::CallFailed(message) => {
+                AutoSwapprError::ContractError { message }
+            }
+            ContractError::InvalidAddress(details) => AutoSwapprError::InvalidInput {
+                details: format!("Invalid contract address: {}", details),
+            },
+            ContractError::SerializationError(details) => AutoSwapprError::InvalidInput {
+                details: format!("Serialization error: {}", details),
+            },
+            ContractError::DeserializationError(message) => AutoSwapprError::ContractError {
+                message: format!("Deserialization error: {}", message),
+            },
+        }
+    }
+}
+
 /// Helper functions for type conversions and utilities
 pub mod conversions {
     use super::*;
     use crate::types::connector::{SwapData, Uint256};
 
-    /// Convert our Uint256 to Starknet's Uint256
+    /// Convert our Uint256 to the calldata u256 type, which is the same type; use
+    /// `U256::from` to get starknet-rs's `U256`
     pub fn uint256_to_starknet(uint256: &Uint256) -> StarknetUint256 {
-        StarknetUint256 {
-            low: uint256.low,
-            high: uint256.high,
-        }
+        *uint256
     }
 
-    /// Convert Starknet's Uint256 to our Uint256
+    /// Convert the calldata u256 type to our Uint256, which is the same type; use
+    /// `Uint256::from` to convert starknet-rs's `U256`
     pub fn starknet_to_uint256(uint256: &StarknetUint256) -> Uint256 {
-        Uint256 {
-            low: uint256.low,
-            high: uint256.high,
+        *uint256
+    }
+
+    /// Parse a u256 returned as two felts (low, high)
+    pub fn felts_to_uint256(result: &[Felt], method: &str) -> Result<Uint256, ContractError> {
+        if result.len() < 2 {
+            return Err(ContractError::DeserializationError(format!(
+                "Insufficient return values from {}",
+                method
+            )));
         }
+
+        let limb = |felt: Felt| {
+            u128::try_from(felt).map_err(|_| {
+                ContractError::DeserializationError(format!(
+                    "u256 limb returned by {} exceeds u128",
+                    method
+                ))
+            })
+        };
+
+        Ok(Uint256 {
+            low: limb(result[0])?,
+            high: limb(result[1])?,
+        })
     }
 
     /// Convert our SwapData to Cairo-compatible format
@@ -910,18 +1631,31 @@ pub mod conversions {
         Ok(swap_data.clone())
     }
 
-    /// Convert u128 to (low, high) felts for uint256
+    /// Convert u128 to (low, high) felts for uint256; the high limb is always zero
     pub fn u128_to_uint256(amount: u128) -> (Felt, Felt) {
-        let amount_low = Felt::from(amount & 0xFFFFFFFFFFFFFFFF); // Lower 128 bits
-        let amount_high = Felt::from(amount >> 64); // Upper 128 bits
-        (amount_low, amount_high)
+        Uint256::from(amount).to_felts()
     }
 
-    /// Convert (low, high) felts back to u128
-    pub fn uint256_to_u128(low: Felt, high: Felt) -> u128 {
-        let low_u128: u128 = low.try_into().unwrap_or(0);
-        let high_u128: u128 = high.try_into().unwrap_or(0);
-        low_u128 | (high_u128 << 64)
+    /// Convert (low, high) felts back to u128, truncating to the low 128 bits.
+    ///
+    /// The `high` limb only contributes multiples of 2^128 and is dropped, so values above
+    /// `u128::MAX` wrap silently; use [`uint256_to_u128_checked`] to detect that.
+    pub fn uint256_to_u128(low: Felt, _high: Felt) -> u128 {
+        let bytes = low.to_bytes_le();
+        u128::from_le_bytes(bytes[..16].try_into().expect("felt has 32 bytes"))
+    }
+
+    /// Convert (low, high) felts back to u128, failing if the value exceeds u128
+    pub fn uint256_to_u128_checked(low: Felt, high: Felt) -> Result<u128, ContractError> {
+        if high != Felt::ZERO {
+            return Err(ContractError::DeserializationError(
+                "u256 value exceeds u128".to_string(),
+            ));
+        }
+
+        u128::try_from(low).map_err(|_| {
+            ContractError::DeserializationError("u256 low limb exceeds u128".to_string())
+        })
     }
 
     /// Validate if a string is a valid Starknet address
@@ -929,7 +1663,7 @@ pub mod conversions {
         if address.len() < 3 || !address.starts_with("0x") {
             return false;
         }
-        Felt::from_hex(address).is_ok()
+        crate::types::address::Address::from_hex(address).is_ok()
     }
 
     /// Convert Felt to ASCII string
@@ -948,7 +1682,7 @@ pub mod conversions {
             if byte == 0 {
                 break; // Stop at null terminator
             }
-            if byte >= 32 && byte <= 126 {
+            if (32..=126).contains(&byte) {
                 // Printable ASCII range
                 bytes.push(byte);
             }
@@ -989,4 +1723,18 @@ mod tests {
         assert_eq!(our_uint256.low, back_to_ours.low);
         assert_eq!(our_uint256.high, back_to_ours.high);
     }
+
+    #[test]
+    fn test_u128_to_uint256_keeps_full_low_limb() {
+        // Amounts above 2^64, e.g. 20 STRK, stay in the low limb
+        let amount = 20 * 10_u128.pow(18);
+        assert_eq!(
+            conversions::u128_to_uint256(amount),
+            (Felt::from(amount), Felt::ZERO)
+        );
+        assert_eq!(
+            crate::constant::u128_to_uint256(u128::MAX),
+            (Felt::from(u128::MAX), Felt::ZERO)
+        );
+    }
 }
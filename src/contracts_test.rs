@@ -127,4 +127,822 @@ mod contracts_tests {
         assert_eq!(route.percent, 100);
         assert_eq!(route.additional_swap_params.len(), 0);
     }
+
+    #[test]
+    fn test_fibrous_calldata_serializes_both_u256_limbs() {
+        use crate::contracts::{RouteParams, build_fibrous_calldata};
+        use crate::types::connector::Uint256;
+
+        let route_params = RouteParams {
+            token_in: Felt::from_hex("0x123").unwrap(),
+            token_out: Felt::from_hex("0x456").unwrap(),
+            amount_in: Uint256 { low: 1000, high: 7 },
+            min_received: Uint256 { low: 900, high: 3 },
+            destination: Felt::from_hex("0x789").unwrap(),
+        };
+
+        let calldata = build_fibrous_calldata(
+            &route_params,
+            &[],
+            Felt::from_hex("0xaaa").unwrap(),
+            Felt::from_hex("0xbbb").unwrap(),
+        );
+
+        // [protocol_swapper, beneficiary, token_in, token_out, amount_in (2), min_received (2), destination, len]
+        assert_eq!(calldata.len(), 10);
+        assert_eq!(calldata[4], Felt::from(1000u128));
+        assert_eq!(calldata[5], Felt::from(7u128));
+        assert_eq!(calldata[6], Felt::from(900u128));
+        assert_eq!(calldata[7], Felt::from(3u128));
+        assert_eq!(calldata[8], Felt::from_hex("0x789").unwrap());
+        assert_eq!(calldata[9], Felt::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_balance_of_and_allowance_keep_high_limb() {
+        use crate::contracts::{Erc20Contract, erc20_abi};
+        use crate::test_utils::MockRpc;
+
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(42u128), Felt::from(7u128)],
+            )
+            .on_call(
+                token,
+                erc20_abi::ALLOWANCE,
+                vec![Felt::from(u128::MAX), Felt::from(1u128)],
+            )
+            .start()
+            .await;
+
+        let provider = server.provider();
+        let erc20 = Erc20Contract::new(token);
+
+        let balance = erc20
+            .balance_of(&provider, Felt::from_hex("0x456").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(balance.low, 42);
+        assert_eq!(balance.high, 7);
+
+        let allowance = erc20
+            .allowance(
+                &provider,
+                Felt::from_hex("0x456").unwrap(),
+                Felt::from_hex("0x789").unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(allowance.low, u128::MAX);
+        assert_eq!(allowance.high, 1);
+    }
+
+    #[test]
+    fn test_contract_error_converts_to_structured_sdk_error() {
+        use crate::contracts::ContractError;
+        use crate::types::connector::AutoSwapprError;
+
+        let error: AutoSwapprError =
+            ContractError::ProviderError(starknet::providers::ProviderError::RateLimited).into();
+        assert!(matches!(error, AutoSwapprError::ProviderError { .. }));
+
+        let error: AutoSwapprError = ContractError::CallFailed("reverted".to_string()).into();
+        assert!(
+            matches!(error, AutoSwapprError::ContractError { message } if message == "reverted")
+        );
+
+        let error: AutoSwapprError = ContractError::InvalidAddress("0xzz".to_string()).into();
+        assert!(matches!(error, AutoSwapprError::InvalidInput { .. }));
+    }
+
+    #[test]
+    fn test_set_fee_type_calldata_accepts_percentage_in_range() {
+        use crate::contracts::build_set_fee_type_calldata;
+        use crate::types::connector::FeeType;
+
+        let calldata = build_set_fee_type_calldata(FeeType::Percentage, 250).unwrap();
+        assert_eq!(calldata, vec![Felt::ONE, Felt::from(250u16)]);
+
+        let calldata = build_set_fee_type_calldata(FeeType::Fixed, 0).unwrap();
+        assert_eq!(calldata, vec![Felt::ZERO, Felt::ZERO]);
+    }
+
+    #[test]
+    fn test_set_fee_type_calldata_rejects_out_of_range_percentage() {
+        use crate::contracts::{ContractError, build_set_fee_type_calldata};
+        use crate::types::connector::FeeType;
+
+        assert!(matches!(
+            build_set_fee_type_calldata(FeeType::Percentage, 20_000),
+            Err(ContractError::SerializationError(_))
+        ));
+        assert!(matches!(
+            build_set_fee_type_calldata(FeeType::Fixed, 250),
+            Err(ContractError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_ekubo_calldata_layout() {
+        use crate::contracts::build_ekubo_calldata;
+        use crate::types::connector::{I129, PoolKey, SwapData, SwapParameters, Uint256};
+
+        let swap_data = SwapData {
+            params: SwapParameters {
+                amount: I129 {
+                    mag: 1_000,
+                    sign: true,
+                },
+                is_token1: true,
+                sqrt_ratio_limit: Uint256 { low: 5, high: 6 },
+                skip_ahead: 7,
+            },
+            pool_key: PoolKey {
+                token0: Felt::from_hex("0xa").unwrap(),
+                token1: Felt::from_hex("0xb").unwrap(),
+                fee: 3000,
+                tick_spacing: 60,
+                extension: Felt::from_hex("0xe").unwrap(),
+            },
+            caller: Felt::from_hex("0xc").unwrap(),
+        };
+
+        let calldata = build_ekubo_calldata(&swap_data).unwrap();
+
+        assert_eq!(
+            calldata,
+            vec![
+                Felt::from(1_000u128), // amount.mag
+                Felt::ONE,             // amount.sign
+                Felt::ONE,             // is_token1
+                Felt::from(5u128),     // sqrt_ratio_limit.low
+                Felt::from(6u128),     // sqrt_ratio_limit.high
+                Felt::from(7u32),      // skip_ahead
+                Felt::from_hex("0xa").unwrap(),
+                Felt::from_hex("0xb").unwrap(),
+                Felt::from(3000u128),
+                Felt::from(60u128),
+                Felt::from_hex("0xe").unwrap(),
+                Felt::from_hex("0xc").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ekubo_calldata_matches_cairo_codec() {
+        use crate::contracts::build_ekubo_calldata;
+        use crate::types::connector::{PoolKey, SwapData, Uint256};
+        use starknet::core::{
+            codec::{Decode, Encode},
+            types::U256,
+        };
+
+        // Distinct values in every field so a swapped pair shows up
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .sign(true)
+            .pool(
+                Felt::from_hex("0xb").unwrap(),
+                Felt::from_hex("0xa").unwrap(),
+            )
+            .is_token1(true)
+            .sqrt_ratio_limit(U256::from_words(5, 6))
+            .skip_ahead(7)
+            .extension(Felt::from_hex("0xe").unwrap(), 0x64, 0x9)
+            .caller(Felt::from_hex("0xc").unwrap())
+            .build()
+            .unwrap();
+
+        let mut encoded = Vec::new();
+        swap_data.encode(&mut encoded).unwrap();
+        let calldata = build_ekubo_calldata(&swap_data).unwrap();
+        assert_eq!(calldata, encoded);
+
+        // Decoding the calldata gives back every field in place
+        let decoded = SwapData::decode(&calldata).unwrap();
+        assert_eq!(decoded.params.amount.mag, 1_000);
+        assert!(decoded.params.amount.sign);
+        assert!(!decoded.params.is_token1);
+        assert_eq!(decoded.params.sqrt_ratio_limit, Uint256 { low: 5, high: 6 });
+        assert_eq!(decoded.params.skip_ahead, 7);
+        assert_eq!(decoded.pool_key, swap_data.pool_key);
+        assert_eq!(
+            decoded.pool_key,
+            PoolKey::with_extension(
+                Felt::from_hex("0xa").unwrap(),
+                Felt::from_hex("0xb").unwrap(),
+                Felt::from_hex("0xe").unwrap(),
+                0x64,
+                0x9
+            )
+        );
+        assert_eq!(decoded.caller, Felt::from_hex("0xc").unwrap());
+    }
+
+    #[test]
+    fn test_negative_amount_sets_sign_in_ekubo_calldata() {
+        use crate::contracts::build_ekubo_calldata;
+        use crate::types::connector::SwapData;
+
+        let build = |sign: bool| {
+            let swap_data = SwapData::builder()
+                .amount(1_000)
+                .sign(sign)
+                .pool(
+                    Felt::from_hex("0xa").unwrap(),
+                    Felt::from_hex("0xb").unwrap(),
+                )
+                .caller(Felt::from_hex("0xc").unwrap())
+                .build()
+                .unwrap();
+            build_ekubo_calldata(&swap_data).unwrap()
+        };
+
+        // [mag, sign, ...]
+        assert_eq!(build(true)[..2], [Felt::from(1_000u128), Felt::ONE]);
+        assert_eq!(build(false)[..2], [Felt::from(1_000u128), Felt::ZERO]);
+    }
+
+    #[test]
+    fn test_pool_extension_reaches_ekubo_calldata() {
+        use crate::contracts::build_ekubo_calldata;
+        use crate::types::connector::{PoolKey, SwapData};
+
+        let extension = Felt::from_hex("0x5e7").unwrap();
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(
+                Felt::from_hex("0xb").unwrap(),
+                Felt::from_hex("0xa").unwrap(),
+            )
+            .extension(extension, 0x64, 0x9)
+            .caller(Felt::from_hex("0xc").unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            swap_data.pool_key,
+            PoolKey::with_extension(
+                Felt::from_hex("0xa").unwrap(),
+                Felt::from_hex("0xb").unwrap(),
+                extension,
+                0x64,
+                0x9
+            )
+        );
+        let calldata = build_ekubo_calldata(&swap_data).unwrap();
+        // [.., token0, token1, fee, tick_spacing, extension, caller]
+        assert_eq!(
+            calldata[calldata.len() - 4..calldata.len() - 1],
+            [Felt::from(0x64u32), Felt::from(0x9u32), extension]
+        );
+    }
+
+    #[test]
+    fn test_avnu_calldata_layout() {
+        use crate::contracts::{Route, build_avnu_calldata};
+        use crate::types::connector::Uint256;
+
+        let felt = |hex: &str| Felt::from_hex(hex).unwrap();
+        let routes = vec![Route {
+            token_from: felt("0x1"),
+            token_to: felt("0x2"),
+            exchange_address: felt("0x3"),
+            percent: 1_000_000_000_000,
+            additional_swap_params: vec![felt("0x4"), felt("0x5")],
+        }];
+
+        let calldata = build_avnu_calldata(
+            felt("0xaa"),
+            felt("0x1"),
+            Uint256 { low: 100, high: 1 },
+            felt("0x2"),
+            Uint256::from(90u128),
+            felt("0xbb"),
+            15,
+            felt("0xcc"),
+            &routes,
+        );
+
+        assert_eq!(
+            calldata,
+            vec![
+                felt("0xaa"),
+                felt("0x1"),
+                Felt::from(100u128),
+                Felt::ONE,
+                felt("0x2"),
+                Felt::from(90u128),
+                Felt::ZERO,
+                felt("0xbb"),
+                Felt::from(15u128),
+                felt("0xcc"),
+                Felt::ONE, // routes length
+                felt("0x1"),
+                felt("0x2"),
+                felt("0x3"),
+                Felt::from(1_000_000_000_000u128),
+                Felt::TWO, // additional_swap_params length
+                felt("0x4"),
+                felt("0x5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_uint256_to_u128_checked_accepts_zero_high_limb() {
+        use crate::contracts::conversions;
+
+        let value = conversions::uint256_to_u128_checked(Felt::from(u128::MAX), Felt::ZERO);
+        assert_eq!(value.unwrap(), u128::MAX);
+        assert_eq!(
+            conversions::uint256_to_u128(Felt::from(42u128), Felt::ZERO),
+            42
+        );
+    }
+
+    #[test]
+    fn test_uint256_to_u128_checked_rejects_nonzero_high_limb() {
+        use crate::contracts::{ContractError, conversions};
+
+        let result = conversions::uint256_to_u128_checked(Felt::from(42u128), Felt::ONE);
+        assert!(matches!(
+            result,
+            Err(ContractError::DeserializationError(_))
+        ));
+
+        // The unchecked conversion truncates to the low limb
+        assert_eq!(
+            conversions::uint256_to_u128(Felt::from(42u128), Felt::ONE),
+            42
+        );
+    }
+
+    #[test]
+    fn test_swap_calldata_has_only_abi_arguments() {
+        use crate::contracts::{RouteParams, build_ekubo_calldata, build_fibrous_calldata};
+        use crate::types::connector::{SwapData, Uint256};
+
+        // Cairo 1 rejects calldata longer than the entrypoint's arguments
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(Felt::ONE, Felt::TWO)
+            .caller(Felt::THREE)
+            .build()
+            .unwrap();
+        assert_eq!(build_ekubo_calldata(&swap_data).unwrap().len(), 12);
+
+        let route_params = RouteParams {
+            token_in: Felt::ONE,
+            token_out: Felt::TWO,
+            amount_in: Uint256::from_u128(1_000),
+            min_received: Uint256::from_u128(900),
+            destination: Felt::THREE,
+        };
+        assert_eq!(
+            build_fibrous_calldata(&route_params, &[], Felt::ONE, Felt::TWO).len(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_transfer_calldata_layout() {
+        use crate::contracts::{Erc20Contract, erc20_abi};
+        use crate::types::connector::Uint256;
+        use starknet::core::utils::get_selector_from_name;
+
+        let token = Felt::from_hex("0x123").unwrap();
+        let erc20 = Erc20Contract::new(token);
+        let amount = Uint256 { low: 1000, high: 7 };
+
+        let call = erc20
+            .transfer_call(Felt::from_hex("0x456").unwrap(), amount)
+            .unwrap();
+        assert_eq!(call.to, token);
+        assert_eq!(
+            call.selector,
+            get_selector_from_name(erc20_abi::TRANSFER).unwrap()
+        );
+        assert_eq!(
+            call.calldata,
+            vec![
+                Felt::from_hex("0x456").unwrap(),
+                Felt::from(1000u128),
+                Felt::from(7u128),
+            ]
+        );
+
+        let call = erc20
+            .transfer_from_call(Felt::ONE, Felt::TWO, amount)
+            .unwrap();
+        assert_eq!(
+            call.selector,
+            get_selector_from_name(erc20_abi::TRANSFER_FROM).unwrap()
+        );
+        assert_eq!(
+            call.calldata,
+            vec![
+                Felt::ONE,
+                Felt::TWO,
+                Felt::from(1000u128),
+                Felt::from(7u128)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_total_supply_reads_both_limbs() {
+        use crate::contracts::{ContractError, Erc20Contract, erc20_abi};
+        use crate::test_utils::MockRpc;
+        use starknet::core::types::StarknetError;
+        use starknet::providers::ProviderError;
+
+        let snake = Felt::from_hex("0x123").unwrap();
+        let camel = Felt::from_hex("0x456").unwrap();
+        let wrapped = Felt::from_hex("0x789").unwrap();
+        let reverting = Felt::from_hex("0xabc").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                snake,
+                erc20_abi::TOTAL_SUPPLY,
+                vec![Felt::from(42u128), Felt::from(7u128)],
+            )
+            .on_call_error(
+                camel,
+                erc20_abi::TOTAL_SUPPLY,
+                21,
+                "Requested entrypoint does not exist in the contract",
+            )
+            .on_call(
+                camel,
+                erc20_abi::TOTAL_SUPPLY_CAMEL,
+                vec![Felt::from(5u128), Felt::from(1u128)],
+            )
+            .on_call_revert(
+                wrapped,
+                erc20_abi::TOTAL_SUPPLY,
+                "Entry point EntryPointSelector(0x1557) not found in contract.",
+            )
+            .on_call(
+                wrapped,
+                erc20_abi::TOTAL_SUPPLY_CAMEL,
+                vec![Felt::from(5u128), Felt::from(1u128)],
+            )
+            .on_call_revert(reverting, erc20_abi::TOTAL_SUPPLY, "u256_sub Overflow")
+            .on_call(
+                reverting,
+                erc20_abi::TOTAL_SUPPLY_CAMEL,
+                vec![Felt::from(5u128), Felt::from(1u128)],
+            )
+            .start()
+            .await;
+
+        let provider = server.provider();
+
+        let supply = Erc20Contract::new(snake)
+            .total_supply(&provider)
+            .await
+            .unwrap();
+        assert_eq!((supply.low, supply.high), (42, 7));
+
+        let supply = Erc20Contract::new(camel)
+            .total_supply(&provider)
+            .await
+            .unwrap();
+        assert_eq!((supply.low, supply.high), (5, 1));
+
+        // A missing entrypoint reported as a contract error falls back too
+        let supply = Erc20Contract::new(wrapped)
+            .total_supply(&provider)
+            .await
+            .unwrap();
+        assert_eq!((supply.low, supply.high), (5, 1));
+
+        // A revert in total_supply is reported, not retried as totalSupply
+        let result = Erc20Contract::new(reverting).total_supply(&provider).await;
+        assert!(matches!(
+            result,
+            Err(ContractError::ProviderError(ProviderError::StarknetError(
+                StarknetError::ContractError(_)
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_ekubo_calldata_splits_large_sqrt_ratio_limit() {
+        use crate::contracts::{SwapParameters, build_ekubo_calldata};
+        use crate::types::connector::{I129, PoolKey, SwapData, Uint256};
+
+        // 3 * 2^128 + 5
+        let limit = Uint256 { low: 5, high: 3 };
+        let swap_data = SwapData::new(
+            SwapParameters {
+                amount: I129::new(1_000, false),
+                is_token1: false,
+                sqrt_ratio_limit: limit,
+                skip_ahead: 0,
+            },
+            PoolKey::new(Felt::ONE, Felt::TWO),
+            Felt::THREE,
+        );
+
+        let calldata = build_ekubo_calldata(&swap_data).unwrap();
+        assert_eq!(calldata[3], Felt::from(5u128)); // low
+        assert_eq!(calldata[4], Felt::from(3u128)); // high
+    }
+
+    #[test]
+    fn test_swap_calls_are_composable_without_sending() {
+        use crate::contracts::{
+            AutoSwapprContract, RouteParams, abi, build_ekubo_calldata, build_fibrous_calldata,
+        };
+        use crate::types::connector::{I129, PoolKey, SwapData, SwapParameters, Uint256};
+        use starknet::core::utils::get_selector_from_name;
+
+        let contract_address = Felt::from_hex("0xabc").unwrap();
+        // No provider is needed to build calls
+        let contract = AutoSwapprContract::new(contract_address);
+
+        let swap_data = SwapData {
+            params: SwapParameters::new(I129::new(1_000, false), false),
+            pool_key: PoolKey::new(
+                Felt::from_hex("0xa").unwrap(),
+                Felt::from_hex("0xb").unwrap(),
+            ),
+            caller: Felt::from_hex("0xc").unwrap(),
+        };
+        let call = contract.ekubo_swap_call(&swap_data).unwrap();
+        assert_eq!(call.to, contract_address);
+        assert_eq!(
+            call.selector,
+            get_selector_from_name(abi::EKUBO_SWAP).unwrap()
+        );
+        assert_eq!(call.calldata, build_ekubo_calldata(&swap_data).unwrap());
+
+        let route_params = RouteParams {
+            token_in: Felt::from_hex("0xa").unwrap(),
+            token_out: Felt::from_hex("0xb").unwrap(),
+            amount_in: Uint256::from_u128(500),
+            min_received: Uint256::from_u128(490),
+            destination: Felt::from_hex("0xc").unwrap(),
+        };
+        let call = contract
+            .fibrous_swap_call(&route_params, &[], Felt::ONE, Felt::TWO)
+            .unwrap();
+        assert_eq!(
+            call.selector,
+            get_selector_from_name(abi::FIBROUS_SWAP).unwrap()
+        );
+        assert_eq!(
+            call.calldata,
+            build_fibrous_calldata(&route_params, &[], Felt::ONE, Felt::TWO)
+        );
+    }
+
+    // Serve `result` as the contract_parameters response and parse it
+    async fn parse_contract_parameters(
+        result: Vec<Felt>,
+    ) -> Result<crate::types::connector::ContractInfo, crate::contracts::ContractError> {
+        use crate::contracts::{AutoSwapprContract, abi};
+        use crate::test_utils::MockRpc;
+
+        let contract_address = Felt::from_hex("0xabc").unwrap();
+        let server = MockRpc::new()
+            .on_call(contract_address, abi::CONTRACT_PARAMETERS, result)
+            .start()
+            .await;
+
+        let provider = server.provider();
+        AutoSwapprContract::new(contract_address)
+            .get_contract_parameters(&provider)
+            .await
+    }
+
+    fn contract_parameters_response(fee_type: Felt, percentage_fee: Felt) -> Vec<Felt> {
+        vec![
+            Felt::from(1u8),
+            Felt::from(2u8),
+            Felt::from(3u8),
+            Felt::from(4u8),
+            Felt::from(5u8),
+            fee_type,
+            percentage_fee,
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_get_contract_parameters_parses_fee() {
+        let info =
+            parse_contract_parameters(contract_parameters_response(Felt::ONE, Felt::from(250u16)))
+                .await
+                .unwrap();
+
+        assert_eq!(info.fee_type, FeeType::Percentage);
+        assert_eq!(info.percentage_fee, 250);
+    }
+
+    #[tokio::test]
+    async fn test_get_contract_parameters_rejects_unknown_fee_type() {
+        use crate::contracts::ContractError;
+
+        let result =
+            parse_contract_parameters(contract_parameters_response(Felt::TWO, Felt::ZERO)).await;
+
+        match result {
+            Err(ContractError::DeserializationError(message)) => assert!(message.contains("0x2")),
+            other => panic!("expected a deserialization error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_contract_parameters_rejects_oversized_percentage_fee() {
+        use crate::contracts::ContractError;
+
+        let result = parse_contract_parameters(contract_parameters_response(
+            Felt::ONE,
+            Felt::from(70_000u32),
+        ))
+        .await;
+
+        match result {
+            Err(ContractError::DeserializationError(message)) => {
+                assert!(message.contains("0x11170"))
+            }
+            other => panic!("expected a deserialization error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_from_status_reads_u256_value() {
+        use crate::contracts::{AutoSwapprContract, abi};
+        use crate::test_utils::MockRpc;
+
+        let contract_address = Felt::from_hex("0xabc").unwrap();
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                contract_address,
+                abi::GET_TOKEN_FROM_STATUS_AND_VALUE,
+                vec![Felt::ONE, Felt::from(7u8), Felt::from(9u8)],
+            )
+            .start()
+            .await;
+
+        let provider = server.provider();
+        let (status, value) = AutoSwapprContract::new(contract_address)
+            .get_token_from_status_and_value(&provider, token)
+            .await
+            .unwrap();
+
+        assert!(status);
+        assert_eq!(value.low, 7);
+        assert_eq!(value.high, 9);
+    }
+
+    #[test]
+    fn test_custom_abi_overrides_call_selector() {
+        use crate::contracts::{AutoSwapprContract, ContractAbi};
+        use crate::types::connector::{I129, PoolKey, SwapData, SwapParameters};
+        use starknet::core::utils::get_selector_from_name;
+
+        let swap_data = SwapData {
+            params: SwapParameters::new(I129::new(1_000, false), false),
+            pool_key: PoolKey::new(
+                Felt::from_hex("0xa").unwrap(),
+                Felt::from_hex("0xb").unwrap(),
+            ),
+            caller: Felt::from_hex("0xc").unwrap(),
+        };
+        let renamed = get_selector_from_name("ekubo_swap_v2").unwrap();
+        let abi = ContractAbi {
+            ekubo_swap: renamed,
+            ..ContractAbi::v1()
+        };
+
+        let default = AutoSwapprContract::new(Felt::ONE);
+        let custom = AutoSwapprContract::new_with_abi(Felt::ONE, abi);
+
+        assert_eq!(
+            default.ekubo_swap_call(&swap_data).unwrap().selector,
+            get_selector_from_name("ekubo_swap").unwrap()
+        );
+        assert_eq!(
+            custom.ekubo_swap_call(&swap_data).unwrap().selector,
+            renamed
+        );
+        // Methods left alone keep the v1 selector
+        assert_eq!(
+            custom.ekubo_manual_swap_call(&swap_data).unwrap().selector,
+            get_selector_from_name("ekubo_manual_swap").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_permit_calldata_layout() {
+        use crate::contracts::{Erc20Contract, erc20_abi};
+        use crate::types::connector::Uint256;
+        use starknet::core::utils::get_selector_from_name;
+
+        let token = Felt::from_hex("0x123").unwrap();
+        let erc20 = Erc20Contract::new(token);
+        let (owner, spender) = (
+            Felt::from_hex("0x456").unwrap(),
+            Felt::from_hex("0x789").unwrap(),
+        );
+        let signature = [Felt::from(0xaau8), Felt::from(0xbbu8)];
+
+        let call = erc20
+            .permit_call(
+                owner,
+                spender,
+                Uint256 { low: 1000, high: 7 },
+                1_700_000_000,
+                &signature,
+            )
+            .unwrap();
+        assert_eq!(call.to, token);
+        assert_eq!(
+            call.selector,
+            get_selector_from_name(erc20_abi::PERMIT).unwrap()
+        );
+        assert_eq!(
+            call.calldata,
+            vec![
+                owner,
+                spender,
+                Felt::from(1000u128),
+                Felt::from(7u128),
+                Felt::from(1_700_000_000u64),
+                Felt::TWO,
+                signature[0],
+                signature[1],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_permit_requires_token_support() {
+        use crate::contracts::{Erc20Contract, erc20_abi};
+        use crate::test_utils::MockRpc;
+        use crate::types::connector::Uint256;
+        use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
+        use starknet::core::chain_id;
+        use starknet::signers::{LocalWallet, SigningKey};
+
+        let (with_permit, without_permit) = (Felt::from_hex("0x123").unwrap(), Felt::TWO);
+        let provider = MockRpc::new()
+            .on_call(with_permit, erc20_abi::NONCES, vec![Felt::from(3u8)])
+            .on_call_error(
+                without_permit,
+                erc20_abi::NONCES,
+                21,
+                "Requested entrypoint does not exist in the contract",
+            )
+            .into_provider();
+        let owner = Felt::from_hex("0x456").unwrap();
+        let account = SingleOwnerAccount::new(
+            provider.clone(),
+            LocalWallet::from(SigningKey::from_secret_scalar(Felt::ONE)),
+            owner,
+            chain_id::MAINNET,
+            ExecutionEncoding::New,
+        );
+        let permit = |token| {
+            let erc20 = Erc20Contract::new(token);
+            let account = &account;
+            async move {
+                erc20
+                    .permit(account, Felt::TWO, Uint256::from(5u128), 9, &[Felt::ONE])
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let call = permit(with_permit).await.unwrap();
+        assert_eq!(call.calldata[..2], [owner, Felt::TWO]);
+        assert!(permit(without_permit).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_permit_nonce_rejects_empty_result() {
+        use crate::contracts::{ContractError, Erc20Contract, erc20_abi};
+        use crate::test_utils::MockRpc;
+
+        let token = Felt::from_hex("0x123").unwrap();
+        let provider = MockRpc::new()
+            .on_call(token, erc20_abi::NONCES, vec![])
+            .into_provider();
+
+        let result = Erc20Contract::new(token)
+            .permit_nonce(&provider, Felt::ONE)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ContractError::DeserializationError(_))
+        ));
+    }
 }
@@ -0,0 +1,116 @@
+// Conversions between raw token base units and human-readable decimal strings, and
+// display formats for on-chain values
+
+use starknet::core::types::Felt;
+
+use crate::types::connector::{AutoSwapprError, Uint256};
+
+/// Format a transaction hash as block explorers show it: lowercase hex with a `0x` prefix,
+/// zero-padded to 64 digits
+pub fn format_tx_hash(hash: Felt) -> String {
+    hash.to_fixed_hex_string()
+}
+
+/// Format a base-unit amount as a decimal string, e.g. `1500000` at 6 decimals is `"1.5"`
+pub fn to_display(amount: u128, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let digits = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Parse a decimal string such as `"1.5"` into base units without floating point.
+///
+/// Fails on more fractional digits than `decimals` and on amounts that overflow `u128`.
+pub fn from_display(s: &str, decimals: u8) -> Result<u128, AutoSwapprError> {
+    u128::try_from(from_display_u256(s, decimals)?)
+}
+
+/// Like [`from_display`], for amounts up to the full `u256` range
+pub fn from_display_u256(s: &str, decimals: u8) -> Result<Uint256, AutoSwapprError> {
+    let invalid = |reason: &str| AutoSwapprError::InvalidInput {
+        details: format!("Invalid amount '{}': {}", s, reason),
+    };
+
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid("empty amount"));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(invalid(&format!(
+            "more than {} fractional digits",
+            decimals
+        )));
+    }
+
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid("not a decimal number"));
+    }
+
+    let whole = if whole.is_empty() { "0" } else { whole };
+    Uint256::from_dec_str(&format!(
+        "{}{:0<width$}",
+        whole,
+        fraction,
+        width = decimals as usize
+    ))
+    .map_err(|_| invalid("exceeds u256"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_display() {
+        assert_eq!(from_display("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(from_display("0.000001", 6).unwrap(), 1);
+        assert_eq!(from_display("1.5", 18).unwrap(), 1_500_000_000_000_000_000);
+        assert_eq!(from_display("42", 0).unwrap(), 42);
+        assert_eq!(from_display(".5", 1).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_from_display_rejects_invalid_input() {
+        assert!(matches!(
+            from_display("0.0000001", 6),
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+        assert!(from_display("", 6).is_err());
+        assert!(from_display("1.2.3", 6).is_err());
+        assert!(from_display("-1", 6).is_err());
+        assert!(from_display("340282366920938463463374607431768211456", 0).is_err());
+        assert!(from_display(".", 6).is_err());
+    }
+
+    #[test]
+    fn test_format_tx_hash() {
+        assert_eq!(
+            format_tx_hash(Felt::from_hex("0xABC").unwrap()),
+            "0x0000000000000000000000000000000000000000000000000000000000000abc"
+        );
+        assert_eq!(format_tx_hash(Felt::ZERO).len(), 66);
+    }
+
+    #[test]
+    fn test_to_display() {
+        assert_eq!(to_display(1_500_000, 6), "1.5");
+        assert_eq!(to_display(1, 6), "0.000001");
+        assert_eq!(to_display(2_000_000, 6), "2");
+        assert_eq!(to_display(0, 18), "0");
+        assert_eq!(to_display(42, 0), "42");
+    }
+}
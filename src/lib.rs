@@ -1,15 +1,48 @@
+// Emit a tracing event when the `tracing` feature is enabled, and nothing otherwise
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
+
+pub mod avnu;
+pub mod client;
 pub mod constant;
+pub mod contracts;
+pub mod format;
+pub mod math;
+mod nonce;
+pub mod provider;
+pub mod read_client;
+pub mod simple_client;
 pub mod swappr;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod types;
 
 // Re-export main types and clients for easy access
+pub use client::{AutoSwapprClient, AutoSwapprClientBuilder, SwapPlan};
+pub use provider::{Network, ReconnectingTransport, RetryPolicy};
+pub use read_client::AutoSwapprReadClient;
+pub use types::address::{Address, IntoAddress, TokenRef};
 pub use types::connector::{
-    AutoSwappr, AutoSwapprError, ContractInfo, Delta, FeeType, I129, PoolKey, Route, SwapData,
-    SwapOptions, SwapParameters, SwapParams, SwapResult,
+    AccountType, AutoSwappr, AutoSwapprConfig, AutoSwapprError, ClientSnapshot, ContractInfo,
+    Delta, FeeToken, FeeType, HealthStatus, I129, PoolKey, PortfolioValue, Route, SwapData,
+    SwapDataBuilder, SwapExecuted, SwapOptions, SwapParameters, SwapParams, SwapRecord, SwapResult,
+    TxStatus, Uint256,
 };
 
 pub use constant::{ETH, STRK, TokenAddress, TokenInfo, USDC, USDT, WBTC};
 
 #[cfg(test)]
 #[path = "contracts_test.rs"]
+#[allow(
+    clippy::module_inception,
+    clippy::bool_assert_comparison,
+    clippy::useless_vec
+)]
 mod contracts_tests;
+
+#[cfg(test)]
+mod test_utils;
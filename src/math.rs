@@ -0,0 +1,366 @@
+// Slippage bounds, fees and price limits for swaps, computed without intermediate overflow
+
+use crate::types::connector::{AutoSwapprError, Uint256};
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// Lowest sqrt ratio an Ekubo pool accepts as a price limit
+pub const MIN_SQRT_RATIO: u128 = 18446748437148339061;
+
+/// Highest sqrt ratio an Ekubo pool accepts as a price limit
+pub const MAX_SQRT_RATIO: Uint256 = Uint256 {
+    low: 0x6f3528fe26840249f4b191ef6dff7928,
+    high: 0xfffffc080ed7b455,
+};
+
+/// Minimum output to accept for a quoted `expected_out`, rounded down
+pub fn min_received(expected_out: u128, slippage_bps: u16) -> Result<u128, AutoSwapprError> {
+    check_slippage(slippage_bps)?;
+
+    let min = Uint256::from(expected_out)
+        .mul_div(
+            Uint256::from((BPS_DENOMINATOR - slippage_bps) as u128),
+            Uint256::from(BPS_DENOMINATOR as u128),
+        )
+        .ok_or_else(|| overflow(expected_out))?;
+
+    u128::try_from(min)
+}
+
+/// Maximum input to send for a quoted `expected_in` on an exact-output swap, rounded up
+pub fn max_sent(expected_in: u128, slippage_bps: u16) -> Result<u128, AutoSwapprError> {
+    check_slippage(slippage_bps)?;
+
+    let denominator = Uint256::from(BPS_DENOMINATOR as u128);
+    let max = Uint256::from(expected_in)
+        .mul_div(
+            Uint256::from((BPS_DENOMINATOR + slippage_bps) as u128),
+            Uint256::from(1u128),
+        )
+        .and_then(|scaled| scaled.checked_add(Uint256::from(BPS_DENOMINATOR as u128 - 1)))
+        .and_then(|scaled| scaled.mul_div(Uint256::from(1u128), denominator))
+        .ok_or_else(|| overflow(expected_in))?;
+
+    u128::try_from(max)
+}
+
+/// Percentage fee of `bps` basis points on `amount`, rounded down or, with `round_up`, up.
+///
+/// The contract truncates its percentage fee, so pass `round_up = false` to match it.
+/// Fees above `u128::MAX`, only possible with `bps` above 100%, saturate.
+pub fn apply_percentage_fee(amount: u128, bps: u16, round_up: bool) -> u128 {
+    let remainder = if round_up {
+        BPS_DENOMINATOR as u128 - 1
+    } else {
+        0
+    };
+    let fee = Uint256::from(amount)
+        .mul_div(Uint256::from(bps as u128), Uint256::from(1u128))
+        .and_then(|scaled| scaled.checked_add(Uint256::from(remainder)))
+        .and_then(|scaled| {
+            scaled.mul_div(Uint256::from(1u128), Uint256::from(BPS_DENOMINATOR as u128))
+        });
+
+    fee.and_then(|fee| u128::try_from(fee).ok())
+        .unwrap_or(u128::MAX)
+}
+
+/// Ekubo `sqrt_ratio_limit` that keeps an exact-output swap for `desired_out` of one pool
+/// token from spending more than `max_in` of the other.
+///
+/// Selling token0 moves the pool price (token1 per token0) down, so the limit is a floor
+/// of `desired_out / max_in`; selling token1 (`input_is_token1`) moves it up, so the limit
+/// is a ceiling of `max_in / desired_out`. Either way every unit is bought at no worse than
+/// that rate, and the limit is rounded towards the current price so the bound holds exactly;
+/// a swap that hits it fills only partially. Fails if the required price is outside the
+/// range Ekubo can represent.
+pub fn sqrt_ratio_limit_for_max_input(
+    desired_out: u128,
+    max_in: u128,
+    input_is_token1: bool,
+) -> Result<Uint256, AutoSwapprError> {
+    if desired_out == 0 || max_in == 0 {
+        return Err(AutoSwapprError::ZeroAmount);
+    }
+
+    let min = Uint256::from(MIN_SQRT_RATIO);
+    let max = MAX_SQRT_RATIO;
+    let out_of_range = || AutoSwapprError::InvalidInput {
+        details: format!(
+            "Buying {} for at most {} needs a price outside Ekubo's range",
+            desired_out, max_in
+        ),
+    };
+
+    if input_is_token1 {
+        let limit = q128_sqrt(max_in, desired_out, false).ok_or_else(|| overflow(max_in))?;
+        if limit < min {
+            return Err(out_of_range());
+        }
+        Ok(limit.min(max))
+    } else {
+        let limit = q128_sqrt(desired_out, max_in, true).filter(|limit| *limit <= max);
+        Ok(limit.ok_or_else(out_of_range)?.max(min))
+    }
+}
+
+/// Percentage, from 0 to 100, by which swapping `amount` of one pool token in lowers its
+/// price in the other, for a pool at `sqrt_ratio` (Q128) with `liquidity` active.
+///
+/// Assumes the liquidity stays constant over the move and ignores the pool fee, so swaps
+/// crossing initialized ticks move the price further than estimated. A pool without
+/// liquidity gives 100.
+pub fn price_impact_percent(
+    sqrt_ratio: Uint256,
+    liquidity: u128,
+    amount: u128,
+    is_token1: bool,
+) -> f64 {
+    if amount == 0 {
+        return 0.0;
+    }
+
+    let sqrt_price = sqrt_ratio.high as f64 + sqrt_ratio.low as f64 / 2f64.powi(128);
+    let (liquidity, amount) = (liquidity as f64, amount as f64);
+
+    // Within one liquidity range, selling token0 moves the sqrt price from s to
+    // L·s / (L + a·s) and selling token1 moves it to s + a / L; this is the ratio of the
+    // sqrt prices, oriented so the input token's price falls by its square
+    let remaining = if is_token1 {
+        liquidity * sqrt_price / (liquidity * sqrt_price + amount)
+    } else {
+        liquidity / (liquidity + amount * sqrt_price)
+    };
+
+    (1.0 - remaining * remaining) * 100.0
+}
+
+// sqrt(numerator / denominator) as a Q128 fixed-point number, rounded up or down.
+//
+// Takes the root of numerator * 2^(128 + 2 * half) / denominator for the largest `half`
+// that fits in 256 bits, for precision, then scales it back by 2^(64 - half).
+fn q128_sqrt(numerator: u128, denominator: u128, round_up: bool) -> Option<Uint256> {
+    let q128 = Uint256 { low: 0, high: 1 };
+    let (half, scaled) = (0..=64u32).rev().find_map(|half| {
+        let quotient = shl(numerator, 2 * half).mul_div(q128, Uint256::from(denominator))?;
+        let quotient = if round_up {
+            quotient.checked_add(Uint256::from(1u128))?
+        } else {
+            quotient
+        };
+        Some((half, quotient))
+    })?;
+
+    isqrt(scaled, round_up).map(|root| shl(root, 64 - half))
+}
+
+// `value << shift` for shifts up to 128 bits
+fn shl(value: u128, shift: u32) -> Uint256 {
+    match shift {
+        0 => Uint256::from(value),
+        128 => Uint256 {
+            low: 0,
+            high: value,
+        },
+        _ => Uint256 {
+            low: value << shift,
+            high: value >> (128 - shift),
+        },
+    }
+}
+
+// Integer square root rounded up or down, or `None` if rounding up needs more than 128 bits
+fn isqrt(value: Uint256, round_up: bool) -> Option<u128> {
+    let square = |r: u128| Uint256::from(r).mul_div(Uint256::from(r), Uint256::from(1u128));
+
+    let mut root = 0u128;
+    for bit in (0..128).rev() {
+        let candidate = root | 1 << bit;
+        if square(candidate).is_some_and(|sq| sq <= value) {
+            root = candidate;
+        }
+    }
+
+    if round_up && square(root).is_some_and(|sq| sq < value) {
+        root.checked_add(1)
+    } else {
+        Some(root)
+    }
+}
+
+fn check_slippage(slippage_bps: u16) -> Result<(), AutoSwapprError> {
+    if slippage_bps > BPS_DENOMINATOR {
+        return Err(AutoSwapprError::InvalidInput {
+            details: format!(
+                "Slippage of {} bps exceeds {} bps",
+                slippage_bps, BPS_DENOMINATOR
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+fn overflow(amount: u128) -> AutoSwapprError {
+    AutoSwapprError::InvalidInput {
+        details: format!("Slippage bound for {} overflows", amount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_received() {
+        assert_eq!(min_received(1_000_000, 100).unwrap(), 990_000);
+        assert_eq!(min_received(1_000_000, 500).unwrap(), 950_000);
+        assert_eq!(min_received(999, 100).unwrap(), 989);
+        assert_eq!(min_received(1_000_000, 0).unwrap(), 1_000_000);
+        assert_eq!(min_received(1_000_000, 10_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_max_sent() {
+        assert_eq!(max_sent(1_000_000, 100).unwrap(), 1_010_000);
+        assert_eq!(max_sent(1_000_000, 500).unwrap(), 1_050_000);
+        assert_eq!(max_sent(999, 100).unwrap(), 1_009);
+    }
+
+    #[test]
+    fn test_slippage_near_u128_max() {
+        // u128::MAX * 9900 would overflow a u128 intermediate
+        assert_eq!(
+            min_received(u128::MAX, 100).unwrap(),
+            336879543251729078828740861357450529340
+        );
+        assert_eq!(
+            min_received(u128::MAX, 500).unwrap(),
+            323268248574891540290205877060179800882
+        );
+        assert!(matches!(
+            max_sent(u128::MAX, 100),
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_apply_percentage_fee_rounding() {
+        // 1% of 1_050 is 10.5
+        assert_eq!(apply_percentage_fee(1_050, 100, false), 10);
+        assert_eq!(apply_percentage_fee(1_050, 100, true), 11);
+        // 1% of 99 is 0.99
+        assert_eq!(apply_percentage_fee(99, 100, false), 0);
+        assert_eq!(apply_percentage_fee(99, 100, true), 1);
+        // Exact fees are the same either way
+        assert_eq!(apply_percentage_fee(1_000_000, 100, false), 10_000);
+        assert_eq!(apply_percentage_fee(1_000_000, 100, true), 10_000);
+        assert_eq!(apply_percentage_fee(1_000, 0, true), 0);
+        // No u128 overflow in the intermediate product
+        assert_eq!(apply_percentage_fee(u128::MAX, 100, false), u128::MAX / 100);
+        assert_eq!(
+            apply_percentage_fee(u128::MAX, 100, true),
+            u128::MAX / 100 + 1
+        );
+        assert_eq!(apply_percentage_fee(u128::MAX, 20_000, false), u128::MAX);
+    }
+
+    #[test]
+    fn test_sqrt_ratio_limit_for_max_input() {
+        // A price floor of 1/4 is a sqrt ratio of 1/2, or 2^127 as Q128, rounded up
+        assert_eq!(
+            sqrt_ratio_limit_for_max_input(100, 400, false).unwrap(),
+            Uint256::from((1 << 127) + 1)
+        );
+        assert!(matches!(
+            sqrt_ratio_limit_for_max_input(0, 400, false),
+            Err(AutoSwapprError::ZeroAmount)
+        ));
+        // Tiny prices clamp to Ekubo's minimum, and prices past its maximum are rejected
+        assert_eq!(
+            sqrt_ratio_limit_for_max_input(1, u128::MAX, false).unwrap(),
+            Uint256::from(MIN_SQRT_RATIO)
+        );
+        assert!(matches!(
+            sqrt_ratio_limit_for_max_input(u128::MAX, 1, false),
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sqrt_ratio_limit_for_max_token1_input() {
+        // A price ceiling of 4 is a sqrt ratio of 2, or 2^129 as Q128, rounded down
+        assert_eq!(
+            sqrt_ratio_limit_for_max_input(100, 400, true).unwrap(),
+            Uint256 { low: 0, high: 2 }
+        );
+        // Huge prices clamp to Ekubo's maximum, and prices below its minimum are rejected
+        assert_eq!(
+            sqrt_ratio_limit_for_max_input(1, u128::MAX, true).unwrap(),
+            MAX_SQRT_RATIO
+        );
+        assert!(matches!(
+            sqrt_ratio_limit_for_max_input(u128::MAX, 1, true),
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sqrt_ratio_limit_bounds_input() {
+        for (desired_out, max_in) in [
+            (3, 7),
+            (1_000_000, 999_999_999),
+            (1, u128::MAX),
+            (u128::MAX, u128::MAX / 3),
+        ] {
+            let q128 = Uint256 { low: 0, high: 1 };
+
+            // At the limit price (sqrt_ratio / 2^128)^2, max_in buys at least desired_out:
+            // sqrt_ratio^2 / desired_out >= 2^256 / max_in
+            let limit = sqrt_ratio_limit_for_max_input(desired_out, max_in, false).unwrap();
+            let lhs = limit.mul_div(limit, Uint256::from(desired_out));
+            let rhs = q128.mul_div(q128, Uint256::from(max_in));
+            assert!(lhs.unwrap() > rhs.unwrap());
+
+            // Selling token1 the price is inverted: sqrt_ratio^2 / max_in <= 2^256 / desired_out.
+            // A limit clamped to Ekubo's maximum is below the bound by construction.
+            let limit = sqrt_ratio_limit_for_max_input(desired_out, max_in, true).unwrap();
+            if limit == MAX_SQRT_RATIO {
+                continue;
+            }
+            let lhs = limit.mul_div(limit, Uint256::from(max_in));
+            let rhs = q128.mul_div(q128, Uint256::from(desired_out));
+            assert!(lhs.unwrap() <= rhs.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_slippage_above_100_percent_rejected() {
+        assert!(min_received(1_000, 10_001).is_err());
+        assert!(max_sent(1_000, 10_001).is_err());
+    }
+
+    #[test]
+    fn test_price_impact_percent() {
+        // Price 1 with liquidity 1e6: selling 1% of the liquidity moves the price by ~2%
+        let sqrt_ratio = Uint256 { low: 0, high: 1 };
+        let impact = price_impact_percent(sqrt_ratio, 1_000_000, 10_000, false);
+        assert!((impact - 1.9704).abs() < 1e-3, "{}", impact);
+        let impact = price_impact_percent(sqrt_ratio, 1_000_000, 10_000, true);
+        assert!((impact - 1.9704).abs() < 1e-3, "{}", impact);
+
+        assert_eq!(price_impact_percent(sqrt_ratio, 1_000_000, 0, false), 0.0);
+    }
+
+    #[test]
+    fn test_price_impact_percent_shallow_pool() {
+        let sqrt_ratio = MAX_SQRT_RATIO;
+
+        for is_token1 in [false, true] {
+            let impact = price_impact_percent(sqrt_ratio, 1, u128::MAX, is_token1);
+            assert!(impact.is_finite() && impact <= 100.0 && impact > 99.0);
+            assert_eq!(price_impact_percent(sqrt_ratio, 0, 1, is_token1), 100.0);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Local pending-nonce tracking so back-to-back transactions from one account don't collide
+
+use std::sync::Mutex;
+
+use starknet::{
+    accounts::{Account, ConnectedAccount, ExecutionEncoder, RawDeclarationV3, RawExecutionV3},
+    core::types::{BlockId, Call, Felt},
+    providers::ProviderError,
+    signers::SignerInteractivityContext,
+};
+
+use crate::contracts::ContractError;
+
+/// The next nonce to use for the client's account, fetched once and then advanced locally
+#[derive(Debug, Default)]
+pub(crate) struct NonceTracker {
+    next: Mutex<Option<Felt>>,
+}
+
+impl NonceTracker {
+    /// Forget the cached nonce so the next transaction re-syncs it from the provider
+    pub(crate) fn reset(&self) {
+        *self.next.lock().unwrap() = None;
+    }
+
+    /// Drop the cached nonce when a send fails because the network rejected the nonce
+    pub(crate) fn track<T>(&self, result: Result<T, ContractError>) -> Result<T, ContractError> {
+        if let Err(ContractError::AccountError(message)) = &result
+            && message.to_lowercase().contains("nonce")
+        {
+            trace_event!(warn, error = %message, "nonce rejected, re-syncing from provider");
+            self.reset();
+        }
+
+        result
+    }
+
+    /// Hand back a reserved nonce whose transaction was never sent.
+    ///
+    /// Only the most recent reservation can be rolled back; if a later nonce is already
+    /// out, the cache is dropped instead so the next transaction re-syncs without a gap.
+    pub(crate) fn release(&self, nonce: Felt) {
+        let mut next = self.next.lock().unwrap();
+        *next = (*next == Some(nonce + Felt::ONE)).then_some(nonce);
+    }
+
+    // Hands out the cached nonce and advances it, seeding the cache with `fetched` if it is
+    // empty; a nonce another task cached first wins over `fetched`
+    fn reserve(&self, fetched: Option<Felt>) -> Option<Felt> {
+        let mut next = self.next.lock().unwrap();
+        let nonce = (*next).or(fetched)?;
+        *next = Some(nonce + Felt::ONE);
+        Some(nonce)
+    }
+}
+
+/// An account that reserves its nonce from a [`NonceTracker`] instead of reading the provider.
+///
+/// Every `get_nonce` call takes a fresh nonce, so callers fetch it once per transaction and
+/// hand it back with [`NonceTracker::release`] if the transaction is never sent.
+pub(crate) struct TrackedAccount<'a, A> {
+    inner: &'a A,
+    tracker: &'a NonceTracker,
+}
+
+impl<'a, A> TrackedAccount<'a, A> {
+    pub(crate) fn new(inner: &'a A, tracker: &'a NonceTracker) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+impl<A: ExecutionEncoder> ExecutionEncoder for TrackedAccount<'_, A> {
+    fn encode_calls(&self, calls: &[Call]) -> Vec<Felt> {
+        self.inner.encode_calls(calls)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl<A: Account + Sync> Account for TrackedAccount<'_, A> {
+    type SignError = A::SignError;
+
+    fn address(&self) -> Felt {
+        self.inner.address()
+    }
+
+    fn chain_id(&self) -> Felt {
+        self.inner.chain_id()
+    }
+
+    async fn sign_execution_v3(
+        &self,
+        execution: &RawExecutionV3,
+        query_only: bool,
+    ) -> Result<Vec<Felt>, Self::SignError> {
+        self.inner.sign_execution_v3(execution, query_only).await
+    }
+
+    async fn sign_declaration_v3(
+        &self,
+        declaration: &RawDeclarationV3,
+        query_only: bool,
+    ) -> Result<Vec<Felt>, Self::SignError> {
+        self.inner
+            .sign_declaration_v3(declaration, query_only)
+            .await
+    }
+
+    fn is_signer_interactive(&self, context: SignerInteractivityContext<'_>) -> bool {
+        self.inner.is_signer_interactive(context)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl<A: ConnectedAccount + Sync> ConnectedAccount for TrackedAccount<'_, A> {
+    type Provider = A::Provider;
+
+    fn provider(&self) -> &Self::Provider {
+        self.inner.provider()
+    }
+
+    fn block_id(&self) -> BlockId {
+        self.inner.block_id()
+    }
+
+    async fn get_nonce(&self) -> Result<Felt, ProviderError> {
+        if let Some(nonce) = self.tracker.reserve(None) {
+            return Ok(nonce);
+        }
+
+        let fetched = self.inner.get_nonce().await?;
+        Ok(self
+            .tracker
+            .reserve(Some(fetched))
+            .expect("seeded with the fetched nonce"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(tracker: &NonceTracker) -> Option<Felt> {
+        *tracker.next.lock().unwrap()
+    }
+
+    #[test]
+    fn test_reserve_advances_and_settles() {
+        let tracker = NonceTracker::default();
+        assert_eq!(tracker.reserve(None), None);
+        assert_eq!(
+            tracker.reserve(Some(Felt::from(5u8))),
+            Some(Felt::from(5u8))
+        );
+        // A nonce cached by another task wins over a later fetch
+        assert_eq!(
+            tracker.reserve(Some(Felt::from(5u8))),
+            Some(Felt::from(6u8))
+        );
+        assert_eq!(cached(&tracker), Some(Felt::from(7u8)));
+
+        // An unsent transaction hands back the latest reservation...
+        tracker.release(Felt::from(6u8));
+        assert_eq!(cached(&tracker), Some(Felt::from(6u8)));
+
+        // ...but re-syncs when a later nonce is already out or the nonce was rejected
+        tracker.reserve(None);
+        tracker.reserve(None);
+        tracker.release(Felt::from(6u8));
+        assert_eq!(cached(&tracker), None);
+        tracker.reserve(Some(Felt::from(9u8)));
+        let _ = tracker.track::<()>(Err(ContractError::CallFailed("reverted".to_string())));
+        assert_eq!(cached(&tracker), Some(Felt::from(10u8)));
+        let _ = tracker.track::<()>(Err(ContractError::AccountError(
+            "InvalidTransactionNonce: \"nonce too low\"".to_string(),
+        )));
+        assert_eq!(cached(&tracker), None);
+    }
+
+    #[test]
+    fn test_concurrent_reservations_are_unique() {
+        let tracker = NonceTracker::default();
+        let nonces: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| tracker.reserve(Some(Felt::from(5u8))).unwrap()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut nonces: Vec<u64> = nonces.iter().map(|n| u64::try_from(*n).unwrap()).collect();
+        nonces.sort();
+        assert_eq!(nonces, (5..13).collect::<Vec<_>>());
+    }
+}
@@ -1,3 +1,17 @@
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use starknet::core::types::Felt;
+use starknet::providers::{
+    ProviderRequestData, Url,
+    jsonrpc::{
+        HttpTransport, HttpTransportError, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport,
+    },
+};
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 use thiserror::Error;
 
 /// Network configuration for different Starknet networks
@@ -5,15 +19,223 @@ use thiserror::Error;
 pub enum Network {
     Mainnet,
     Testnet,
+    /// An RPC endpoint URL
     Custom(String),
+    /// A chain other than mainnet or Sepolia, by the chain id its RPC reports
+    OtherChain(Felt),
 }
 
 impl Network {
+    /// Default RPC URL of the network; empty for [`Network::OtherChain`], which names a chain
+    /// but no endpoint
     pub fn rpc_url(&self) -> &str {
         match self {
-            Network::Mainnet => "https://starknet-mainnet.public.blastapi.io/rpc/v0_7",
-            Network::Testnet => "https://starknet-goerli.public.blastapi.io/rpc/v0_7",
+            Network::Mainnet => "https://starknet-mainnet.public.blastapi.io/rpc/v0_9",
+            Network::Testnet => "https://starknet-sepolia.public.blastapi.io/rpc/v0_9",
             Network::Custom(url) => url,
+            Network::OtherChain(_) => "",
+        }
+    }
+}
+
+/// JSON-RPC spec version targeted by the default RPC URLs
+pub const SUPPORTED_SPEC_VERSION: &str = "0.9";
+
+/// Whether an RPC reporting spec version `actual` (e.g. `"0.9.1"`) matches
+/// [`SUPPORTED_SPEC_VERSION`]; patch versions are compatible
+pub fn is_spec_version_supported(actual: &str) -> bool {
+    actual
+        .strip_prefix(SUPPORTED_SPEC_VERSION)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Retry behaviour for RPC requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Wait for `duration` without assuming a particular async runtime on wasm
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::time::sleep(duration).await;
+
+    #[cfg(target_arch = "wasm32")]
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// Current unix time in seconds; `SystemTime` is unavailable in the browser
+pub(crate) fn unix_timestamp() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    #[cfg(target_arch = "wasm32")]
+    return (js_sys::Date::now() / 1000.0) as u64;
+}
+
+/// Current unix time in milliseconds, for timing requests on native and wasm alike
+pub(crate) fn unix_timestamp_millis() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+
+    #[cfg(target_arch = "wasm32")]
+    return js_sys::Date::now() as u64;
+}
+
+/// Transport errors that mean the connection itself failed, as opposed to the node
+/// answering with an error
+pub trait ConnectionError {
+    /// Whether a freshly built transport might succeed where this one failed
+    fn is_connection_error(&self) -> bool;
+}
+
+impl ConnectionError for HttpTransportError {
+    fn is_connection_error(&self) -> bool {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            HttpTransportError::Reqwest(e) => e.is_connect() || e.is_request(),
+            #[cfg(target_arch = "wasm32")]
+            HttpTransportError::Reqwest(e) => e.is_request(),
+            _ => false,
+        }
+    }
+}
+
+impl ConnectionError for std::io::Error {
+    fn is_connection_error(&self) -> bool {
+        use std::io::ErrorKind;
+
+        matches!(
+            self.kind(),
+            ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::NotConnected
+                | ErrorKind::BrokenPipe
+                | ErrorKind::UnexpectedEof
+        )
+    }
+}
+
+/// Transport that rebuilds its inner transport and retries a request once when the
+/// connection drops, e.g. after an idle keep-alive connection was closed.
+///
+/// Only connection-level failures (see [`ConnectionError`]) trigger a reconnect; JSON-RPC
+/// errors such as contract reverts are returned as they are. Clones share the connection.
+pub struct ReconnectingTransport<T> {
+    transport: Arc<RwLock<Arc<T>>>,
+    connect: Arc<dyn Fn() -> T + Send + Sync>,
+}
+
+impl<T> ReconnectingTransport<T> {
+    /// Wrap transports built by `connect`, which is called again on every reconnect
+    pub fn new(connect: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        Self {
+            transport: Arc::new(RwLock::new(Arc::new(connect()))),
+            connect: Arc::new(connect),
+        }
+    }
+
+    fn current(&self) -> Arc<T> {
+        self.transport.read().unwrap().clone()
+    }
+
+    // Replace `failed` with a fresh transport, unless a concurrent request already did
+    fn reconnect(&self, failed: &Arc<T>) -> Arc<T> {
+        let mut transport = self.transport.write().unwrap();
+        if Arc::ptr_eq(&transport, failed) {
+            *transport = Arc::new((self.connect)());
+        }
+        transport.clone()
+    }
+}
+
+impl ReconnectingTransport<HttpTransport> {
+    /// HTTP transport to `url` that reconnects with a new HTTP client
+    pub fn http(url: Url) -> Self {
+        Self::new(move || HttpTransport::new(url.clone()))
+    }
+}
+
+impl<T> Clone for ReconnectingTransport<T> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            connect: self.connect.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ReconnectingTransport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectingTransport")
+            .field("transport", &self.current())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl<T> JsonRpcTransport for ReconnectingTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync + 'static,
+    T::Error: ConnectionError,
+{
+    type Error = T::Error;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let transport = self.current();
+        match transport.send_request(method, &params).await {
+            Err(e) if e.is_connection_error() => {
+                trace_event!(warn, method = ?method, error = %e, "connection lost, reconnecting");
+                self.reconnect(&transport)
+                    .send_request(method, &params)
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        let transport = self.current();
+        match transport.send_requests(requests.as_ref()).await {
+            Err(e) if e.is_connection_error() => {
+                trace_event!(warn, error = %e, "connection lost, reconnecting");
+                self.reconnect(&transport)
+                    .send_requests(requests.as_ref())
+                    .await
+            }
+            result => result,
         }
     }
 }
@@ -77,8 +299,9 @@ impl StarknetProvider {
     pub async fn chain_id(&self) -> Result<String, ProviderError> {
         match self.network {
             Network::Mainnet => Ok("0x534e5f4d41494e".to_string()), // SN_MAIN
-            Network::Testnet => Ok("0x534e5f474f45524c49".to_string()), // SN_GOERLI
+            Network::Testnet => Ok("0x534e5f5345504f4c4941".to_string()), // SN_SEPOLIA
             Network::Custom(_) => Ok("0x0".to_string()),
+            Network::OtherChain(chain_id) => Ok(format!("{:#x}", chain_id)),
         }
     }
 
@@ -125,5 +348,98 @@ mod tests {
         let provider = StarknetProvider::new(Network::Testnet).unwrap();
         let chain_id = provider.chain_id().await;
         assert!(chain_id.is_ok());
+        assert_eq!(chain_id.unwrap(), "0x534e5f5345504f4c4941");
+    }
+
+    // Fails every request with a dropped connection when `broken`
+    struct FlakyTransport {
+        inner: crate::testing::MockTransport,
+        broken: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl JsonRpcTransport for FlakyTransport {
+        type Error = std::io::Error;
+
+        async fn send_request<P, R>(
+            &self,
+            method: JsonRpcMethod,
+            params: P,
+        ) -> Result<JsonRpcResponse<R>, Self::Error>
+        where
+            P: Serialize + Send + Sync,
+            R: DeserializeOwned + Send,
+        {
+            if self.broken {
+                return Err(std::io::ErrorKind::ConnectionReset.into());
+            }
+            self.inner.send_request(method, params).await
+        }
+
+        async fn send_requests<R>(
+            &self,
+            requests: R,
+        ) -> Result<Vec<JsonRpcResponse<Value>>, Self::Error>
+        where
+            R: AsRef<[ProviderRequestData]> + Send + Sync,
+        {
+            self.inner.send_requests(requests).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_once_after_connection_error() {
+        use starknet::{
+            core::types::{BlockId, BlockTag, Felt, FunctionCall},
+            core::utils::get_selector_from_name,
+            providers::{JsonRpcClient, Provider},
+        };
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let token = Felt::from_hex("0x123").unwrap();
+        let mock = crate::test_utils::MockRpc::new()
+            .on_call(token, "decimals", vec![Felt::from(18u8)])
+            .on_call_error(token, "balance_of", 40, "Contract error")
+            .transport();
+        let connects = Arc::new(AtomicUsize::new(0));
+        let transport = {
+            let connects = connects.clone();
+            // Only the first connection is broken
+            ReconnectingTransport::new(move || FlakyTransport {
+                inner: mock.clone(),
+                broken: connects.fetch_add(1, Ordering::SeqCst) == 0,
+            })
+        };
+        let provider = JsonRpcClient::new(transport);
+        let call = |selector: &str| FunctionCall {
+            contract_address: token,
+            entry_point_selector: get_selector_from_name(selector).unwrap(),
+            calldata: vec![],
+        };
+
+        let result = provider
+            .call(call("decimals"), BlockId::Tag(BlockTag::Latest))
+            .await
+            .unwrap();
+        assert_eq!(result, vec![Felt::from(18u8)]);
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+
+        // A revert is the node answering, so the connection is kept
+        assert!(
+            provider
+                .call(call("balance_of"), BlockId::Tag(BlockTag::Latest))
+                .await
+                .is_err()
+        );
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_is_spec_version_supported() {
+        assert!(is_spec_version_supported("0.9"));
+        assert!(is_spec_version_supported("0.9.1"));
+        assert!(!is_spec_version_supported("0.8.0"));
+        assert!(!is_spec_version_supported("0.7.1"));
+        assert!(!is_spec_version_supported("0.90"));
     }
 }
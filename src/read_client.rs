@@ -0,0 +1,185 @@
+use crate::{
+    contracts::{AutoSwapprContract, Erc20Contract},
+    types::{
+        address::IntoAddress,
+        connector::{AutoSwapprError, ContractInfo, Uint256},
+    },
+};
+use starknet::{
+    core::types::{BlockId, Felt},
+    providers::{
+        Url,
+        jsonrpc::{HttpTransport, JsonRpcClient},
+    },
+};
+use std::sync::Arc;
+
+/// Read-only client for AutoSwappr that needs no account or private key.
+///
+/// Suited to explorers and monitoring tools; write operations are only available on
+/// [`AutoSwapprClient`](crate::AutoSwapprClient).
+pub struct AutoSwapprReadClient {
+    provider: Arc<JsonRpcClient<HttpTransport>>,
+    autoswappr_contract: AutoSwapprContract,
+}
+
+impl AutoSwapprReadClient {
+    /// Create a read-only client for the AutoSwappr contract at `contract_address`
+    pub fn new(rpc_url: &str, contract_address: &str) -> Result<Self, AutoSwapprError> {
+        let rpc_url = Url::parse(rpc_url).map_err(|e| AutoSwapprError::InvalidInput {
+            details: format!("Invalid RPC URL: {}", e),
+        })?;
+
+        let contract_address =
+            Felt::from_hex(contract_address).map_err(|e| AutoSwapprError::InvalidInput {
+                details: format!("Invalid contract address: {}", e),
+            })?;
+
+        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(rpc_url)));
+        let autoswappr_contract = AutoSwapprContract::new(contract_address);
+
+        Ok(Self {
+            provider,
+            autoswappr_contract,
+        })
+    }
+
+    /// Run read calls against `block_id`, e.g. a past `BlockId::Number`, instead of the
+    /// latest block
+    pub fn with_block_id(mut self, block_id: BlockId) -> Self {
+        self.autoswappr_contract = self.autoswappr_contract.with_block_id(block_id);
+        self
+    }
+
+    /// Get the block that read calls query
+    pub fn block_id(&self) -> BlockId {
+        self.autoswappr_contract.block_id()
+    }
+
+    fn erc20(&self, token: Felt) -> Erc20Contract {
+        Erc20Contract::new(token).with_block_id(self.block_id())
+    }
+
+    /// Get contract parameters
+    pub async fn get_contract_parameters(&self) -> Result<ContractInfo, AutoSwapprError> {
+        Ok(self
+            .autoswappr_contract
+            .get_contract_parameters(&*self.provider)
+            .await?)
+    }
+
+    /// Get token amount in USD
+    pub async fn get_token_amount_in_usd(
+        &self,
+        token: impl IntoAddress,
+        token_amount: u128,
+    ) -> Result<u128, AutoSwapprError> {
+        let token_felt = token.into_address()?.felt();
+
+        let result = self
+            .autoswappr_contract
+            .get_token_amount_in_usd(
+                &*self.provider,
+                token_felt,
+                Uint256::from_u128(token_amount),
+            )
+            .await?;
+
+        u128::try_from(result)
+    }
+
+    /// Get the token balance of `owner`
+    pub async fn get_token_balance(
+        &self,
+        token_address: impl IntoAddress,
+        owner: impl IntoAddress,
+    ) -> Result<u128, AutoSwapprError> {
+        let token_felt = token_address.into_address()?.felt();
+        let owner_felt = owner.into_address()?.felt();
+
+        let erc20_contract = self.erc20(token_felt);
+        let result = erc20_contract
+            .balance_of(&*self.provider, owner_felt)
+            .await?;
+
+        u128::try_from(result)
+    }
+
+    /// Check token allowance
+    pub async fn get_allowance(
+        &self,
+        token_address: impl IntoAddress,
+        owner: impl IntoAddress,
+        spender: impl IntoAddress,
+    ) -> Result<u128, AutoSwapprError> {
+        let token_felt = token_address.into_address()?.felt();
+        let owner_felt = owner.into_address()?.felt();
+        let spender_felt = spender.into_address()?.felt();
+
+        let erc20_contract = self.erc20(token_felt);
+        let result = erc20_contract
+            .allowance(&*self.provider, owner_felt, spender_felt)
+            .await?;
+
+        u128::try_from(result)
+    }
+
+    /// Get token information as (name, symbol, decimals)
+    pub async fn get_token_info(
+        &self,
+        token_address: impl IntoAddress,
+    ) -> Result<(String, String, u8), AutoSwapprError> {
+        let token_felt = token_address.into_address()?.felt();
+        let erc20_contract = self.erc20(token_felt);
+
+        let name = erc20_contract.name(&*self.provider).await?;
+        let symbol = erc20_contract.symbol(&*self.provider).await?;
+        let decimals = erc20_contract.decimals(&*self.provider).await?;
+
+        Ok((name, symbol, decimals))
+    }
+
+    /// Get contract address
+    pub fn contract_address(&self) -> String {
+        self.autoswappr_contract.address().to_string()
+    }
+
+    /// Get the underlying provider
+    pub fn provider(&self) -> &JsonRpcClient<HttpTransport> {
+        &self.provider
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::erc20_abi;
+    use crate::test_utils::{MockRpc, TEST_ACCOUNT, TEST_CONTRACT};
+
+    #[tokio::test]
+    async fn test_read_client_fetches_balance_without_key() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let server = MockRpc::new()
+            .on_call(
+                token,
+                erc20_abi::BALANCE_OF,
+                vec![Felt::from(5_000u32), Felt::ZERO],
+            )
+            .start()
+            .await;
+
+        let client = AutoSwapprReadClient::new(&server.url, TEST_CONTRACT).unwrap();
+        let balance = client
+            .get_token_balance("0x123", TEST_ACCOUNT)
+            .await
+            .unwrap();
+
+        assert_eq!(balance, 5_000);
+    }
+
+    #[test]
+    fn test_read_client_rejects_invalid_contract_address() {
+        let result = AutoSwapprReadClient::new("http://127.0.0.1:5050", "not-hex");
+        assert!(matches!(result, Err(AutoSwapprError::InvalidInput { .. })));
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::read_client::AutoSwapprReadClient;
+use crate::types::address::Address;
+use crate::types::connector::{self, AutoSwapprError, SwapOptions, Uint256};
+
 /// Simple configuration for the AutoSwappr SDK
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SimpleConfig {
@@ -23,6 +27,22 @@ pub enum SimpleError {
     Other { message: String },
 }
 
+impl From<AutoSwapprError> for SimpleError {
+    fn from(error: AutoSwapprError) -> Self {
+        match error {
+            AutoSwapprError::InvalidInput { details } => SimpleError::InvalidInput { details },
+            AutoSwapprError::ZeroAmount => SimpleError::InvalidInput {
+                details: error.to_string(),
+            },
+            AutoSwapprError::NetworkError { message } => SimpleError::NetworkError { message },
+            AutoSwapprError::ContractError { message } => SimpleError::ContractError { message },
+            other => SimpleError::Other {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
 /// Simple client for AutoSwappr functionality
 pub struct SimpleAutoSwapprClient {
     config: SimpleConfig,
@@ -78,46 +98,108 @@ impl SimpleAutoSwapprClient {
         Ok(())
     }
 
-    /// Create a basic swap data structure
+    /// Create the Ekubo swap data for swapping `token_in` into `token_out`.
+    ///
+    /// `options.amount` is parsed as a string (base units, or whole tokens with
+    /// `amount_is_human`); `skip_ahead` and `sqrt_ratio_limit` (decimal) are passed through
+    /// to the swap parameters, and `extension` (hex), `fee` and `tick_spacing` to the pool
+    /// key. The pool tokens are sorted by address, which decides whether `token_in` is
+    /// token1, so `options.is_token1` is ignored.
     pub fn create_swap_data(
         &self,
         token_in: &str,
         token_out: &str,
-        amount: &str,
-    ) -> Result<SwapData, SimpleError> {
+        options: &SwapOptions,
+    ) -> Result<connector::SwapData, SimpleError> {
         self.validate_config()?;
 
-        if token_in.is_empty() || token_out.is_empty() || amount.is_empty() {
+        if token_in.is_empty() || token_out.is_empty() || options.amount.is_empty() {
             return Err(SimpleError::InvalidInput {
                 details: "Token addresses and amount cannot be empty".to_string(),
             });
         }
 
-        Ok(SwapData {
-            token_in: token_in.to_string(),
-            token_out: token_out.to_string(),
-            amount: amount.to_string(),
-            caller: self.config.account_address.clone(),
-        })
+        let token_in = Address::from_hex(token_in)?.felt();
+        let token_out = Address::from_hex(token_out)?.felt();
+        let caller = Address::from_hex(&self.config.account_address)?.felt();
+        let amount = u128::try_from(options.parse_amount()?)?;
+
+        let mut builder = connector::SwapData::builder()
+            .amount(amount)
+            .pool(token_in, token_out)
+            .skip_ahead(options.skip_ahead.unwrap_or(0))
+            .caller(caller);
+        if let Some(limit) = &options.sqrt_ratio_limit {
+            builder = builder.sqrt_ratio_limit(Uint256::from_dec_str(limit)?);
+        }
+        if let Some(extension) = &options.extension {
+            let (Some(fee), Some(tick_spacing)) = (options.fee, options.tick_spacing) else {
+                return Err(SimpleError::InvalidInput {
+                    details: "An extension pool needs its fee and tick spacing".to_string(),
+                });
+            };
+            builder = builder.extension(Address::from_hex(extension)?.felt(), fee, tick_spacing);
+        }
+
+        Ok(builder.build()?)
     }
 
-    /// Simulate a swap (placeholder for actual implementation)
-    pub async fn simulate_swap(&self, swap_data: &SwapData) -> Result<String, SimpleError> {
+    /// Estimate a swap from the contract's oracle without submitting anything.
+    ///
+    /// The input is valued in USD and converted into `token_out` at its oracle price, so
+    /// pool fees and price impact are ignored; treat `estimated_out` as a rough guide.
+    pub async fn simulate_swap(&self, swap_data: &SwapData) -> Result<SimulatedSwap, SimpleError> {
         self.validate_config()?;
 
-        // This is a placeholder - in a real implementation, this would:
-        // 1. Connect to the Starknet network
-        // 2. Create the appropriate contract calls
-        // 3. Execute the swap transaction
-        // 4. Return the transaction hash
+        let amount_in = u128::try_from(Uint256::from_dec_str(&swap_data.amount)?)?;
+        let client =
+            AutoSwapprReadClient::new(&self.config.rpc_url, &self.config.contract_address)?;
+
+        // Price the input, then one whole token_out as the exchange rate
+        let usd_in = client
+            .get_token_amount_in_usd(swap_data.token_in.as_str(), amount_in)
+            .await?;
+        let (_, _, decimals_out) = client.get_token_info(swap_data.token_out.as_str()).await?;
+        let one_out =
+            10u128
+                .checked_pow(decimals_out as u32)
+                .ok_or_else(|| SimpleError::ContractError {
+                    message: format!("Unsupported token decimals: {}", decimals_out),
+                })?;
+        let usd_per_out = client
+            .get_token_amount_in_usd(swap_data.token_out.as_str(), one_out)
+            .await?;
+        if usd_per_out == 0 {
+            return Err(SimpleError::ContractError {
+                message: format!("No oracle price for {}", swap_data.token_out),
+            });
+        }
 
-        Ok(format!(
-            "Simulated swap: {} {} -> {} (amount: {})",
-            swap_data.token_in, swap_data.amount, swap_data.token_out, swap_data.amount
-        ))
+        let estimated_out = Uint256::from(usd_in)
+            .mul_div(Uint256::from(one_out), Uint256::from(usd_per_out))
+            .ok_or_else(|| SimpleError::Other {
+                message: "Estimated output overflows".to_string(),
+            })?;
+
+        Ok(SimulatedSwap {
+            token_in: swap_data.token_in.clone(),
+            token_out: swap_data.token_out.clone(),
+            amount_in: amount_in.to_string(),
+            estimated_out: u128::try_from(estimated_out)?.to_string(),
+        })
     }
 }
 
+/// Oracle estimate of a swap from [`SimpleAutoSwapprClient::simulate_swap`], with amounts as
+/// decimal strings in base units
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SimulatedSwap {
+    pub token_in: String,
+    pub token_out: String,
+    pub amount_in: String,
+    pub estimated_out: String,
+}
+
 /// Simple swap data structure
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SwapData {
@@ -130,6 +212,9 @@ pub struct SwapData {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::contracts::erc20_abi;
+    use crate::test_utils::MockRpc;
+    use starknet::core::types::Felt;
 
     #[test]
     fn test_client_creation() {
@@ -171,6 +256,20 @@ mod tests {
         assert!(client.validate_config().is_err());
     }
 
+    fn swap_options(amount: &str) -> SwapOptions {
+        SwapOptions {
+            amount: amount.to_string(),
+            is_token1: None,
+            skip_ahead: None,
+            sqrt_ratio_limit: None,
+            amount_is_human: false,
+            decimals: None,
+            extension: None,
+            fee: None,
+            tick_spacing: None,
+        }
+    }
+
     #[tokio::test]
     async fn test_swap_data_creation() {
         let config = SimpleConfig {
@@ -182,19 +281,98 @@ mod tests {
 
         let client = SimpleAutoSwapprClient::new(config);
         let swap_data = client
-            .create_swap_data("0xabc", "0xdef", "1000000")
+            .create_swap_data("0xabc", "0xdef", &swap_options("1000000"))
             .unwrap();
 
-        assert_eq!(swap_data.token_in, "0xabc");
-        assert_eq!(swap_data.token_out, "0xdef");
-        assert_eq!(swap_data.amount, "1000000");
+        assert_eq!(swap_data.pool_key.token0, Felt::from(0xabcu32));
+        assert_eq!(swap_data.pool_key.token1, Felt::from(0xdefu32));
+        assert_eq!(swap_data.params.amount.mag, 1_000_000);
+        assert!(!swap_data.params.is_token1);
+        assert_eq!(swap_data.caller, Felt::from(0x456u32));
+    }
+
+    #[test]
+    fn test_swap_options_flow_into_swap_data() {
+        let config = SimpleConfig {
+            contract_address: "0x123".to_string(),
+            rpc_url: "https://testnet.starknet.io".to_string(),
+            account_address: "0x456".to_string(),
+            private_key: "0x789".to_string(),
+        };
+        let options = SwapOptions {
+            skip_ahead: Some(7),
+            sqrt_ratio_limit: Some("42".to_string()),
+            amount_is_human: true,
+            decimals: Some(6),
+            extension: Some("0xe".to_string()),
+            fee: Some(42),
+            tick_spacing: Some(5),
+            ..swap_options("1.5")
+        };
+
+        let client = SimpleAutoSwapprClient::new(config);
+        let swap_data = client.create_swap_data("0xdef", "0xabc", &options).unwrap();
+
+        // 0xdef sorts after 0xabc, so the input is the pool's token1
+        assert!(swap_data.params.is_token1);
+        assert_eq!(swap_data.params.skip_ahead, 7);
+        assert_eq!(swap_data.params.sqrt_ratio_limit, Uint256::from(42u128));
+        assert_eq!(swap_data.params.amount.mag, 1_500_000);
+        assert_eq!(swap_data.pool_key.extension, Felt::from(0xeu32));
+        assert_eq!(swap_data.pool_key.fee, 42);
+        assert_eq!(swap_data.pool_key.tick_spacing, 5);
+        assert_eq!(
+            swap_data.tokens(),
+            (Felt::from(0xdefu32), Felt::from(0xabcu32))
+        );
+
+        assert!(matches!(
+            client.create_swap_data("0xabc", "0xdef", &swap_options("0")),
+            Err(SimpleError::InvalidInput { .. })
+        ));
+        let options = SwapOptions {
+            extension: Some("not a felt".to_string()),
+            fee: Some(42),
+            tick_spacing: Some(5),
+            ..swap_options("1")
+        };
+        assert!(matches!(
+            client.create_swap_data("0xabc", "0xdef", &options),
+            Err(SimpleError::InvalidInput { .. })
+        ));
+        // An extension pool's fee tier can't be guessed
+        let options = SwapOptions {
+            extension: Some("0xe".to_string()),
+            ..swap_options("1")
+        };
+        assert!(matches!(
+            client.create_swap_data("0xabc", "0xdef", &options),
+            Err(SimpleError::InvalidInput { .. })
+        ));
     }
 
     #[tokio::test]
     async fn test_simulate_swap() {
+        let contract = Felt::from(0x123u32);
+        let token_out = Felt::from(0xdefu32);
+        // 1 token_in (0xabc) is worth $2 and one whole token_out (6 decimals) $0.50
+        let server = MockRpc::new()
+            .on_call_seq(
+                contract,
+                "get_token_amount_in_usd",
+                vec![
+                    vec![Felt::from(2_000_000u32), Felt::ZERO],
+                    vec![Felt::from(500_000u32), Felt::ZERO],
+                ],
+            )
+            .on_call(token_out, erc20_abi::NAME, vec![Felt::from(0x4f5554u32)])
+            .on_call(token_out, erc20_abi::SYMBOL, vec![Felt::from(0x4f5554u32)])
+            .on_call(token_out, erc20_abi::DECIMALS, vec![Felt::from(6u8)])
+            .start()
+            .await;
         let config = SimpleConfig {
             contract_address: "0x123".to_string(),
-            rpc_url: "https://testnet.starknet.io".to_string(),
+            rpc_url: server.url.clone(),
             account_address: "0x456".to_string(),
             private_key: "0x789".to_string(),
         };
@@ -208,6 +386,14 @@ mod tests {
         };
 
         let result = client.simulate_swap(&swap_data).await.unwrap();
-        assert!(result.contains("Simulated swap"));
+        assert_eq!(
+            result,
+            SimulatedSwap {
+                token_in: "0xabc".to_string(),
+                token_out: "0xdef".to_string(),
+                amount_in: "1000000".to_string(),
+                estimated_out: "4000000".to_string(),
+            }
+        );
     }
 }
@@ -13,9 +13,9 @@ use starknet::{
 use crate::{
     I129, PoolKey, SwapData, SwapParameters, TokenAddress,
     constant::u128_to_uint256,
+    math::{MAX_SQRT_RATIO, sqrt_ratio_limit_for_max_input},
     types::connector::{AutoSwappr, ErrorResponse, SuccessResponse},
 };
-use axum::Json;
 use reqwest::Client;
 use serde_json::json;
 
@@ -34,7 +34,7 @@ impl AutoSwappr {
     ///
     /// # Returns
     ///
-    /// Returns `Ok(AutoSwappr)` if configuration is successful, or an `Err(Json<ErrorResponse>)`
+    /// Returns `Ok(AutoSwappr)` if configuration is successful, or an `Err(ErrorResponse)`
     /// if any of the inputs are invalid or empty.
     ///
     /// # Errors
@@ -50,33 +50,42 @@ impl AutoSwappr {
         account_address: String,
         private_key: String,
         contract_address: String,
-    ) -> Result<AutoSwappr, Json<ErrorResponse>> {
+    ) -> Result<AutoSwappr, ErrorResponse> {
         if rpc_url.is_empty() {
-            return Err(Json(ErrorResponse {
+            return Err(ErrorResponse {
                 success: false,
                 message: "EMPTY RPC STRING".to_string(),
-            }));
+            });
         }
 
         if account_address.is_empty() {
-            return Err(Json(ErrorResponse {
+            return Err(ErrorResponse {
                 success: false,
                 message: "EMPTY ACCOUNT ADDRESS STRING".to_string(),
-            }));
+            });
         }
 
         if private_key.is_empty() {
-            return Err(Json(ErrorResponse {
+            return Err(ErrorResponse {
                 success: false,
                 message: "EMPTY PRIVATE KEY STRING".to_string(),
-            }));
+            });
         }
-        let signer = LocalWallet::from(SigningKey::from_secret_scalar(
-            Felt::from_hex(&private_key).unwrap(),
-        ));
-        let contract_address = Felt::from_hex(&contract_address).unwrap();
-        let address = Felt::from_hex(&account_address).unwrap();
-        let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&rpc_url).unwrap()));
+        let invalid = |message: &str| ErrorResponse {
+            success: false,
+            message: message.to_string(),
+        };
+
+        let private_key_felt =
+            Felt::from_hex(&private_key).map_err(|_| invalid("INVALID PRIVATE KEY"))?;
+        let contract_address =
+            Felt::from_hex(&contract_address).map_err(|_| invalid("INVALID CONTRACT ADDRESS"))?;
+        let address =
+            Felt::from_hex(&account_address).map_err(|_| invalid("INVALID ACCOUNT ADDRESS"))?;
+        let url = Url::parse(&rpc_url).map_err(|_| invalid("INVALID RPC URL"))?;
+
+        let signer = LocalWallet::from(SigningKey::from_secret_scalar(private_key_felt));
+        let provider = JsonRpcClient::new(HttpTransport::new(url));
 
         let account = SingleOwnerAccount::new(
             provider,
@@ -85,12 +94,20 @@ impl AutoSwappr {
             chain_id::MAINNET,
             ExecutionEncoding::New,
         );
+        trace_event!(
+            debug,
+            rpc_url = %rpc_url,
+            account = %account_address,
+            "configured AutoSwappr"
+        );
+
         Ok(AutoSwappr {
             rpc_url,
             account_address,
             private_key,
             account,
             contract_address,
+            auto_approve: true,
         })
     }
 
@@ -104,8 +121,8 @@ impl AutoSwappr {
     ///
     /// # Returns
     ///
-    /// Returns `Ok(Json<SuccessResponse>)` with the transaction hash on success,
-    /// or `Err(Json<ErrorResponse>)` if the swap fails.
+    /// Returns `Ok(SuccessResponse)` with the transaction hash on success,
+    /// or `Err(ErrorResponse)` if the swap fails.
     ///
     /// # Errors
     ///
@@ -113,88 +130,258 @@ impl AutoSwappr {
     /// - `swap_amount` is zero
     /// - Token information cannot be retrieved
     /// - The transaction execution fails
-    /// - Insufficient balance or allowance
+    /// - Insufficient balance
+    /// - Insufficient allowance while `auto_approve` is off (`INSUFFICIENT ALLOWANCE`); with it
+    ///   on, the approval is sent in the same transaction as the swap
     pub async fn ekubo_manual_swap(
         &mut self,
         token0: Felt,
         token1: Felt,
         swap_amount: u128,
-    ) -> Result<Json<SuccessResponse>, Json<ErrorResponse>> {
+    ) -> Result<SuccessResponse, ErrorResponse> {
+        let (pool_key, is_token1) = PoolKey::for_swap(token0, token1);
+        self.ekubo_manual_swap_with_pool(pool_key, swap_amount, is_token1)
+            .await
+    }
+
+    /// Execute a manual token swap through an explicit pool.
+    ///
+    /// Unlike [`ekubo_manual_swap`](Self::ekubo_manual_swap), the pool key is used as given,
+    /// letting callers target a specific fee tier or extension for the pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool_key` - The pre-validated Ekubo pool to swap through
+    /// * `swap_amount` - The amount to swap in token units (scaled by the input token's decimals)
+    /// * `is_token1` - Whether the input token is `pool_key.token1` rather than `pool_key.token0`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - `swap_amount` is zero
+    /// - The pool key uses the same token on both sides
+    /// - The input token is not a known token (`TOKEN IS NOT AVAILABLE`), or `swap_amount`
+    ///   overflows once scaled by its decimals (`SWAP AMOUNT OVERFLOWS`)
+    /// - The transaction execution fails
+    pub async fn ekubo_manual_swap_with_pool(
+        &mut self,
+        pool_key: PoolKey,
+        swap_amount: u128,
+        is_token1: bool,
+    ) -> Result<SuccessResponse, ErrorResponse> {
+        self.ekubo_manual_swap_with_options(pool_key, swap_amount, is_token1, 0)
+            .await
+    }
+
+    /// Execute a manual token swap through an explicit pool with Ekubo's `skip_ahead` set.
+    ///
+    /// `skip_ahead` is how many uninitialized tick bitmap words Ekubo may skip over in one
+    /// step while searching for the next initialized tick. Large swaps through sparse
+    /// liquidity use less gas with a higher value; `0` (the default elsewhere) checks every word.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`ekubo_manual_swap_with_pool`](Self::ekubo_manual_swap_with_pool).
+    pub async fn ekubo_manual_swap_with_options(
+        &mut self,
+        pool_key: PoolKey,
+        swap_amount: u128,
+        is_token1: bool,
+        skip_ahead: u32,
+    ) -> Result<SuccessResponse, ErrorResponse> {
         if swap_amount == 0 {
-            return Err(Json(ErrorResponse {
+            return Err(ErrorResponse {
                 success: false,
                 message: "SWAP AMOUNT IS ZERO".to_string(),
-            }));
+            });
+        }
+
+        if pool_key.token0 == pool_key.token1 {
+            return Err(ErrorResponse {
+                success: false,
+                message: "POOL TOKENS MUST DIFFER".to_string(),
+            });
         }
 
+        let token_in = if is_token1 {
+            pool_key.token1
+        } else {
+            pool_key.token0
+        };
+
+        let actual_amount = scale_to_base_units(token_in, swap_amount)?;
+
+        let swap_call = self.manual_swap_call(pool_key, actual_amount, is_token1, skip_ahead);
+        self.send_swap(token_in, actual_amount, swap_call).await
+    }
+
+    /// Buy exactly `amount_out` of `token_out` with `token_in`, spending at most
+    /// `max_amount_in` of `token_in`.
+    ///
+    /// Both amounts are in token units, scaled by each token's decimals. The swap amount is
+    /// sent negative, which Ekubo reads as exact output, with a price limit that stops the
+    /// swap early rather than spend more than `max_amount_in`. Allowance is checked, and
+    /// topped up when `auto_approve` is on, against `max_amount_in`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - Either amount is zero, or the two tokens are the same
+    /// - Either token is not a known token, or the amounts imply an unreachable price
+    /// - Insufficient allowance while `auto_approve` is off
+    /// - The transaction execution fails
+    pub async fn ekubo_manual_swap_exact_output(
+        &mut self,
+        token_in: Felt,
+        token_out: Felt,
+        amount_out: u128,
+        max_amount_in: u128,
+    ) -> Result<SuccessResponse, ErrorResponse> {
+        if amount_out == 0 || max_amount_in == 0 {
+            return Err(ErrorResponse {
+                success: false,
+                message: "SWAP AMOUNT IS ZERO".to_string(),
+            });
+        }
+
+        if token_in == token_out {
+            return Err(ErrorResponse {
+                success: false,
+                message: "POOL TOKENS MUST DIFFER".to_string(),
+            });
+        }
+
+        let actual_out = scale_to_base_units(token_out, amount_out)?;
+        let actual_max_in = scale_to_base_units(token_in, max_amount_in)?;
+
+        let (pool_key, input_is_token1) = PoolKey::for_swap(token_in, token_out);
+        let swap_call =
+            self.exact_output_call(pool_key, actual_out, actual_max_in, input_is_token1)?;
+        self.send_swap(token_in, actual_max_in, swap_call).await
+    }
+
+    // Send `swap_call`, approving `required` of `token_in` in the same transaction if the
+    // allowance is short and `auto_approve` is on
+    async fn send_swap(
+        &mut self,
+        token_in: Felt,
+        required: u128,
+        swap_call: Call,
+    ) -> Result<SuccessResponse, ErrorResponse> {
         let allowance = self
-            .get_allowance(&self.account_address, token0)
+            .get_allowance(&self.account_address, token_in)
             .await
-            .unwrap();
-
-        let token_decimal = TokenAddress::new()
-            .get_token_info_by_address(token0)
-            .unwrap()
-            .decimals;
-        let actual_amount = swap_amount * 10_u128.pow(token_decimal as u32);
-        let (amount_low, amount_high) = u128_to_uint256(actual_amount);
+            .map_err(|_| ErrorResponse {
+                success: false,
+                message: "FAILED TO READ ALLOWANCE".to_string(),
+            })?;
+        let (amount_low, amount_high) = u128_to_uint256(required);
+
+        trace_event!(
+            debug,
+            token = %format!("{:#x}", token_in),
+            allowance,
+            required,
+            "allowance checked"
+        );
 
-        let pool_key = PoolKey::new(token0, token1);
-        let swap_parameters = SwapParameters::new(I129::new(actual_amount, false), false);
-        let swap_data = SwapData::new(swap_parameters, pool_key, self.account.address());
-        // let mut account  = self.account;
         self.account
             .set_block_id(BlockId::Tag(BlockTag::PreConfirmed));
 
-        let mut serialized = vec![];
-        swap_data.encode(&mut serialized).unwrap();
-
-        if allowance >= actual_amount {
-            let swap_call = Call {
-                to: self.contract_address,
-                selector: selector!("ekubo_manual_swap"),
-                calldata: serialized,
-            };
-
-            let result = self.account.execute_v3(vec![swap_call]).send().await;
-            match result {
-                Ok(x) => Ok(Json(SuccessResponse {
-                    success: true,
-                    tx_hash: x.transaction_hash,
-                })),
-                Err(_) => Err(Json(ErrorResponse {
-                    success: false,
-                    message: "FAILED TO SWAP".to_string(),
-                })),
-            }
+        let calls = if allowance >= required {
+            trace_event!(debug, "allowance sufficient, submitting swap");
+            vec![swap_call]
+        } else if !self.auto_approve {
+            return Err(ErrorResponse {
+                success: false,
+                message: "INSUFFICIENT ALLOWANCE".to_string(),
+            });
         } else {
+            trace_event!(debug, "allowance insufficient, approving alongside swap");
             let approve_call = Call {
-                to: token0,
+                to: token_in,
                 selector: selector!("approve"),
                 calldata: vec![self.contract_address, amount_low, amount_high],
             };
+            vec![approve_call, swap_call]
+        };
 
-            let swap_call = Call {
-                to: self.contract_address,
-                selector: selector!("ekubo_manual_swap"),
-                calldata: serialized,
-            };
-
-            let result = self
-                .account
-                .execute_v3(vec![approve_call, swap_call])
-                .send()
-                .await;
-            match result {
-                Ok(x) => Ok(Json(SuccessResponse {
+        let result = self.account.execute_v3(calls).send().await;
+        match result {
+            Ok(x) => {
+                trace_event!(
+                    info,
+                    tx_hash = %format!("{:#x}", x.transaction_hash),
+                    "swap submitted"
+                );
+                Ok(SuccessResponse {
                     success: true,
                     tx_hash: x.transaction_hash,
-                })),
-                Err(_) => Err(Json(ErrorResponse {
-                    success: false,
-                    message: "FAILED TO SWAP".to_string(),
-                })),
+                })
             }
+            Err(_) => Err(ErrorResponse {
+                success: false,
+                message: "FAILED TO SWAP".to_string(),
+            }),
+        }
+    }
+
+    // Build the ekubo_manual_swap call for an amount already scaled to base units
+    fn manual_swap_call(
+        &self,
+        pool_key: PoolKey,
+        amount: u128,
+        is_token1: bool,
+        skip_ahead: u32,
+    ) -> Call {
+        let mut swap_parameters = SwapParameters::new(I129::new(amount, false), is_token1);
+        swap_parameters.skip_ahead = skip_ahead;
+        // Selling token1 moves the price up, so the limit has to be the upper extreme
+        if is_token1 {
+            swap_parameters.sqrt_ratio_limit = MAX_SQRT_RATIO;
+        }
+
+        self.swap_data_call(SwapData::new(
+            swap_parameters,
+            pool_key,
+            self.account.address(),
+        ))
+    }
+
+    // Build the ekubo_manual_swap call buying `amount_out` base units for at most `max_in`.
+    // The amount is in the output token, so `is_token1` is the opposite of `input_is_token1`
+    fn exact_output_call(
+        &self,
+        pool_key: PoolKey,
+        amount_out: u128,
+        max_in: u128,
+        input_is_token1: bool,
+    ) -> Result<Call, ErrorResponse> {
+        let mut swap_parameters =
+            SwapParameters::new(I129::new(amount_out, true), !input_is_token1);
+        swap_parameters.sqrt_ratio_limit =
+            sqrt_ratio_limit_for_max_input(amount_out, max_in, input_is_token1).map_err(|e| {
+                ErrorResponse {
+                    success: false,
+                    message: e.to_string(),
+                }
+            })?;
+
+        Ok(self.swap_data_call(SwapData::new(
+            swap_parameters,
+            pool_key,
+            self.account.address(),
+        )))
+    }
+
+    fn swap_data_call(&self, swap_data: SwapData) -> Call {
+        let mut serialized = vec![];
+        swap_data.encode(&mut serialized).unwrap();
+
+        Call {
+            to: self.contract_address,
+            selector: selector!("ekubo_manual_swap"),
+            calldata: serialized,
         }
     }
 
@@ -297,12 +484,271 @@ impl AutoSwappr {
     }
 }
 
+// Scale a token-unit `amount` of a known token to base units
+fn scale_to_base_units(token: Felt, amount: u128) -> Result<u128, ErrorResponse> {
+    let decimals = TokenAddress::new()
+        .get_token_info_by_address(token)
+        .map_err(|message| ErrorResponse {
+            success: false,
+            message,
+        })?
+        .decimals;
+
+    10_u128
+        .checked_pow(decimals as u32)
+        .and_then(|scale| amount.checked_mul(scale))
+        .ok_or_else(|| ErrorResponse {
+            success: false,
+            message: "SWAP AMOUNT OVERFLOWS".to_string(),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::constant::{STRK, USDC};
+    use crate::contracts::{build_ekubo_calldata, erc20_abi};
+    use crate::test_utils::{MockRpcServer, invoke_calldata, submitting_server};
 
     use super::*;
 
+    fn test_swapper() -> AutoSwappr {
+        AutoSwappr::config(
+            "http://127.0.0.1:5050".to_string(),
+            "0x1234".to_string(),
+            "0x5678".to_string(),
+            "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn responses_are_plain_types() {
+        let result: Result<AutoSwappr, ErrorResponse> = AutoSwappr::config(
+            String::new(),
+            "0x1234".to_string(),
+            "0x5678".to_string(),
+            "0x9abc".to_string(),
+        );
+        let error = result.err().unwrap();
+        assert!(!error.success);
+        assert_eq!(error.message, "EMPTY RPC STRING");
+
+        let mut swapper = test_swapper();
+        let result: Result<SuccessResponse, ErrorResponse> =
+            swapper.ekubo_manual_swap(*STRK, *USDC, 0).await;
+        assert_eq!(result.unwrap_err().message, "SWAP AMOUNT IS ZERO");
+    }
+
+    #[test]
+    fn config_rejects_non_hex_private_key() {
+        let result = AutoSwappr::config(
+            "http://127.0.0.1:5050".to_string(),
+            "0x1234".to_string(),
+            "not-a-key".to_string(),
+            "0x5678".to_string(),
+        );
+        assert_eq!(result.err().unwrap().message, "INVALID PRIVATE KEY");
+    }
+
+    #[test]
+    fn config_rejects_malformed_url() {
+        let result = AutoSwappr::config(
+            "not a url".to_string(),
+            "0x1234".to_string(),
+            "0x5678".to_string(),
+            "0x9abc".to_string(),
+        );
+        assert_eq!(result.err().unwrap().message, "INVALID RPC URL");
+    }
+
+    #[test]
+    fn manual_swap_call_keeps_pool_key() {
+        let swapper = test_swapper();
+        let pool_key = PoolKey {
+            token0: *STRK,
+            token1: *USDC,
+            fee: 1020847100762815390390123822295304634,
+            tick_spacing: 5982,
+            extension: Felt::ZERO,
+        };
+
+        let call = swapper.manual_swap_call(pool_key, 1_000, true, 0);
+
+        // [mag, sign, is_token1, sqrt_ratio_limit (2), skip_ahead, token0, token1, fee, tick_spacing, extension, caller]
+        assert_eq!(call.calldata[2], Felt::ONE);
+        assert_eq!(call.calldata[3], Felt::from(MAX_SQRT_RATIO.low));
+        assert_eq!(call.calldata[4], Felt::from(MAX_SQRT_RATIO.high));
+        assert_eq!(call.calldata[6], *STRK);
+        assert_eq!(call.calldata[7], *USDC);
+        assert_eq!(
+            call.calldata[8],
+            Felt::from(1020847100762815390390123822295304634u128)
+        );
+        assert_eq!(call.calldata[9], Felt::from(5982u128));
+        assert_eq!(call.calldata[10], Felt::ZERO);
+    }
+
+    #[test]
+    fn manual_swap_call_serializes_skip_ahead() {
+        let swapper = test_swapper();
+
+        let call = swapper.manual_swap_call(PoolKey::new(*STRK, *USDC), 1_000, false, 12);
+
+        // [mag, sign, is_token1, sqrt_ratio_limit (2), skip_ahead, ...]
+        assert_eq!(call.calldata[5], Felt::from(12u32));
+        assert_eq!(call.calldata[6], *STRK);
+    }
+
+    #[test]
+    fn manual_swap_call_matches_client_calldata() {
+        let swapper = test_swapper();
+        let (pool_key, is_token1) = PoolKey::for_swap(*USDC, *STRK);
+
+        let call = swapper.manual_swap_call(pool_key.clone(), 1_000, is_token1, 3);
+
+        let swap_data = SwapData::builder()
+            .amount(1_000)
+            .pool(*USDC, *STRK)
+            .skip_ahead(3)
+            .caller(swapper.account.address())
+            .build()
+            .unwrap();
+        assert_eq!(swap_data.pool_key, pool_key);
+        assert_eq!(call.calldata, build_ekubo_calldata(&swap_data).unwrap());
+    }
+
+    // Node that reports `allowance` of STRK and accepts transactions
+    async fn allowance_server(allowance: u128) -> MockRpcServer {
+        submitting_server()
+            .on_call(
+                *STRK,
+                erc20_abi::ALLOWANCE,
+                vec![Felt::from(allowance), Felt::ZERO],
+            )
+            .start()
+            .await
+    }
+
+    fn swapper_for(server: &MockRpcServer) -> AutoSwappr {
+        AutoSwappr::config(
+            server.url.clone(),
+            "0x1234".to_string(),
+            "0x5678".to_string(),
+            "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn swap_approves_short_allowance_by_default() {
+        let server = allowance_server(0).await;
+        let mut swapper = swapper_for(&server);
+        assert!(swapper.auto_approve);
+
+        let response = swapper.ekubo_manual_swap(*STRK, *USDC, 1).await.unwrap();
+        assert_eq!(response.tx_hash, Felt::from(0xa0u32));
+
+        // [n_calls, to, selector, ...]: the approval leads the batch
+        let calldata = invoke_calldata(&server);
+        assert_eq!(calldata[..3], [Felt::TWO, *STRK, selector!("approve")]);
+    }
+
+    #[tokio::test]
+    async fn exact_output_swap_sends_negative_amount() {
+        // Enough allowance for the swap alone
+        let server = allowance_server(u128::MAX).await;
+        let mut swapper = swapper_for(&server);
+
+        swapper
+            .ekubo_manual_swap_exact_output(*STRK, *USDC, 2, 10)
+            .await
+            .unwrap();
+
+        // [n_calls, to, selector, len, mag, sign, is_token1, sqrt_ratio_limit (2), ...]
+        let calldata = invoke_calldata(&server);
+        assert_eq!(calldata[0], Felt::ONE);
+        assert_eq!(calldata[2], selector!("ekubo_manual_swap"));
+        assert_eq!(calldata[4], Felt::from(2_000_000u32));
+        assert_eq!(calldata[5], Felt::ONE);
+        // The amount is in USDC, which sorts after STRK and so is token1
+        assert_eq!(calldata[6], Felt::ONE);
+        assert_eq!(calldata[10], *STRK);
+        assert_eq!(calldata[11], *USDC);
+    }
+
+    #[test]
+    fn exact_input_call_keeps_positive_sign() {
+        let swapper = test_swapper();
+
+        let call = swapper.manual_swap_call(PoolKey::new(*STRK, *USDC), 1_000, false, 0);
+
+        assert_eq!(call.calldata[0], Felt::from(1_000u32));
+        assert_eq!(call.calldata[1], Felt::ZERO);
+    }
+
+    #[tokio::test]
+    async fn swap_rejects_short_allowance_without_auto_approve() {
+        let server = allowance_server(0).await;
+        let mut swapper = AutoSwappr {
+            auto_approve: false,
+            ..swapper_for(&server)
+        };
+
+        let result = swapper.ekubo_manual_swap(*STRK, *USDC, 1).await;
+        assert_eq!(result.unwrap_err().message, "INSUFFICIENT ALLOWANCE");
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+    }
+
+    #[tokio::test]
+    async fn swap_reports_unreadable_allowance() {
+        // No allowance entrypoint mocked, so the read fails
+        let server = submitting_server().start().await;
+        let mut swapper = swapper_for(&server);
+
+        let result = swapper.ekubo_manual_swap(*STRK, *USDC, 1).await;
+        assert_eq!(result.unwrap_err().message, "FAILED TO READ ALLOWANCE");
+        assert!(server.requests("starknet_addInvokeTransaction").is_empty());
+    }
+
+    #[tokio::test]
+    async fn swap_with_pool_rejects_unknown_token() {
+        let mut swapper = test_swapper();
+        let pool_key = PoolKey::new(Felt::from(0x123u32), *STRK);
+
+        let result = swapper
+            .ekubo_manual_swap_with_pool(pool_key, 1, false)
+            .await;
+        assert_eq!(result.unwrap_err().message, "TOKEN IS NOT AVAILABLE");
+    }
+
+    #[tokio::test]
+    async fn swap_with_pool_rejects_overflowing_amount() {
+        let mut swapper = test_swapper();
+        let (pool_key, is_token1) = PoolKey::for_swap(*STRK, *USDC);
+
+        let result = swapper
+            .ekubo_manual_swap_with_pool(pool_key, u128::MAX, is_token1)
+            .await;
+        assert_eq!(result.unwrap_err().message, "SWAP AMOUNT OVERFLOWS");
+    }
+
+    #[tokio::test]
+    async fn swap_with_pool_rejects_identical_tokens() {
+        let mut swapper = test_swapper();
+        let pool_key = PoolKey {
+            token0: *STRK,
+            token1: *STRK,
+            fee: 0,
+            tick_spacing: 0,
+            extension: Felt::ZERO,
+        };
+
+        let result = swapper
+            .ekubo_manual_swap_with_pool(pool_key, 1, false)
+            .await;
+        assert_eq!(result.unwrap_err().message, "POOL TOKENS MUST DIFFER");
+    }
+
     #[tokio::test]
     #[ignore = "owner address and private key  is required to run the test"]
     async fn it_works_bravoos() {
@@ -0,0 +1,129 @@
+// In-process JSON-RPC server used by the unit tests to mock Starknet responses over HTTP
+#![allow(dead_code)]
+
+use axum::{Json, Router, extract::State, routing::post};
+use serde_json::{Value, json};
+use starknet::{
+    core::types::Felt,
+    providers::{JsonRpcClient, Url, jsonrpc::HttpTransport},
+};
+
+pub use crate::testing::{MockRpc, MockTransport};
+use crate::types::connector::{AccountType, AutoSwapprConfig, FeeToken};
+
+pub const TEST_ACCOUNT: &str = "0x0234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
+pub const TEST_CONTRACT: &str =
+    "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b";
+pub const TEST_PRIVATE_KEY: &str =
+    "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
+
+/// Build a client configuration pointing at the given mock RPC URL
+pub fn test_config(rpc_url: &str) -> AutoSwapprConfig {
+    AutoSwapprConfig {
+        contract_address: TEST_CONTRACT.to_string(),
+        rpc_url: rpc_url.to_string(),
+        account_address: TEST_ACCOUNT.to_string(),
+        private_key: TEST_PRIVATE_KEY.to_string(),
+        account_type: AccountType::Auto,
+        fee_token: FeeToken::Strk,
+    }
+}
+
+/// Latest block at unix time `timestamp`, with unit gas prices
+pub fn block_json(timestamp: u64) -> Value {
+    json!({
+        "status": "ACCEPTED_ON_L2",
+        "block_hash": "0x1",
+        "parent_hash": "0x0",
+        "block_number": 100,
+        "new_root": "0x2",
+        "timestamp": timestamp,
+        "sequencer_address": "0x3",
+        "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+        "l2_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+        "l1_data_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+        "l1_da_mode": "BLOB",
+        "starknet_version": "0.14.0",
+        "transactions": []
+    })
+}
+
+/// Fee estimate of a single transaction, with every resource at 1
+pub fn fee_estimate_json() -> Value {
+    json!([{
+        "l1_gas_consumed": "0x1",
+        "l1_gas_price": "0x1",
+        "l2_gas_consumed": "0x1",
+        "l2_gas_price": "0x1",
+        "l1_data_gas_consumed": "0x1",
+        "l1_data_gas_price": "0x1",
+        "overall_fee": "0x3",
+        "unit": "FRI"
+    }])
+}
+
+/// Node that estimates and accepts transactions, answering with hash `0xa0`
+pub fn submitting_server() -> MockRpc {
+    MockRpc::new()
+        .on_method("starknet_getNonce", json!("0x0"))
+        .on_method("starknet_estimateFee", fee_estimate_json())
+        .on_method("starknet_getBlockWithTxs", block_json(2_000))
+        .on_method(
+            "starknet_addInvokeTransaction",
+            json!({ "transaction_hash": "0xa0" }),
+        )
+}
+
+/// Calldata of the only transaction submitted to `server`
+pub fn invoke_calldata(server: &MockRpcServer) -> Vec<Felt> {
+    let requests = server.requests("starknet_addInvokeTransaction");
+    assert_eq!(requests.len(), 1);
+    serde_json::from_value(requests[0]["params"]["invoke_transaction"]["calldata"].clone()).unwrap()
+}
+
+impl MockRpc {
+    /// Start serving on a random local port
+    pub async fn start(self) -> MockRpcServer {
+        let transport = self.transport();
+        let app = Router::new()
+            .route("/", post(handle))
+            .with_state(transport.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        MockRpcServer { url, transport }
+    }
+}
+
+async fn handle(State(transport): State<MockTransport>, Json(body): Json<Value>) -> Json<Value> {
+    match &body {
+        Value::Array(requests) => Json(Value::Array(
+            requests.iter().map(|r| transport.respond(r)).collect(),
+        )),
+        request => Json(transport.respond(request)),
+    }
+}
+
+/// Handle to a running mock server
+pub struct MockRpcServer {
+    pub url: String,
+    transport: MockTransport,
+}
+
+impl MockRpcServer {
+    /// HTTP provider pointed at this server
+    pub fn provider(&self) -> JsonRpcClient<HttpTransport> {
+        JsonRpcClient::new(HttpTransport::new(Url::parse(&self.url).unwrap()))
+    }
+
+    /// All requests received so far for the given JSON-RPC method
+    pub fn requests(&self, method: &str) -> Vec<Value> {
+        self.transport.requests(method)
+    }
+
+    /// Number of `starknet_call` requests received for the given selector name
+    pub fn call_count(&self, selector: &str) -> usize {
+        self.transport.call_count(selector)
+    }
+}
@@ -0,0 +1,355 @@
+// In-memory Starknet provider with canned responses, for deterministic offline tests
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use starknet::{
+    core::{types::Felt, utils::get_selector_from_name},
+    providers::{
+        JsonRpcClient, ProviderRequestData,
+        jsonrpc::{JsonRpcMethod, JsonRpcResponse, JsonRpcTransport},
+    },
+};
+
+/// A [`Provider`](starknet::providers::Provider) answering from a [`MockRpc`] in memory
+/// instead of a node.
+///
+/// Pass it to [`AutoSwapprClient::from_provider`](crate::AutoSwapprClient::from_provider)
+/// or to the contract wrappers to exercise read paths offline.
+pub type MockProvider = JsonRpcClient<MockTransport>;
+
+/// Result of a mocked request: a JSON-RPC result, or an error code and message
+pub type RpcResult = Result<Value, (i64, String)>;
+
+// A JSON-RPC result, or an error code, message and optional data
+type MockResponse = Result<Value, (i64, String, Option<Value>)>;
+
+#[derive(Debug, Clone, Default)]
+struct MockState {
+    calls: HashMap<(Felt, Felt), Vec<MockResponse>>,
+    methods: HashMap<String, Vec<MockResponse>>,
+    requests: Vec<Value>,
+}
+
+impl MockState {
+    // An empty sequence answers with an internal JSON-RPC error rather than panicking
+    fn next(queue: &mut Vec<MockResponse>) -> MockResponse {
+        match queue.len() {
+            0 => Err((-32603, "No mocked responses".to_string(), None)),
+            1 => queue[0].clone(),
+            _ => queue.remove(0),
+        }
+    }
+
+    fn respond(&mut self, request: &Value) -> MockResponse {
+        let method = request["method"].as_str().unwrap_or_default().to_string();
+
+        if method == "starknet_call" {
+            let call = &request["params"]["request"];
+            let contract = parse_felt(&call["contract_address"]);
+            let selector = parse_felt(&call["entry_point_selector"]);
+            return match self.calls.get_mut(&(contract, selector)) {
+                Some(queue) => Self::next(queue),
+                None => Err((20, "Contract not found".to_string(), None)),
+            };
+        }
+
+        match self.methods.get_mut(&method) {
+            Some(queue) => Self::next(queue),
+            None => Err((-32601, format!("Method not found: {}", method), None)),
+        }
+    }
+}
+
+/// Canned JSON-RPC responses, keyed by `(contract, selector)` for `starknet_call` and by
+/// method name for everything else.
+///
+/// Unmocked calls fail with `ContractNotFound`, and unmocked methods with a
+/// method-not-found JSON-RPC error.
+#[derive(Debug, Clone, Default)]
+pub struct MockRpc {
+    state: MockState,
+}
+
+impl MockRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Respond to `starknet_call` on `contract`'s `selector` entrypoint with the given felts
+    pub fn on_call(self, contract: Felt, selector: &str, result: Vec<Felt>) -> Self {
+        self.on_call_seq(contract, selector, vec![result])
+    }
+
+    /// Respond to successive `starknet_call`s with each result in turn, repeating the last one.
+    /// An empty `results` fails every call with an internal error.
+    pub fn on_call_seq(mut self, contract: Felt, selector: &str, results: Vec<Vec<Felt>>) -> Self {
+        self.state.calls.insert(
+            (contract, selector_felt(selector)),
+            results.iter().map(|r| Ok(felts_to_json(r))).collect(),
+        );
+        self
+    }
+
+    /// Fail `starknet_call` on `contract`'s `selector` entrypoint with a JSON-RPC error,
+    /// e.g. code 21 for a missing entrypoint
+    pub fn on_call_error(
+        mut self,
+        contract: Felt,
+        selector: &str,
+        code: i64,
+        message: &str,
+    ) -> Self {
+        self.state.calls.insert(
+            (contract, selector_felt(selector)),
+            vec![Err((code, message.to_string(), None))],
+        );
+        self
+    }
+
+    /// Fail `starknet_call` on `contract`'s `selector` entrypoint with a contract error
+    /// (code 40) whose revert reason is `revert_error`
+    pub fn on_call_revert(mut self, contract: Felt, selector: &str, revert_error: &str) -> Self {
+        self.state.calls.insert(
+            (contract, selector_felt(selector)),
+            vec![Err((
+                40,
+                "Contract error".to_string(),
+                Some(json!({ "revert_error": revert_error })),
+            ))],
+        );
+        self
+    }
+
+    /// Respond to a JSON-RPC method such as `"starknet_chainId"` with the given result
+    pub fn on_method(self, method: &str, result: Value) -> Self {
+        self.on_method_seq(method, vec![result])
+    }
+
+    /// Respond to successive calls of a method with each result in turn, repeating the last one.
+    /// An empty `results` fails every call with an internal error.
+    pub fn on_method_seq(mut self, method: &str, results: Vec<Value>) -> Self {
+        self.state
+            .methods
+            .insert(method.to_string(), results.into_iter().map(Ok).collect());
+        self
+    }
+
+    /// Respond to successive calls of a method with each result or error in turn
+    pub fn on_method_responses(mut self, method: &str, responses: Vec<RpcResult>) -> Self {
+        self.state.methods.insert(
+            method.to_string(),
+            responses
+                .into_iter()
+                .map(|response| response.map_err(|(code, message)| (code, message, None)))
+                .collect(),
+        );
+        self
+    }
+
+    /// Fail a JSON-RPC method with an error
+    pub fn on_method_error(mut self, method: &str, code: i64, message: &str) -> Self {
+        self.state.methods.insert(
+            method.to_string(),
+            vec![Err((code, message.to_string(), None))],
+        );
+        self
+    }
+
+    /// Serve these responses in memory through a transport
+    pub fn transport(self) -> MockTransport {
+        MockTransport {
+            state: Arc::new(Mutex::new(self.state)),
+            #[cfg(test)]
+            call_barrier: None,
+        }
+    }
+
+    /// Build a provider serving these responses
+    pub fn into_provider(self) -> MockProvider {
+        JsonRpcClient::new(self.transport())
+    }
+}
+
+/// In-memory JSON-RPC transport answering from a [`MockRpc`] and recording every request.
+///
+/// Clones share the responses and the recorded requests.
+#[derive(Debug, Clone)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+    #[cfg(test)]
+    call_barrier: Option<Arc<tokio::sync::Barrier>>,
+}
+
+impl MockTransport {
+    /// All requests received so far for the given JSON-RPC method
+    pub fn requests(&self, method: &str) -> Vec<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .requests
+            .iter()
+            .filter(|r| r["method"] == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Number of `starknet_call` requests received for the given selector name
+    pub fn call_count(&self, selector: &str) -> usize {
+        let selector = selector_felt(selector);
+        self.requests("starknet_call")
+            .iter()
+            .filter(|r| parse_felt(&r["params"]["request"]["entry_point_selector"]) == selector)
+            .count()
+    }
+
+    /// Hold each `starknet_call` until `n` calls are pending, so sequential callers stall
+    #[cfg(test)]
+    pub(crate) fn with_call_barrier(mut self, n: usize) -> Self {
+        self.call_barrier = Some(Arc::new(tokio::sync::Barrier::new(n)));
+        self
+    }
+
+    // Record a request and build its JSON-RPC response envelope
+    pub(crate) fn respond(&self, request: &Value) -> Value {
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(request.clone());
+        match state.respond(request) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err((code, message, data)) => {
+                let mut error = json!({ "code": code, "message": message });
+                if let Some(data) = data {
+                    error["data"] = data;
+                }
+                json!({ "jsonrpc": "2.0", "id": request["id"], "error": error })
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl JsonRpcTransport for MockTransport {
+    type Error = std::io::Error;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        #[cfg(test)]
+        if let Some(barrier) = &self.call_barrier
+            && request["method"] == "starknet_call"
+        {
+            barrier.wait().await;
+        }
+        serde_json::from_value(self.respond(&request)).map_err(std::io::Error::other)
+    }
+
+    async fn send_requests<R>(
+        &self,
+        _requests: R,
+    ) -> Result<Vec<JsonRpcResponse<Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        Err(std::io::Error::other("batch requests are not mocked"))
+    }
+}
+
+fn selector_felt(selector: &str) -> Felt {
+    get_selector_from_name(selector).expect("selector names are ASCII")
+}
+
+fn parse_felt(value: &Value) -> Felt {
+    Felt::from_hex(value.as_str().unwrap_or_default()).unwrap_or_default()
+}
+
+fn felts_to_json(felts: &[Felt]) -> Value {
+    Value::Array(
+        felts
+            .iter()
+            .map(|felt| Value::String(format!("{:#x}", felt)))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::contracts::{ContractError, Erc20Contract, erc20_abi};
+
+    #[tokio::test]
+    async fn test_mock_provider_returns_balance() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let provider = Arc::new(
+            MockRpc::new()
+                .on_call(
+                    token,
+                    erc20_abi::BALANCE_OF,
+                    vec![Felt::from(42u8), Felt::ONE],
+                )
+                .into_provider(),
+        );
+
+        let balance = Erc20Contract::new(token)
+            .balance_of(&*provider, Felt::from_hex("0x456").unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(balance.low, 42);
+        assert_eq!(balance.high, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_returns_error() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let provider = Arc::new(
+            MockRpc::new()
+                .on_call_error(token, erc20_abi::BALANCE_OF, 40, "Contract error")
+                .into_provider(),
+        );
+        let erc20 = Erc20Contract::new(token);
+
+        assert!(matches!(
+            erc20.balance_of(&*provider, Felt::ONE).await,
+            Err(ContractError::ProviderError(_))
+        ));
+        // Unmocked entrypoints fail too
+        assert!(erc20.decimals(&*provider).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_sequences_fail_the_request() {
+        let token = Felt::from_hex("0x123").unwrap();
+        let provider = Arc::new(
+            MockRpc::new()
+                .on_call_seq(token, erc20_abi::BALANCE_OF, vec![])
+                .on_method_seq("starknet_chainId", vec![])
+                .into_provider(),
+        );
+
+        assert!(matches!(
+            Erc20Contract::new(token)
+                .balance_of(&*provider, Felt::ONE)
+                .await,
+            Err(ContractError::ProviderError(_))
+        ));
+        assert!(
+            starknet::providers::Provider::chain_id(&*provider)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+use std::{fmt, str::FromStr};
+
+use crate::{constant::TokenAddress, types::connector::AutoSwapprError};
+
+/// Most significant hex digits a felt can have; the field prime is just above 2^251
+pub const MAX_ADDRESS_HEX_DIGITS: usize = 63;
+
+/// Validated Starknet contract or account address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Address(Felt);
+
+impl Address {
+    /// Parse a hex address such as `"0x049d...4dc7"`.
+    ///
+    /// Rejects values with more than [`MAX_ADDRESS_HEX_DIGITS`] significant digits or at or
+    /// above the field prime, which [`Felt::from_hex`] would wrap around to another address.
+    pub fn from_hex(value: &str) -> Result<Self, AutoSwapprError> {
+        let invalid = |reason: String| AutoSwapprError::InvalidInput {
+            details: format!("Invalid address '{}': {}", value, reason),
+        };
+
+        let digits = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+            .unwrap_or(value)
+            .trim_start_matches('0');
+        if digits.len() > MAX_ADDRESS_HEX_DIGITS {
+            return Err(invalid(format!(
+                "more than {} hex digits",
+                MAX_ADDRESS_HEX_DIGITS
+            )));
+        }
+
+        // `Felt::from_hex` only understands a lowercase prefix
+        let normalized = match value.strip_prefix("0X") {
+            Some(rest) => format!("0x{}", rest),
+            None => value.to_string(),
+        };
+        let felt = Felt::from_hex(&normalized).map_err(|e| invalid(e.to_string()))?;
+        // A reduced value no longer prints as the digits it was parsed from
+        if !format!("{:x}", felt).eq_ignore_ascii_case(if digits.is_empty() { "0" } else { digits })
+        {
+            return Err(invalid("not below the field prime".to_string()));
+        }
+
+        Ok(Address(felt))
+    }
+
+    /// Get the address as a felt
+    pub fn felt(self) -> Felt {
+        self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl FromStr for Address {
+    type Err = AutoSwapprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Address::from_hex(s)
+    }
+}
+
+impl From<Felt> for Address {
+    fn from(value: Felt) -> Self {
+        Address(value)
+    }
+}
+
+impl From<Address> for Felt {
+    fn from(value: Address) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<&str> for Address {
+    type Error = AutoSwapprError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Address::from_hex(value)
+    }
+}
+
+impl TryFrom<&String> for Address {
+    type Error = AutoSwapprError;
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        Address::from_hex(value)
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = AutoSwapprError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Address::from_hex(&value)
+    }
+}
+
+/// Anything a client method accepts as an address: `&str`, `String`, [`Felt`] or [`Address`]
+pub trait IntoAddress {
+    fn into_address(self) -> Result<Address, AutoSwapprError>;
+}
+
+impl<T> IntoAddress for T
+where
+    T: TryInto<Address>,
+    T::Error: Into<AutoSwapprError>,
+{
+    fn into_address(self) -> Result<Address, AutoSwapprError> {
+        self.try_into().map_err(Into::into)
+    }
+}
+
+/// A token given by symbol, such as `"ETH"`, or by address.
+///
+/// Converting from a string gives an [`Address`](TokenRef::Address) for `0x` or `0X` hex and a
+/// [`Symbol`](TokenRef::Symbol) otherwise, so swap methods taking `impl Into<TokenRef>`
+/// accept both interchangeably.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenRef {
+    Symbol(String),
+    Address(Felt),
+}
+
+impl TokenRef {
+    /// Get the token's address, looking symbols up case-insensitively in `registry`
+    pub fn resolve(&self, registry: &TokenAddress<'_>) -> Result<Felt, AutoSwapprError> {
+        match self {
+            TokenRef::Address(address) => Ok(*address),
+            TokenRef::Symbol(symbol) if has_hex_prefix(symbol) => {
+                Address::from_hex(symbol).map(Address::felt)
+            }
+            TokenRef::Symbol(symbol) => registry
+                .tokens
+                .iter()
+                .find(|info| info.symbol.eq_ignore_ascii_case(symbol))
+                .map(|info| info.address)
+                .ok_or_else(|| AutoSwapprError::UnsupportedToken {
+                    token: symbol.clone(),
+                }),
+        }
+    }
+}
+
+fn has_hex_prefix(value: &str) -> bool {
+    value
+        .get(..2)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("0x"))
+}
+
+impl From<&str> for TokenRef {
+    fn from(value: &str) -> Self {
+        match Address::from_hex(value) {
+            Ok(address) if has_hex_prefix(value) => TokenRef::Address(address.felt()),
+            // Malformed hex stays a symbol and fails as an invalid address on resolve
+            _ => TokenRef::Symbol(value.to_string()),
+        }
+    }
+}
+
+impl From<&String> for TokenRef {
+    fn from(value: &String) -> Self {
+        TokenRef::from(value.as_str())
+    }
+}
+
+impl From<String> for TokenRef {
+    fn from(value: String) -> Self {
+        TokenRef::from(value.as_str())
+    }
+}
+
+impl From<Felt> for TokenRef {
+    fn from(value: Felt) -> Self {
+        TokenRef::Address(value)
+    }
+}
+
+impl From<Address> for TokenRef {
+    fn from(value: Address) -> Self {
+        TokenRef::Address(value.felt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_from_hex() {
+        let address = Address::from_hex("0x123").unwrap();
+        assert_eq!(address.felt(), Felt::from(0x123u32));
+
+        assert!(matches!(
+            Address::from_hex("not-hex"),
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+        assert!("0xzz".into_address().is_err());
+    }
+
+    #[test]
+    fn test_address_display_round_trip() {
+        let hex = "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+        let address: Address = hex.parse().unwrap();
+
+        assert_eq!(address.to_string(), hex);
+        assert_eq!(Address::from_hex(&address.to_string()).unwrap(), address);
+        assert_eq!(Felt::from(address).into_address().unwrap(), address);
+    }
+
+    #[test]
+    fn test_address_rejects_overlong_felts() {
+        // 65 hex digits
+        let overlong = format!("0x1{}", "0".repeat(64));
+        assert!(matches!(
+            Address::from_hex(&overlong),
+            Err(AutoSwapprError::InvalidInput { details }) if details.contains("hex digits")
+        ));
+
+        // The field prime and the largest 63-digit value are at or above it
+        let prime = "0x800000000000011000000000000000000000000000000000000000000000001";
+        for value in [
+            prime,
+            "0x800000000000011000000000000000000000000000000000000000000000002",
+        ] {
+            assert!(matches!(
+                Address::from_hex(value),
+                Err(AutoSwapprError::InvalidInput { details }) if details.contains("field prime")
+            ));
+        }
+        assert!(Address::from_hex(&format!("0x{}", "f".repeat(63))).is_err());
+
+        // Just below the prime, and leading zeros, are fine
+        let below = "0x800000000000011000000000000000000000000000000000000000000000000";
+        assert_eq!(Address::from_hex(below).unwrap().to_string(), below);
+        assert_eq!(
+            Address::from_hex(&format!("0x{}123", "0".repeat(61))).unwrap(),
+            Address::from(Felt::from(0x123u32))
+        );
+    }
+
+    #[test]
+    fn test_token_ref_resolves_symbol_and_address() {
+        let registry = TokenAddress::new();
+
+        assert_eq!(
+            TokenRef::from("eth").resolve(&registry).unwrap(),
+            *crate::ETH
+        );
+        let hex = format!("{:#x}", *crate::USDC);
+        assert_eq!(
+            TokenRef::from(hex.as_str()),
+            TokenRef::Address(*crate::USDC)
+        );
+        assert_eq!(
+            TokenRef::from(hex.replacen("0x", "0X", 1).as_str()),
+            TokenRef::Address(*crate::USDC)
+        );
+        assert_eq!(
+            TokenRef::from(hex).resolve(&registry).unwrap(),
+            *crate::USDC
+        );
+        // Addresses need not be in the registry
+        assert_eq!(
+            TokenRef::from(Felt::from(0x123u32))
+                .resolve(&registry)
+                .unwrap(),
+            Felt::from(0x123u32)
+        );
+    }
+
+    #[test]
+    fn test_token_ref_rejects_unknown_symbol() {
+        let registry = TokenAddress::new();
+
+        assert!(matches!(
+            TokenRef::from("DOGE").resolve(&registry),
+            Err(AutoSwapprError::UnsupportedToken { token }) if token == "DOGE"
+        ));
+        assert!(matches!(
+            TokenRef::from("0xzz").resolve(&registry),
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            TokenRef::from("0Xzz").resolve(&registry),
+            Err(AutoSwapprError::InvalidInput { .. })
+        ));
+    }
+}
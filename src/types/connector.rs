@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use starknet::{
-    accounts::SingleOwnerAccount,
+    accounts::{ExecutionEncoding, SingleOwnerAccount},
     core::{
         codec::{Decode, Encode},
         types::{Felt, U256},
@@ -10,7 +10,11 @@ use starknet::{
 };
 use thiserror::Error;
 
-use crate::{USDC, USDT};
+use crate::{
+    ETH, STRK, USDC, USDT,
+    math::{MAX_SQRT_RATIO, MIN_SQRT_RATIO},
+    types::address::Address,
+};
 
 /// Configuration for the AutoSwappr SDK
 #[derive(Debug)]
@@ -20,10 +24,416 @@ pub struct AutoSwappr {
     pub private_key: String,
     pub account: SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
     pub contract_address: Felt,
+    pub auto_approve: bool, // Approve alongside a swap when the allowance is short (default true)
+}
+
+/// Configuration for the AutoSwappr client
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutoSwapprConfig {
+    pub contract_address: String,
+    pub rpc_url: String,
+    pub account_address: String,
+    pub private_key: String,
+    #[serde(default)]
+    pub account_type: AccountType, // Account implementation, for the calldata encoding
+    #[serde(default)]
+    pub fee_token: FeeToken, // Token transaction fees are paid in
+}
+
+/// Account implementation behind an address, which decides how `__execute__` calldata is
+/// encoded
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccountType {
+    /// Argent accounts, which take Cairo 1 calldata
+    Argent,
+    /// Older Braavos accounts, which still take Cairo 0 (legacy) calldata
+    Braavos,
+    /// Detect the encoding from the account's class when the client is created
+    #[default]
+    Auto,
+}
+
+impl AccountType {
+    /// The encoding this account type implies, or `None` for [`AccountType::Auto`]
+    pub fn execution_encoding(&self) -> Option<ExecutionEncoding> {
+        match self {
+            AccountType::Argent => Some(ExecutionEncoding::New),
+            AccountType::Braavos => Some(ExecutionEncoding::Legacy),
+            AccountType::Auto => None,
+        }
+    }
+}
+
+/// Token the account pays transaction fees in.
+///
+/// Transactions are sent as v3, which pay in STRK. Starknet no longer accepts the v1
+/// transactions that paid in ETH, so clients configured with [`FeeToken::Eth`] fail to build
+/// with `InvalidInput`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeeToken {
+    /// STRK, paid by v3 transactions within their resource bounds
+    #[default]
+    Strk,
+    /// ETH, only payable by v1 transactions, which are no longer accepted
+    Eth,
+}
+
+impl FeeToken {
+    /// Address of the fee token contract
+    pub fn address(&self) -> Felt {
+        match self {
+            FeeToken::Strk => *STRK,
+            FeeToken::Eth => *ETH,
+        }
+    }
+
+    /// Symbol of the fee token, e.g. `"STRK"`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            FeeToken::Strk => "STRK",
+            FeeToken::Eth => "ETH",
+        }
+    }
+}
+
+impl AutoSwapprConfig {
+    /// Load the configuration from the `RPC_URL`, `PRIVATE_KEY`, `ACCOUNT_ADDRESS`
+    /// and `CONTRACT_ADDRESS` environment variables
+    pub fn from_env() -> Result<AutoSwapprConfig, AutoSwapprError> {
+        Self::from_env_with_prefix("")
+    }
+
+    /// Load the configuration from prefixed environment variables.
+    ///
+    /// A prefix of `AUTOSWAP_A` reads `AUTOSWAP_A_RPC_URL`, `AUTOSWAP_A_PRIVATE_KEY`, etc.
+    pub fn from_env_with_prefix(prefix: &str) -> Result<AutoSwapprConfig, AutoSwapprError> {
+        let read = |name: &str| {
+            let key = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}_{}", prefix.trim_end_matches('_'), name)
+            };
+            std::env::var(&key).map_err(|_| AutoSwapprError::InvalidInput {
+                details: format!("Missing environment variable: {}", key),
+            })
+        };
+
+        let config = AutoSwapprConfig {
+            rpc_url: read("RPC_URL")?,
+            private_key: read("PRIVATE_KEY")?,
+            account_address: read("ACCOUNT_ADDRESS")?,
+            contract_address: read("CONTRACT_ADDRESS")?,
+            account_type: AccountType::Auto,
+            fee_token: FeeToken::Strk,
+        };
+
+        Felt::from_hex(&config.private_key).map_err(|e| AutoSwapprError::InvalidInput {
+            details: format!("Invalid private key: {}", e),
+        })?;
+        // `Felt::from_hex` would silently wrap an address at or above the field prime
+        Address::from_hex(&config.account_address)?;
+        Address::from_hex(&config.contract_address)?;
+
+        Ok(config)
+    }
+}
+
+/// Swap submitted through [`AutoSwapprClient`](crate::AutoSwapprClient) while history is enabled
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SwapRecord {
+    pub tx_hash: String,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount: Uint256,
+    /// Unix time in seconds when the transaction was submitted
+    pub timestamp: u64,
+}
+
+/// `SwapSuccessful` event emitted by the AutoSwappr contract for a completed swap
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SwapExecuted {
+    pub token_from: Felt,
+    pub amount_from: Uint256,
+    pub token_to: Felt,
+    pub amount_to: Uint256,
+    pub beneficiary: Felt,
+    /// Exchange contract the swap went through
+    pub provider: Felt,
+    /// `None` while the block is still pre-confirmed
+    pub block_number: Option<u64>,
+    pub transaction_hash: Felt,
+}
+
+/// Cairo u256 split into its two u128 limbs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Encode, Decode)]
+pub struct Uint256 {
+    pub low: u128,
+    pub high: u128,
+}
+
+impl Uint256 {
+    pub fn from_u128(value: u128) -> Self {
+        Uint256 {
+            low: value,
+            high: 0,
+        }
+    }
+
+    /// Parse a decimal string into a full 256-bit value, rejecting overflow
+    pub fn from_dec_str(value: &str) -> Result<Self, AutoSwapprError> {
+        let invalid = |reason: &str| AutoSwapprError::InvalidInput {
+            details: format!("Invalid amount '{}': {}", value, reason),
+        };
+
+        if value.is_empty() {
+            return Err(invalid("empty string"));
+        }
+
+        let mut result = Uint256::default();
+        for c in value.chars() {
+            let digit = c
+                .to_digit(10)
+                .ok_or_else(|| invalid("not a decimal number"))?;
+            result = result
+                .checked_mul_add(10, digit as u128)
+                .ok_or_else(|| invalid("exceeds u256"))?;
+        }
+
+        Ok(result)
+    }
+
+    /// Parse a `0x`-prefixed hex or a decimal string into a full 256-bit value
+    pub fn from_string(value: &str) -> Result<Self, AutoSwapprError> {
+        match value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+        {
+            Some(digits) => Self::from_hex_digits(value, digits),
+            None => Self::from_dec_str(value),
+        }
+    }
+
+    fn from_hex_digits(value: &str, digits: &str) -> Result<Self, AutoSwapprError> {
+        let invalid = |reason: &str| AutoSwapprError::InvalidInput {
+            details: format!("Invalid amount '{}': {}", value, reason),
+        };
+
+        if digits.is_empty() {
+            return Err(invalid("no hex digits"));
+        }
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid("not a hex number"));
+        }
+
+        let digits = digits.trim_start_matches('0');
+        if digits.len() > 64 {
+            return Err(invalid("exceeds u256"));
+        }
+
+        // The low limb is the last 32 hex digits; all digits are ASCII, so slicing is safe
+        let split = digits.len().saturating_sub(32);
+        let parse = |limb: &str| {
+            if limb.is_empty() {
+                Ok(0)
+            } else {
+                u128::from_str_radix(limb, 16).map_err(|e| invalid(&e.to_string()))
+            }
+        };
+
+        Ok(Uint256 {
+            low: parse(&digits[split..])?,
+            high: parse(&digits[..split])?,
+        })
+    }
+
+    // self * factor + addend, for small factor/addend (< 2^64)
+    fn checked_mul_add(self, factor: u128, addend: u128) -> Option<Self> {
+        const MASK: u128 = u64::MAX as u128;
+
+        let lo = (self.low & MASK) * factor + addend;
+        let hi = (self.low >> 64) * factor + (lo >> 64);
+        let low = (hi << 64) | (lo & MASK);
+        let high = self.high.checked_mul(factor)?.checked_add(hi >> 64)?;
+
+        Some(Uint256 { low, high })
+    }
+
+    /// Checked addition, `None` on overflow
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let (low, carry) = self.low.overflowing_add(rhs.low);
+        let high = self
+            .high
+            .checked_add(rhs.high)?
+            .checked_add(carry as u128)?;
+
+        Some(Uint256 { low, high })
+    }
+
+    /// Checked subtraction, `None` on underflow
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        let (low, borrow) = self.low.overflowing_sub(rhs.low);
+        let high = self
+            .high
+            .checked_sub(rhs.high)?
+            .checked_sub(borrow as u128)?;
+
+        Some(Uint256 { low, high })
+    }
+
+    /// Compute `self * mul / div` rounded down, with a 512-bit intermediate product so
+    /// slippage math can't overflow halfway.
+    ///
+    /// Returns `None` when `div` is zero or the result doesn't fit in 256 bits.
+    pub fn mul_div(self, mul: Self, div: Self) -> Option<Self> {
+        if div == Uint256::default() {
+            return None;
+        }
+
+        let (a, b) = (self.limbs(), mul.limbs());
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = a[i] as u128 * b[j] as u128 + product[i + j] as u128 + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+
+        // Binary long division of the 512-bit product
+        let mut quotient = [0u64; 8];
+        let mut remainder = Uint256::default();
+        for bit in (0..512).rev() {
+            let overflow = remainder.high >> 127 == 1;
+            remainder = Uint256 {
+                low: (remainder.low << 1) | ((product[bit / 64] >> (bit % 64)) & 1) as u128,
+                high: (remainder.high << 1) | (remainder.low >> 127),
+            };
+
+            if overflow || remainder >= div {
+                remainder = remainder.wrapping_sub(div);
+                quotient[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+
+        if quotient[4..].iter().any(|&limb| limb != 0) {
+            return None;
+        }
+
+        Some(Uint256 {
+            low: quotient[0] as u128 | (quotient[1] as u128) << 64,
+            high: quotient[2] as u128 | (quotient[3] as u128) << 64,
+        })
+    }
+
+    /// Split into `(low, high)` felts, the Cairo calldata layout of a u256
+    pub fn to_felts(self) -> (Felt, Felt) {
+        (Felt::from(self.low), Felt::from(self.high))
+    }
+
+    // Little-endian u64 limbs
+    fn limbs(self) -> [u64; 4] {
+        [
+            self.low as u64,
+            (self.low >> 64) as u64,
+            self.high as u64,
+            (self.high >> 64) as u64,
+        ]
+    }
+
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        let (low, borrow) = self.low.overflowing_sub(rhs.low);
+        let high = self
+            .high
+            .wrapping_sub(rhs.high)
+            .wrapping_sub(borrow as u128);
+
+        Uint256 { low, high }
+    }
+}
+
+impl Ord for Uint256 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.high, self.low).cmp(&(other.high, other.low))
+    }
+}
+
+impl PartialOrd for Uint256 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<u128> for Uint256 {
+    fn from(value: u128) -> Self {
+        Uint256::from_u128(value)
+    }
+}
+
+impl TryFrom<Uint256> for u128 {
+    type Error = AutoSwapprError;
+
+    fn try_from(value: Uint256) -> Result<Self, Self::Error> {
+        if value.high != 0 {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!("Value {:?} exceeds u128", value),
+            });
+        }
+
+        Ok(value.low)
+    }
+}
+
+impl From<U256> for Uint256 {
+    fn from(value: U256) -> Self {
+        Uint256 {
+            low: value.low(),
+            high: value.high(),
+        }
+    }
+}
+
+impl From<Uint256> for U256 {
+    fn from(value: Uint256) -> Self {
+        U256::from_words(value.low, value.high)
+    }
+}
+
+impl From<Felt> for Uint256 {
+    fn from(value: Felt) -> Self {
+        let bytes = value.to_bytes_be();
+        let (high, low) = bytes.split_at(16);
+
+        Uint256 {
+            low: u128::from_be_bytes(low.try_into().unwrap()),
+            high: u128::from_be_bytes(high.try_into().unwrap()),
+        }
+    }
+}
+
+impl TryFrom<Uint256> for Felt {
+    type Error = AutoSwapprError;
+
+    /// Fails for values at or above the field prime
+    fn try_from(value: Uint256) -> Result<Self, Self::Error> {
+        let mut bytes = [0u8; 32];
+        bytes[..16].copy_from_slice(&value.high.to_be_bytes());
+        bytes[16..].copy_from_slice(&value.low.to_be_bytes());
+
+        let felt = Felt::from_bytes_be(&bytes);
+        if Uint256::from(felt) != value {
+            return Err(AutoSwapprError::InvalidInput {
+                details: format!("Value {:?} exceeds the felt range", value),
+            });
+        }
+
+        Ok(felt)
+    }
 }
 
 /// Ekubo pool key structure
-#[derive(Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct PoolKey {
     pub token0: Felt,       // First token in the pool
     pub token1: Felt,       // Second token in the pool
@@ -42,15 +452,25 @@ impl I129 {
     pub fn new(mag: u128, sign: bool) -> Self {
         I129 { mag, sign }
     }
+
+    /// Signed value, where `sign` means negative; saturates at `i128::MIN`/`i128::MAX`
+    pub fn to_i128_saturating(&self) -> i128 {
+        match (i128::try_from(self.mag), self.sign) {
+            (Ok(value), false) => value,
+            (Ok(value), true) => -value,
+            (Err(_), false) => i128::MAX,
+            (Err(_), true) => i128::MIN,
+        }
+    }
 }
 
 /// Ekubo swap parameters
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct SwapParameters {
-    pub amount: I129,           // Amount to swap with magnitude and sign
-    pub is_token1: bool,        // Whether the input token is token1
-    pub sqrt_ratio_limit: U256, // Price limit for the swap (U256)
-    pub skip_ahead: u32,        // Skip ahead parameter (u32)
+    pub amount: I129,              // Amount to swap with magnitude and sign
+    pub is_token1: bool,           // Whether the input token is token1
+    pub sqrt_ratio_limit: Uint256, // Price limit for the swap (u256)
+    pub skip_ahead: u32,           // Skip ahead parameter (u32)
 }
 
 impl SwapParameters {
@@ -58,7 +478,7 @@ impl SwapParameters {
         SwapParameters {
             amount,
             is_token1,
-            sqrt_ratio_limit: U256::from(18446748437148339061u128),
+            sqrt_ratio_limit: Uint256::from(MIN_SQRT_RATIO),
             skip_ahead: 0,
         }
     }
@@ -72,6 +492,11 @@ pub struct SwapData {
 }
 
 impl SwapData {
+    /// Start building swap data with a fluent API
+    pub fn builder() -> SwapDataBuilder {
+        SwapDataBuilder::new()
+    }
+
     pub fn new(params: SwapParameters, pool_key: PoolKey, caller: Felt) -> Self {
         SwapData {
             params,
@@ -79,7 +504,134 @@ impl SwapData {
             caller,
         }
     }
+
+    /// The `(input, output)` tokens of the swap.
+    ///
+    /// `params.is_token1` names the token `params.amount` is in, which is the output token
+    /// when the amount is negative (exact output).
+    pub fn tokens(&self) -> (Felt, Felt) {
+        if self.params.is_token1 != self.params.amount.sign {
+            (self.pool_key.token1, self.pool_key.token0)
+        } else {
+            (self.pool_key.token0, self.pool_key.token1)
+        }
+    }
+}
+/// Fluent builder for [`SwapData`]
+#[derive(Debug, Clone, Default)]
+pub struct SwapDataBuilder {
+    amount: Option<u128>,
+    sign: bool,
+    pool: Option<(Felt, Felt)>,
+    is_token1: bool,
+    sqrt_ratio_limit: Option<Uint256>,
+    skip_ahead: u32,
+    extension: Option<(Felt, u128, u128)>,
+    caller: Option<Felt>,
+}
+
+impl SwapDataBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Amount to swap in the token's smallest unit (required)
+    pub fn amount(mut self, amount: u128) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Sign of the amount (defaults to false)
+    pub fn sign(mut self, sign: bool) -> Self {
+        self.sign = sign;
+        self
+    }
+
+    /// Pool tokens in any order; they are sorted and the fee and tick spacing derived as in
+    /// [`PoolKey::new`] (required)
+    pub fn pool(mut self, token0: Felt, token1: Felt) -> Self {
+        self.pool = Some((token0, token1));
+        self
+    }
+
+    /// Whether the amount is in the second token passed to [`pool`](Self::pool) rather than
+    /// the first, i.e. the input token of an exact-input swap (defaults to false)
+    pub fn is_token1(mut self, is_token1: bool) -> Self {
+        self.is_token1 = is_token1;
+        self
+    }
+
+    /// Price limit for the swap (defaults to the limit used by [`SwapParameters::new`])
+    pub fn sqrt_ratio_limit(mut self, sqrt_ratio_limit: impl Into<Uint256>) -> Self {
+        self.sqrt_ratio_limit = Some(sqrt_ratio_limit.into());
+        self
+    }
+
+    /// Number of uninitialized tick bitmap words Ekubo may skip per step (defaults to 0).
+    ///
+    /// Higher values save gas on large swaps through sparse liquidity.
+    pub fn skip_ahead(mut self, skip_ahead: u32) -> Self {
+        self.skip_ahead = skip_ahead;
+        self
+    }
+
+    /// Extension contract of the pool and the pool's fee and tick spacing, see
+    /// [`PoolKey::with_extension`] (defaults to no extension and the fee tier of
+    /// [`PoolKey::new`])
+    pub fn extension(mut self, extension: Felt, fee: u128, tick_spacing: u128) -> Self {
+        self.extension = Some((extension, fee, tick_spacing));
+        self
+    }
+
+    /// Address executing the swap (required)
+    pub fn caller(mut self, caller: Felt) -> Self {
+        self.caller = Some(caller);
+        self
+    }
+
+    /// Validate the required fields and build the swap data
+    pub fn build(self) -> Result<SwapData, AutoSwapprError> {
+        let amount = self.amount.ok_or_else(|| AutoSwapprError::InvalidInput {
+            details: "Swap amount is required".to_string(),
+        })?;
+        if amount == 0 {
+            return Err(AutoSwapprError::ZeroAmount);
+        }
+
+        let (token0, token1) = self.pool.ok_or_else(|| AutoSwapprError::InvalidInput {
+            details: "Pool tokens are required".to_string(),
+        })?;
+
+        let caller = self.caller.ok_or_else(|| AutoSwapprError::InvalidInput {
+            details: "Caller is required".to_string(),
+        })?;
+
+        let pool_key = match self.extension {
+            Some((extension, fee, tick_spacing)) => {
+                PoolKey::with_extension(token0, token1, extension, fee, tick_spacing)
+            }
+            None => PoolKey::new(token0, token1),
+        };
+        let amount_token = if self.is_token1 { token1 } else { token0 };
+
+        let mut params = SwapParameters::new(
+            I129::new(amount, self.sign),
+            amount_token == pool_key.token1,
+        );
+        params.skip_ahead = self.skip_ahead;
+
+        let mut swap_data = SwapData::new(params, pool_key, caller);
+        // Selling token1 moves the price up, so the default limit is the other extreme
+        swap_data.params.sqrt_ratio_limit = match self.sqrt_ratio_limit {
+            Some(sqrt_ratio_limit) => sqrt_ratio_limit,
+            None if swap_data.tokens().0 == swap_data.pool_key.token1 => MAX_SQRT_RATIO,
+            None => swap_data.params.sqrt_ratio_limit,
+        };
+
+        Ok(swap_data)
+    }
 }
+
 /// Route structure for AVNU swaps
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Route {
@@ -117,6 +669,23 @@ pub struct SwapResult {
     pub delta: Delta,
 }
 
+impl SwapResult {
+    /// Signed `(input, output)` amounts of the swap, from the pool's point of view.
+    ///
+    /// Positive amounts flow into the pool and negative ones leave it, so a normal swap
+    /// yields a positive input and a negative output. Magnitudes beyond `i128` saturate.
+    pub fn net_amounts(&self, is_token1: bool) -> (i128, i128) {
+        let amount0 = self.delta.amount0.to_i128_saturating();
+        let amount1 = self.delta.amount1.to_i128_saturating();
+
+        if is_token1 {
+            (amount1, amount0)
+        } else {
+            (amount0, amount1)
+        }
+    }
+}
+
 /// Delta structure for swap results
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Delta {
@@ -159,6 +728,79 @@ pub struct ContractInfo {
     pub percentage_fee: u16,
 }
 
+impl ContractInfo {
+    /// Serialize the contract information as a JSON string
+    pub fn to_json(&self) -> Result<String, AutoSwapprError> {
+        to_json_string(self)
+    }
+}
+
+/// Token information as reported to integrators
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenInfo {
+    pub address: String,
+    pub symbol: String,
+    pub name: String,
+    pub decimals: u8,
+}
+
+impl TokenInfo {
+    /// Serialize the token information as a JSON string
+    pub fn to_json(&self) -> Result<String, AutoSwapprError> {
+        to_json_string(self)
+    }
+}
+
+/// Snapshot of a client's configuration and on-chain contract parameters
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClientSnapshot {
+    pub account_address: String,
+    pub contract_address: String,
+    pub contract_parameters: ContractInfo,
+}
+
+impl ClientSnapshot {
+    /// Serialize the snapshot as a JSON string
+    pub fn to_json(&self) -> Result<String, AutoSwapprError> {
+        to_json_string(self)
+    }
+}
+
+/// Readiness of an RPC endpoint, as reported by [`AutoSwapprClient::health_check`]
+///
+/// [`AutoSwapprClient::health_check`]: crate::AutoSwapprClient::health_check
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct HealthStatus {
+    pub block_number: u64,
+    /// Chain id in hex
+    pub chain_id: String,
+    /// Round-trip time of the health check requests
+    pub latency_ms: u64,
+}
+
+/// USD valuation of an account's token holdings
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PortfolioValue {
+    pub total_usd: f64,
+    /// USD value per token, in the order requested
+    pub breakdown: Vec<(String, f64)>,
+    /// Tokens left out of the total, with the reason
+    pub warnings: Vec<String>,
+}
+
+impl PortfolioValue {
+    /// Serialize the portfolio value as a JSON string
+    pub fn to_json(&self) -> Result<String, AutoSwapprError> {
+        to_json_string(self)
+    }
+}
+
+fn to_json_string<T: Serialize>(value: &T) -> Result<String, AutoSwapprError> {
+    serde_json::to_string(value).map_err(|e| AutoSwapprError::Other {
+        message: format!("Failed to serialize to JSON: {}", e),
+    })
+}
+
 /// Pool configuration for different token pairs
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoolConfig {
@@ -173,17 +815,55 @@ pub struct PoolConfig {
 /// Swap options for configuring the swap
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SwapOptions {
-    pub amount: String,                   // Amount in wei (with decimals)
-    pub is_token1: Option<bool>,          // Whether input token is token1 (defaults to false)
-    pub skip_ahead: Option<u32>,          // Skip ahead parameter (defaults to 0)
+    pub amount: String, // Amount in base units, or whole tokens if amount_is_human
+    pub is_token1: Option<bool>, // Ignored: derived from the token addresses' ordering
+    pub skip_ahead: Option<u32>, // Skip ahead parameter (defaults to 0)
     pub sqrt_ratio_limit: Option<String>, // Custom sqrt ratio limit
+    #[serde(default)]
+    pub amount_is_human: bool, // Whether amount is in whole tokens, e.g. "1.5"
+    #[serde(default)]
+    pub decimals: Option<u8>, // Input token decimals, required when amount_is_human
+    #[serde(default)]
+    pub extension: Option<String>, // Hex address of the pool's extension contract
+    #[serde(default)]
+    pub fee: Option<u128>, // Fee of the extension pool, required with extension
+    #[serde(default)]
+    pub tick_spacing: Option<u128>, // Tick spacing of the extension pool, required with extension
+}
+
+impl SwapOptions {
+    /// Parse `amount` into base units.
+    ///
+    /// Base-unit amounts must be integers. Human amounts may have up to `decimals`
+    /// fractional digits and are scaled without going through floating point.
+    pub fn parse_amount(&self) -> Result<Uint256, AutoSwapprError> {
+        if !self.amount_is_human {
+            return Uint256::from_dec_str(&self.amount);
+        }
+
+        let decimals = self.decimals.ok_or_else(|| AutoSwapprError::InvalidInput {
+            details: "decimals are required for a human-readable amount".to_string(),
+        })?;
+
+        crate::format::from_display_u256(&self.amount, decimals)
+    }
 }
 
 impl PoolKey {
-    pub fn new(token0: Felt, token1: Felt) -> Self {
-        let (fee, tick_spacing) = if token1 == *USDC {
+    /// Pool key for a pair of tokens given in any order.
+    ///
+    /// Ekubo requires `token0 < token1`, so the tokens are sorted by address. Pairs with USDC
+    /// or USDT use that pool's fee tier; any other pair gets a zero fee and tick spacing.
+    pub fn new(token_a: Felt, token_b: Felt) -> Self {
+        let (token0, token1) = if token_a <= token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        let (fee, tick_spacing) = if token0 == *USDC || token1 == *USDC {
             (170141183460469235273462165868118016, 1000)
-        } else if token1 == *USDT {
+        } else if token0 == *USDT || token1 == *USDT {
             (3402823669209384634633746074317682114, 19802)
         } else {
             (0, 0)
@@ -197,6 +877,43 @@ impl PoolKey {
             extension: Felt::ZERO,
         }
     }
+
+    /// Pool key for an Ekubo pool with an extension contract (oracle pools, TWAMM, ...) at
+    /// `extension`, with tokens in any order.
+    ///
+    /// Extension pools pick their own fee tier, so `fee` and `tick_spacing` are taken as given
+    /// rather than derived as in [`new`](Self::new).
+    pub fn with_extension(
+        token_a: Felt,
+        token_b: Felt,
+        extension: Felt,
+        fee: u128,
+        tick_spacing: u128,
+    ) -> Self {
+        PoolKey {
+            fee,
+            tick_spacing,
+            extension,
+            ..PoolKey::new(token_a, token_b)
+        }
+    }
+
+    /// Pool key for swapping `token_in` into `token_out`, and whether `token_in` ended up as
+    /// the pool's token1
+    pub fn for_swap(token_in: Felt, token_out: Felt) -> (Self, bool) {
+        (PoolKey::new(token_in, token_out), token_in > token_out)
+    }
+}
+
+/// Final state of a submitted transaction
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Included and buried under the requested number of confirmations
+    Confirmed { block_number: u64 },
+    /// Was included, then disappeared from the chain while waiting for confirmations
+    Reorged,
+    /// Was included but its execution reverted, with the revert reason
+    Reverted(String),
 }
 
 #[derive(Debug, Serialize)]
@@ -211,6 +928,28 @@ pub struct ErrorResponse {
     pub message: String,
 }
 
+// Let axum handlers return swap results directly; axum isn't built for wasm32
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+mod server {
+    use super::{ErrorResponse, SuccessResponse};
+    use axum::{
+        Json,
+        response::{IntoResponse, Response},
+    };
+
+    impl IntoResponse for SuccessResponse {
+        fn into_response(self) -> Response {
+            Json(self).into_response()
+        }
+    }
+
+    impl IntoResponse for ErrorResponse {
+        fn into_response(self) -> Response {
+            Json(self).into_response()
+        }
+    }
+}
+
 /// Error types for the AutoSwappr SDK
 #[derive(Error, Debug)]
 pub enum AutoSwapprError {
@@ -237,3 +976,380 @@ pub enum AutoSwapprError {
     #[error("{message}")]
     Other { message: String },
 }
+
+// Lets infallible conversions such as `Felt` into `Address` stand in for fallible ones
+impl From<std::convert::Infallible> for AutoSwapprError {
+    fn from(never: std::convert::Infallible) -> Self {
+        match never {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn set_env(prefix: &str, vars: &[(&str, &str)]) {
+        for (name, value) in vars {
+            // SAFETY: environment access is serialized through ENV_LOCK
+            unsafe { std::env::set_var(format!("{}_{}", prefix, name), value) };
+        }
+    }
+
+    #[test]
+    fn test_config_from_env_with_prefix() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        set_env(
+            "AUTOSWAP_TEST_A",
+            &[
+                (
+                    "RPC_URL",
+                    "https://starknet-mainnet.public.blastapi.io/rpc/v0_9",
+                ),
+                ("PRIVATE_KEY", "0x1234"),
+                ("ACCOUNT_ADDRESS", "0x5678"),
+                (
+                    "CONTRACT_ADDRESS",
+                    "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b",
+                ),
+            ],
+        );
+
+        let config = AutoSwapprConfig::from_env_with_prefix("AUTOSWAP_TEST_A").unwrap();
+        assert_eq!(
+            config.rpc_url,
+            "https://starknet-mainnet.public.blastapi.io/rpc/v0_9"
+        );
+        assert_eq!(config.private_key, "0x1234");
+        assert_eq!(config.account_address, "0x5678");
+        assert_eq!(
+            config.contract_address,
+            "0x05582ad635c43b4c14dbfa53cbde0df32266164a0d1b36e5b510e5b34aeb364b"
+        );
+    }
+
+    #[test]
+    fn test_uint256_from_string_hex() {
+        assert_eq!(
+            Uint256::from_string("0x1f4").unwrap(),
+            Uint256::from_u128(500)
+        );
+        assert_eq!(
+            Uint256::from_string("0x2ffffffffffffffffffffffffffffffff").unwrap(),
+            Uint256 {
+                low: u128::MAX,
+                high: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_uint256_from_string_decimal_above_u128() {
+        // u128::MAX + 1 = 2^128
+        assert_eq!(
+            Uint256::from_string("340282366920938463463374607431768211456").unwrap(),
+            Uint256 { low: 0, high: 1 }
+        );
+    }
+
+    #[test]
+    fn test_uint256_from_string_rejects_malformed_input() {
+        for value in [
+            "",
+            "0x",
+            "0xfg",
+            "12a",
+            format!("0x{}", "1".repeat(65)).as_str(),
+        ] {
+            assert!(
+                matches!(
+                    Uint256::from_string(value),
+                    Err(AutoSwapprError::InvalidInput { .. })
+                ),
+                "{value} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_swap_data_builder() {
+        let swap_data = SwapData::builder()
+            .amount(1_000_000)
+            .pool(*crate::STRK, *USDC)
+            .sqrt_ratio_limit(U256::from(42u128))
+            .skip_ahead(5)
+            .caller(Felt::from_hex("0x456").unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(swap_data.params.amount.mag, 1_000_000);
+        assert!(!swap_data.params.amount.sign);
+        assert!(!swap_data.params.is_token1);
+        assert_eq!(swap_data.params.sqrt_ratio_limit, Uint256::from(42u128));
+        assert_eq!(swap_data.params.skip_ahead, 5);
+        assert_eq!(swap_data.pool_key.token0, *crate::STRK);
+        assert_eq!(swap_data.pool_key.token1, *USDC);
+        assert_eq!(swap_data.pool_key.tick_spacing, 1000);
+        assert_eq!(swap_data.caller, Felt::from_hex("0x456").unwrap());
+    }
+
+    #[test]
+    fn test_pool_key_sorts_tokens() {
+        let (forward, strk_is_token1) = PoolKey::for_swap(*crate::STRK, *USDC);
+        let (reverse, usdc_is_token1) = PoolKey::for_swap(*USDC, *crate::STRK);
+
+        assert_eq!(forward, reverse);
+        assert_eq!(forward, PoolKey::new(*USDC, *crate::STRK));
+        assert_eq!(forward.token0, *crate::STRK);
+        assert_eq!(forward.token1, *USDC);
+        assert_eq!(forward.tick_spacing, 1000);
+        assert!(!strk_is_token1);
+        assert!(usdc_is_token1);
+    }
+
+    #[test]
+    fn test_swap_data_builder_reversed_pool() {
+        let build = |token_in, token_out| {
+            SwapData::builder()
+                .amount(1_000_000)
+                .pool(token_in, token_out)
+                .caller(Felt::from_hex("0x456").unwrap())
+                .build()
+                .unwrap()
+        };
+        let forward = build(*crate::STRK, *USDC);
+        let reverse = build(*USDC, *crate::STRK);
+
+        assert_eq!(forward.pool_key, reverse.pool_key);
+        assert!(!forward.params.is_token1);
+        assert!(reverse.params.is_token1);
+        assert_eq!(forward.tokens(), (*crate::STRK, *USDC));
+        assert_eq!(reverse.tokens(), (*USDC, *crate::STRK));
+        // Selling token1 pushes the price up, so the default limit flips to the maximum
+        assert_eq!(reverse.params.sqrt_ratio_limit, MAX_SQRT_RATIO);
+    }
+
+    #[test]
+    fn test_swap_data_builder_missing_caller() {
+        let result = SwapData::builder()
+            .amount(1_000_000)
+            .pool(*crate::STRK, *USDC)
+            .build();
+
+        match result {
+            Err(AutoSwapprError::InvalidInput { details }) => assert!(details.contains("Caller")),
+            other => panic!("expected missing caller error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_config_from_env_missing_variable() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        set_env(
+            "AUTOSWAP_TEST_B",
+            &[
+                (
+                    "RPC_URL",
+                    "https://starknet-mainnet.public.blastapi.io/rpc/v0_9",
+                ),
+                ("ACCOUNT_ADDRESS", "0x5678"),
+                ("CONTRACT_ADDRESS", "0x9abc"),
+            ],
+        );
+
+        let result = AutoSwapprConfig::from_env_with_prefix("AUTOSWAP_TEST_B");
+        match result {
+            Err(AutoSwapprError::InvalidInput { details }) => {
+                assert!(details.contains("AUTOSWAP_TEST_B_PRIVATE_KEY"))
+            }
+            other => panic!("expected missing variable error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_config_from_env_rejects_address_above_field_prime() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        set_env(
+            "AUTOSWAP_TEST_C",
+            &[
+                (
+                    "RPC_URL",
+                    "https://starknet-mainnet.public.blastapi.io/rpc/v0_9",
+                ),
+                ("PRIVATE_KEY", "0x1234"),
+                ("ACCOUNT_ADDRESS", "0x5678"),
+                (
+                    "CONTRACT_ADDRESS",
+                    "0x0800000000000011000000000000000000000000000000000000000000000001",
+                ),
+            ],
+        );
+
+        let result = AutoSwapprConfig::from_env_with_prefix("AUTOSWAP_TEST_C");
+        match result {
+            Err(AutoSwapprError::InvalidInput { details }) => {
+                assert!(details.contains("not below the field prime"))
+            }
+            other => panic!("expected invalid address error, got {:?}", other),
+        }
+    }
+
+    fn swap_options(amount: &str, amount_is_human: bool, decimals: Option<u8>) -> SwapOptions {
+        SwapOptions {
+            amount: amount.to_string(),
+            is_token1: None,
+            skip_ahead: None,
+            sqrt_ratio_limit: None,
+            amount_is_human,
+            decimals,
+            extension: None,
+            fee: None,
+            tick_spacing: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_base_unit_amount() {
+        let options = swap_options("1000000000000000000", false, None);
+        assert_eq!(
+            options.parse_amount().unwrap(),
+            Uint256::from_u128(1_000_000_000_000_000_000)
+        );
+
+        // 2^128 needs the high limb
+        let options = swap_options("340282366920938463463374607431768211456", false, None);
+        assert_eq!(options.parse_amount().unwrap(), Uint256 { low: 0, high: 1 });
+
+        assert!(swap_options("1.5", false, None).parse_amount().is_err());
+        assert!(swap_options("", false, None).parse_amount().is_err());
+    }
+
+    #[test]
+    fn test_parse_human_amount() {
+        let options = swap_options("1.5", true, Some(18));
+        assert_eq!(
+            options.parse_amount().unwrap(),
+            Uint256::from_u128(1_500_000_000_000_000_000)
+        );
+
+        assert_eq!(
+            swap_options("2", true, Some(6)).parse_amount().unwrap(),
+            Uint256::from_u128(2_000_000)
+        );
+        assert!(swap_options("1.5", true, None).parse_amount().is_err());
+        assert!(
+            swap_options("0.1234567", true, Some(6))
+                .parse_amount()
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[tokio::test]
+    async fn test_error_response_into_json_response() {
+        use axum::response::IntoResponse;
+
+        let response = ErrorResponse {
+            success: false,
+            message: "FAILED TO SWAP".to_string(),
+        }
+        .into_response();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["message"], "FAILED TO SWAP");
+    }
+
+    #[test]
+    fn test_uint256_mul_div_beyond_u128() {
+        // 2^129 * 7_500 / 10_000 = 2^128 + 2^127
+        let amount = Uint256 { low: 0, high: 2 };
+        assert_eq!(
+            amount.mul_div(Uint256::from(7_500u128), Uint256::from(10_000u128)),
+            Some(Uint256 {
+                low: 1 << 127,
+                high: 1
+            })
+        );
+
+        // Rounds down
+        assert_eq!(
+            Uint256::from(10u128).mul_div(Uint256::from(1u128), Uint256::from(3u128)),
+            Some(Uint256::from(3u128))
+        );
+
+        // The intermediate product exceeds 256 bits
+        let max = Uint256 {
+            low: u128::MAX,
+            high: u128::MAX,
+        };
+        assert_eq!(max.mul_div(max, max), Some(max));
+        assert_eq!(
+            max.mul_div(Uint256::from(2u128), Uint256::from(1u128)),
+            None
+        );
+        assert_eq!(max.mul_div(max, Uint256::default()), None);
+    }
+
+    #[test]
+    fn test_uint256_add_sub() {
+        let a = Uint256 {
+            low: u128::MAX,
+            high: 0,
+        };
+        let sum = a.checked_add(Uint256::from(1u128)).unwrap();
+        assert_eq!(sum, Uint256 { low: 0, high: 1 });
+        assert_eq!(sum.checked_sub(Uint256::from(1u128)), Some(a));
+        assert_eq!(Uint256::from(1u128).checked_sub(sum), None);
+        assert!(sum > a);
+    }
+
+    #[test]
+    fn test_uint256_conversions() {
+        let felt = Felt::MAX;
+        let value = Uint256::from(felt);
+        assert_eq!(Felt::try_from(value).unwrap(), felt);
+        assert!(Felt::try_from(value.checked_add(Uint256::from(1u128)).unwrap()).is_err());
+
+        let u256 = U256::from_words(7, 9);
+        assert_eq!(U256::from(Uint256::from(u256)), u256);
+        for (low, high) in [(0, 0), (u128::MAX, 0), (0, 1), (u128::MAX, u128::MAX)] {
+            let uint256 = Uint256 { low, high };
+            let u256 = U256::from(uint256);
+            assert_eq!((u256.low(), u256.high()), (low, high));
+            assert_eq!(Uint256::from(u256), uint256);
+        }
+
+        assert_eq!(u128::try_from(Uint256::from(42u128)).unwrap(), 42);
+        assert!(u128::try_from(Uint256 { low: 0, high: 1 }).is_err());
+    }
+
+    #[test]
+    fn test_net_amounts_token0_in() {
+        let result = SwapResult {
+            delta: Delta {
+                amount0: I129::new(1_000, false),
+                amount1: I129::new(2_500, true),
+            },
+        };
+
+        assert_eq!(result.net_amounts(false), (1_000, -2_500));
+    }
+
+    #[test]
+    fn test_net_amounts_token1_in() {
+        let result = SwapResult {
+            delta: Delta {
+                amount0: I129::new(u128::MAX, true),
+                amount1: I129::new(700, false),
+            },
+        };
+
+        assert_eq!(result.net_amounts(true), (700, i128::MIN));
+    }
+}